@@ -1,10 +1,196 @@
+#[derive(Default)]
+pub struct EventsLengths {
+    pub dvs: usize,
+    pub trigger: usize,
+}
+
+/// DvsColumns stores DVS events as a structure of arrays.
+///
+/// Columns are easier to process with vectorised code (and to export to columnar formats) than packed events.
+#[derive(Debug, Default, Clone)]
+pub struct DvsColumns {
+    pub t: Vec<u64>,
+    pub x: Vec<u16>,
+    pub y: Vec<u16>,
+    pub on: Vec<bool>,
+}
+
+impl DvsColumns {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            t: Vec::with_capacity(capacity),
+            x: Vec::with_capacity(capacity),
+            y: Vec::with_capacity(capacity),
+            on: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.t.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.t.is_empty()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.t.reserve(additional);
+        self.x.reserve(additional);
+        self.y.reserve(additional);
+        self.on.reserve(additional);
+    }
+
+    pub fn clear(&mut self) {
+        self.t.clear();
+        self.x.clear();
+        self.y.clear();
+        self.on.clear();
+    }
+
+    pub fn push(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) {
+        self.t.push(event.t);
+        self.x.push(event.x);
+        self.y.push(event.y);
+        self.on.push(matches!(
+            event.polarity,
+            neuromorphic_types::DvsPolarity::On
+        ));
+    }
+}
+
+/// TriggerColumns stores trigger events as a structure of arrays.
+#[derive(Debug, Default, Clone)]
+pub struct TriggerColumns {
+    pub t: Vec<u64>,
+    pub id: Vec<u8>,
+    pub rising: Vec<bool>,
+}
+
+impl TriggerColumns {
+    pub fn len(&self) -> usize {
+        self.t.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.t.is_empty()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.t.reserve(additional);
+        self.id.reserve(additional);
+        self.rising.reserve(additional);
+    }
+
+    pub fn clear(&mut self) {
+        self.t.clear();
+        self.id.clear();
+        self.rising.clear();
+    }
+
+    pub fn push(&mut self, event: neuromorphic_types::TriggerEvent<u64, u8>) {
+        self.t.push(event.t);
+        self.id.push(event.id);
+        self.rising.push(matches!(
+            event.polarity,
+            neuromorphic_types::TriggerPolarity::Rising
+        ));
+    }
+}
+
+/// Schema of the record batches returned by DvsColumns::into_record_batch.
+///
+/// polarity is 0 for OFF events and 1 for ON events (the values of neuromorphic_types::DvsPolarity).
+#[cfg(feature = "arrow")]
+pub fn dvs_events_schema() -> arrow::datatypes::SchemaRef {
+    std::sync::Arc::new(arrow::datatypes::Schema::new(vec![
+        arrow::datatypes::Field::new("t", arrow::datatypes::DataType::UInt64, false),
+        arrow::datatypes::Field::new("x", arrow::datatypes::DataType::UInt16, false),
+        arrow::datatypes::Field::new("y", arrow::datatypes::DataType::UInt16, false),
+        arrow::datatypes::Field::new("polarity", arrow::datatypes::DataType::UInt8, false),
+    ]))
+}
+
+/// Schema of the record batches returned by TriggerColumns::into_record_batch.
+///
+/// polarity is 0 for falling edges and 1 for rising edges (the values of neuromorphic_types::TriggerPolarity).
+#[cfg(feature = "arrow")]
+pub fn trigger_events_schema() -> arrow::datatypes::SchemaRef {
+    std::sync::Arc::new(arrow::datatypes::Schema::new(vec![
+        arrow::datatypes::Field::new("t", arrow::datatypes::DataType::UInt64, false),
+        arrow::datatypes::Field::new("id", arrow::datatypes::DataType::UInt8, false),
+        arrow::datatypes::Field::new("polarity", arrow::datatypes::DataType::UInt8, false),
+    ]))
+}
+
+#[cfg(feature = "arrow")]
+impl DvsColumns {
+    /// into_record_batch moves the columns into a record batch (t, x, and y are not copied).
+    pub fn into_record_batch(
+        self,
+    ) -> Result<arrow::record_batch::RecordBatch, arrow::error::ArrowError> {
+        arrow::record_batch::RecordBatch::try_new(
+            dvs_events_schema(),
+            vec![
+                std::sync::Arc::new(arrow::array::UInt64Array::from(self.t)),
+                std::sync::Arc::new(arrow::array::UInt16Array::from(self.x)),
+                std::sync::Arc::new(arrow::array::UInt16Array::from(self.y)),
+                std::sync::Arc::new(arrow::array::UInt8Array::from_iter_values(
+                    self.on.into_iter().map(u8::from),
+                )),
+            ],
+        )
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl TriggerColumns {
+    pub fn into_record_batch(
+        self,
+    ) -> Result<arrow::record_batch::RecordBatch, arrow::error::ArrowError> {
+        arrow::record_batch::RecordBatch::try_new(
+            trigger_events_schema(),
+            vec![
+                std::sync::Arc::new(arrow::array::UInt64Array::from(self.t)),
+                std::sync::Arc::new(arrow::array::UInt8Array::from(self.id)),
+                std::sync::Arc::new(arrow::array::UInt8Array::from_iter_values(
+                    self.rising.into_iter().map(u8::from),
+                )),
+            ],
+        )
+    }
+}
+
+#[cfg(feature = "arrow")]
+pub struct RecordBatches {
+    pub dvs_events: arrow::record_batch::RecordBatch,
+    pub trigger_events: arrow::record_batch::RecordBatch,
+}
+
 macro_rules! register {
     ($($module:ident),+) => {
         $(
             pub mod $module;
         )+
 
+        $(
+            impl $module::Adapter {
+                /// convert_into_columns appends DVS events to the columns, reserving memory once.
+                pub fn convert_into_columns<HandleTriggerEvent>(
+                    &mut self,
+                    slice: &[u8],
+                    columns: &mut DvsColumns,
+                    handle_trigger_event: HandleTriggerEvent,
+                ) where
+                    HandleTriggerEvent: FnMut(neuromorphic_types::TriggerEvent<u64, u8>),
+                {
+                    columns.reserve(self.events_lengths(slice).dvs);
+                    self.convert(slice, |dvs_event| columns.push(dvs_event), handle_trigger_event);
+                }
+            }
+        )+
+
         paste::paste! {
+            #[derive(Clone)]
             pub enum Adapter {
                 $(
                     [<$module:camel>]($module::Adapter),
@@ -18,8 +204,87 @@ macro_rules! register {
                     }
                 }
             )+
+
+            impl Adapter {
+                pub fn events_lengths(&self, slice: &[u8]) -> EventsLengths {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](adapter) => adapter.events_lengths(slice),
+                        )+
+                    }
+                }
+
+                pub fn current_t(&self) -> u64 {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](adapter) => adapter.current_t(),
+                        )+
+                    }
+                }
+
+                pub fn convert<HandleDvsEvent, HandleTriggerEvent>(
+                    &mut self,
+                    slice: &[u8],
+                    handle_dvs_event: HandleDvsEvent,
+                    handle_trigger_event: HandleTriggerEvent,
+                ) where
+                    HandleDvsEvent: FnMut(neuromorphic_types::DvsEvent<u64, u16, u16>),
+                    HandleTriggerEvent: FnMut(neuromorphic_types::TriggerEvent<u64, u8>),
+                {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](adapter) => {
+                                adapter.convert(slice, handle_dvs_event, handle_trigger_event)
+                            }
+                        )+
+                    }
+                }
+
+                pub fn convert_into_columns<HandleTriggerEvent>(
+                    &mut self,
+                    slice: &[u8],
+                    columns: &mut DvsColumns,
+                    handle_trigger_event: HandleTriggerEvent,
+                ) where
+                    HandleTriggerEvent: FnMut(neuromorphic_types::TriggerEvent<u64, u8>),
+                {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](adapter) => {
+                                adapter.convert_into_columns(slice, columns, handle_trigger_event)
+                            }
+                        )+
+                    }
+                }
+
+                /// convert_into_record_batches decodes the slice into a DVS batch and a trigger batch.
+                #[cfg(feature = "arrow")]
+                pub fn convert_into_record_batches(
+                    &mut self,
+                    slice: &[u8],
+                ) -> Result<RecordBatches, arrow::error::ArrowError> {
+                    let mut dvs_columns = DvsColumns::default();
+                    let mut trigger_columns = TriggerColumns::default();
+                    trigger_columns.reserve(self.events_lengths(slice).trigger);
+                    self.convert_into_columns(slice, &mut dvs_columns, |trigger_event| {
+                        trigger_columns.push(trigger_event)
+                    });
+                    Ok(RecordBatches {
+                        dvs_events: dvs_columns.into_record_batch()?,
+                        trigger_events: trigger_columns.into_record_batch()?,
+                    })
+                }
+
+                pub fn consume(&mut self, slice: &[u8]) {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](adapter) => adapter.consume(slice),
+                        )+
+                    }
+                }
+            }
         }
     }
 }
 
-register! { evt3 }
+register! { evt3, evt2, evt21 }
@@ -0,0 +1,145 @@
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Error {}
+
+pub use super::DvsColumns;
+pub use super::EventsLengths;
+pub use super::TriggerColumns;
+
+#[derive(Clone)]
+pub struct Adapter {
+    t: u64,
+    overflows: u32,
+    previous_msb_t: u32,
+    width: u16,
+    height: u16,
+}
+
+impl Adapter {
+    pub fn from_dimensions(width: u16, height: u16) -> Self {
+        Self {
+            t: 0,
+            overflows: 0,
+            previous_msb_t: 0,
+            width,
+            height,
+        }
+    }
+
+    pub fn events_lengths(&self, slice: &[u8]) -> EventsLengths {
+        let mut lengths = EventsLengths::default();
+        for index in 0..slice.len() / 4 {
+            let word = u32::from_le_bytes([
+                slice[index * 4],
+                slice[index * 4 + 1],
+                slice[index * 4 + 2],
+                slice[index * 4 + 3],
+            ]);
+            match word >> 28 {
+                0b0000 | 0b0001 => {
+                    let x = ((word >> 11) & 0b11111111111) as u16;
+                    let y = (word & 0b11111111111) as u16;
+                    if x < self.width && y < self.height {
+                        lengths.dvs += 1;
+                    }
+                }
+                0b1010 => {
+                    lengths.trigger += 1;
+                }
+                _ => (),
+            }
+        }
+        lengths
+    }
+
+    pub fn current_t(&self) -> u64 {
+        self.t
+    }
+
+    pub fn convert<HandleDvsEvent, HandleTriggerEvent>(
+        &mut self,
+        slice: &[u8],
+        mut handle_dvs_event: HandleDvsEvent,
+        mut handle_trigger_event: HandleTriggerEvent,
+    ) where
+        HandleDvsEvent: FnMut(neuromorphic_types::DvsEvent<u64, u16, u16>),
+        HandleTriggerEvent: FnMut(neuromorphic_types::TriggerEvent<u64, u8>),
+    {
+        for index in 0..slice.len() / 4 {
+            let word = u32::from_le_bytes([
+                slice[index * 4],
+                slice[index * 4 + 1],
+                slice[index * 4 + 2],
+                slice[index * 4 + 3],
+            ]);
+            match word >> 28 {
+                0b0000 | 0b0001 => {
+                    self.update_lsb_t(word);
+                    let x = ((word >> 11) & 0b11111111111) as u16;
+                    let y = (word & 0b11111111111) as u16;
+                    if x < self.width && y < self.height {
+                        handle_dvs_event(neuromorphic_types::DvsEvent {
+                            t: self.t,
+                            x,
+                            y: self.height - 1 - y,
+                            polarity: if (word >> 28) == 0b0001 {
+                                neuromorphic_types::DvsPolarity::On
+                            } else {
+                                neuromorphic_types::DvsPolarity::Off
+                            },
+                        });
+                    }
+                }
+                0b1000 => self.update_msb_t(word),
+                0b1010 => {
+                    self.update_lsb_t(word);
+                    handle_trigger_event(neuromorphic_types::TriggerEvent {
+                        t: self.t,
+                        id: ((word >> 8) & 0b11111) as u8,
+                        polarity: if (word & 1) > 0 {
+                            neuromorphic_types::TriggerPolarity::Rising
+                        } else {
+                            neuromorphic_types::TriggerPolarity::Falling
+                        },
+                    });
+                }
+                _ => (),
+            }
+        }
+    }
+
+    pub fn consume(&mut self, slice: &[u8]) {
+        for index in 0..slice.len() / 4 {
+            let word = u32::from_le_bytes([
+                slice[index * 4],
+                slice[index * 4 + 1],
+                slice[index * 4 + 2],
+                slice[index * 4 + 3],
+            ]);
+            match word >> 28 {
+                0b0000 | 0b0001 | 0b1010 => self.update_lsb_t(word),
+                0b1000 => self.update_msb_t(word),
+                _ => (),
+            }
+        }
+    }
+
+    fn update_msb_t(&mut self, word: u32) {
+        let msb_t = word & 0xfffffff;
+        if msb_t < self.previous_msb_t && (self.previous_msb_t - msb_t) > (1 << 27) {
+            self.overflows += 1;
+        }
+        self.previous_msb_t = msb_t;
+        let t = ((self.previous_msb_t as u64) << 6) | ((self.overflows as u64) << 34);
+        if t >= self.t {
+            self.t = t;
+        }
+    }
+
+    fn update_lsb_t(&mut self, word: u32) {
+        let t = ((((word >> 22) & 0b111111) as u64) | ((self.previous_msb_t as u64) << 6))
+            | ((self.overflows as u64) << 34);
+        if t >= self.t {
+            self.t = t;
+        }
+    }
+}
@@ -0,0 +1,151 @@
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Error {}
+
+pub use super::DvsColumns;
+pub use super::EventsLengths;
+pub use super::TriggerColumns;
+
+#[derive(Clone)]
+pub struct Adapter {
+    t: u64,
+    overflows: u32,
+    previous_msb_t: u32,
+    width: u16,
+    height: u16,
+}
+
+impl Adapter {
+    pub fn from_dimensions(width: u16, height: u16) -> Self {
+        Self {
+            t: 0,
+            overflows: 0,
+            previous_msb_t: 0,
+            width,
+            height,
+        }
+    }
+
+    pub fn events_lengths(&self, slice: &[u8]) -> EventsLengths {
+        let mut lengths = EventsLengths::default();
+        for index in 0..slice.len() / 8 {
+            let word = u64::from_le_bytes(
+                slice[index * 8..(index + 1) * 8]
+                    .try_into()
+                    .expect("the slice has 8 bytes"),
+            );
+            match word >> 60 {
+                0b0000 | 0b0001 => {
+                    let x = ((word >> 43) & 0b11111111111) as u16;
+                    let y = ((word >> 32) & 0b11111111111) as u16;
+                    if x < self.width && y < self.height {
+                        lengths.dvs += ((word & 0xffffffff)
+                            & ((1u64 << std::cmp::min(32, self.width - x)) - 1))
+                            .count_ones() as usize;
+                    }
+                }
+                0b1010 => {
+                    lengths.trigger += 1;
+                }
+                _ => (),
+            }
+        }
+        lengths
+    }
+
+    pub fn current_t(&self) -> u64 {
+        self.t
+    }
+
+    pub fn convert<HandleDvsEvent, HandleTriggerEvent>(
+        &mut self,
+        slice: &[u8],
+        mut handle_dvs_event: HandleDvsEvent,
+        mut handle_trigger_event: HandleTriggerEvent,
+    ) where
+        HandleDvsEvent: FnMut(neuromorphic_types::DvsEvent<u64, u16, u16>),
+        HandleTriggerEvent: FnMut(neuromorphic_types::TriggerEvent<u64, u8>),
+    {
+        for index in 0..slice.len() / 8 {
+            let word = u64::from_le_bytes(
+                slice[index * 8..(index + 1) * 8]
+                    .try_into()
+                    .expect("the slice has 8 bytes"),
+            );
+            match word >> 60 {
+                0b0000 | 0b0001 => {
+                    self.update_lsb_t(word);
+                    let x = ((word >> 43) & 0b11111111111) as u16;
+                    let y = ((word >> 32) & 0b11111111111) as u16;
+                    if x < self.width && y < self.height {
+                        let polarity = if (word >> 60) == 0b0001 {
+                            neuromorphic_types::DvsPolarity::On
+                        } else {
+                            neuromorphic_types::DvsPolarity::Off
+                        };
+                        let mut set =
+                            (word & 0xffffffff) & ((1u64 << std::cmp::min(32, self.width - x)) - 1);
+                        // iterate over set bits only (trailing_zeros compiles to tzcnt / rbit + clz)
+                        while set != 0 {
+                            handle_dvs_event(neuromorphic_types::DvsEvent {
+                                t: self.t,
+                                x: x + set.trailing_zeros() as u16,
+                                y: self.height - 1 - y,
+                                polarity,
+                            });
+                            set &= set - 1;
+                        }
+                    }
+                }
+                0b1000 => self.update_msb_t(word),
+                0b1010 => {
+                    self.update_lsb_t(word);
+                    handle_trigger_event(neuromorphic_types::TriggerEvent {
+                        t: self.t,
+                        id: ((word >> 40) & 0b11111) as u8,
+                        polarity: if ((word >> 32) & 1) > 0 {
+                            neuromorphic_types::TriggerPolarity::Rising
+                        } else {
+                            neuromorphic_types::TriggerPolarity::Falling
+                        },
+                    });
+                }
+                _ => (),
+            }
+        }
+    }
+
+    pub fn consume(&mut self, slice: &[u8]) {
+        for index in 0..slice.len() / 8 {
+            let word = u64::from_le_bytes(
+                slice[index * 8..(index + 1) * 8]
+                    .try_into()
+                    .expect("the slice has 8 bytes"),
+            );
+            match word >> 60 {
+                0b0000 | 0b0001 | 0b1010 => self.update_lsb_t(word),
+                0b1000 => self.update_msb_t(word),
+                _ => (),
+            }
+        }
+    }
+
+    fn update_msb_t(&mut self, word: u64) {
+        let msb_t = ((word >> 32) & 0xfffffff) as u32;
+        if msb_t < self.previous_msb_t && (self.previous_msb_t - msb_t) > (1 << 27) {
+            self.overflows += 1;
+        }
+        self.previous_msb_t = msb_t;
+        let t = ((self.previous_msb_t as u64) << 6) | ((self.overflows as u64) << 34);
+        if t >= self.t {
+            self.t = t;
+        }
+    }
+
+    fn update_lsb_t(&mut self, word: u64) {
+        let t = (((word >> 54) & 0b111111) | ((self.previous_msb_t as u64) << 6))
+            | ((self.overflows as u64) << 34);
+        if t >= self.t {
+            self.t = t;
+        }
+    }
+}
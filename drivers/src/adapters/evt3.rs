@@ -1,6 +1,36 @@
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum Error {}
 
+/// State holds the decoder's context, which depends on all the previous words.
+///
+/// A state saved before a word can be restored to decode the rest of the stream
+/// independently (see Adapter::checkpoints).
+#[derive(Debug, Clone, Copy)]
+pub struct State {
+    pub t: u64,
+    pub overflows: u32,
+    pub previous_msb_t: u16,
+    pub previous_lsb_t: u16,
+    pub x: u16,
+    pub y: u16,
+    pub polarity: neuromorphic_types::DvsPolarity,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Checkpoint {
+    /// offset in bytes of a TIME_HIGH word (or 0 for the first checkpoint)
+    pub offset: usize,
+    /// state of the adapter before the word at offset
+    pub state: State,
+}
+
+#[cfg(feature = "rayon")]
+pub struct Events {
+    pub dvs_events: Vec<neuromorphic_types::DvsEvent<u64, u16, u16>>,
+    pub trigger_events: Vec<neuromorphic_types::TriggerEvent<u64, u8>>,
+}
+
+#[derive(Clone)]
 pub struct Adapter {
     t: u64,
     overflows: u32,
@@ -13,11 +43,9 @@ pub struct Adapter {
     polarity: neuromorphic_types::DvsPolarity,
 }
 
-#[derive(Default)]
-pub struct EventsLengths {
-    pub dvs: usize,
-    pub trigger: usize,
-}
+pub use super::DvsColumns;
+pub use super::EventsLengths;
+pub use super::TriggerColumns;
 
 impl Adapter {
     pub fn from_dimensions(width: u16, height: u16) -> Self {
@@ -34,6 +62,128 @@ impl Adapter {
         }
     }
 
+    pub fn state(&self) -> State {
+        State {
+            t: self.t,
+            overflows: self.overflows,
+            previous_msb_t: self.previous_msb_t,
+            previous_lsb_t: self.previous_lsb_t,
+            x: self.x,
+            y: self.y,
+            polarity: self.polarity,
+        }
+    }
+
+    pub fn restore(&mut self, state: State) {
+        self.t = state.t;
+        self.overflows = state.overflows;
+        self.previous_msb_t = state.previous_msb_t;
+        self.previous_lsb_t = state.previous_lsb_t;
+        self.x = state.x;
+        self.y = state.y;
+        self.polarity = state.polarity;
+    }
+
+    /// checkpoints splits the slice at TIME_HIGH words, at least minimum_spacing bytes apart.
+    ///
+    /// The first checkpoint is always at offset 0 and uses the adapter's current state.
+    /// Finding checkpoints requires a sequential pass over the slice, but this pass does not
+    /// expand vectors nor generate events and is much faster than convert.
+    /// The adapter is not modified.
+    pub fn checkpoints(&self, slice: &[u8], minimum_spacing: usize) -> Vec<Checkpoint> {
+        let mut adapter = self.clone();
+        let mut checkpoints = vec![Checkpoint {
+            offset: 0,
+            state: adapter.state(),
+        }];
+        let mut next_offset = minimum_spacing.max(2);
+        for index in 0..slice.len() / 2 {
+            let word = u16::from_le_bytes([slice[index * 2], slice[index * 2 + 1]]);
+            match word >> 12 {
+                0b0000 => {
+                    adapter.y = word & 0b11111111111;
+                    if adapter.y < adapter.height {
+                        adapter.y = adapter.height - 1 - adapter.y;
+                    }
+                }
+                0b0010 | 0b0011 => {
+                    adapter.x = word & 0b11111111111;
+                    adapter.polarity = if (word & (1 << 11)) > 0 {
+                        neuromorphic_types::DvsPolarity::On
+                    } else {
+                        neuromorphic_types::DvsPolarity::Off
+                    };
+                }
+                0b0100 if adapter.x < adapter.width && adapter.y < adapter.height => {
+                    adapter.x += 12;
+                }
+                0b0101 if adapter.x < adapter.width && adapter.y < adapter.height => {
+                    adapter.x += 8;
+                }
+                0b0110 => adapter.time_low(word),
+                0b1000 => {
+                    if index * 2 >= next_offset {
+                        checkpoints.push(Checkpoint {
+                            offset: index * 2,
+                            state: adapter.state(),
+                        });
+                        next_offset = index * 2 + minimum_spacing.max(2);
+                    }
+                    adapter.time_high(word);
+                }
+                _ => (),
+            }
+        }
+        checkpoints
+    }
+
+    /// convert_parallel decodes segments of the slice on rayon's thread pool and concatenates the events.
+    ///
+    /// Segments are delimited by checkpoints (see Adapter::checkpoints) at least segment_length bytes apart.
+    /// The result is identical to a sequential call of convert, including the adapter's final state.
+    #[cfg(feature = "rayon")]
+    pub fn convert_parallel(
+        &mut self,
+        slice: &[u8],
+        segment_length: usize,
+    ) -> Events {
+        use rayon::prelude::*;
+        let checkpoints = self.checkpoints(slice, segment_length);
+        let adapter = self.clone();
+        let segments: Vec<_> = checkpoints
+            .par_iter()
+            .enumerate()
+            .map(|(index, checkpoint)| {
+                let end = checkpoints
+                    .get(index + 1)
+                    .map_or(slice.len(), |next_checkpoint| next_checkpoint.offset);
+                let mut segment_adapter = adapter.clone();
+                segment_adapter.restore(checkpoint.state);
+                let mut dvs_events = Vec::new();
+                let mut trigger_events = Vec::new();
+                segment_adapter.convert(
+                    &slice[checkpoint.offset..end],
+                    |dvs_event| dvs_events.push(dvs_event),
+                    |trigger_event| trigger_events.push(trigger_event),
+                );
+                (dvs_events, trigger_events, segment_adapter.state())
+            })
+            .collect();
+        let mut dvs_events =
+            Vec::with_capacity(segments.iter().map(|segment| segment.0.len()).sum());
+        let mut trigger_events =
+            Vec::with_capacity(segments.iter().map(|segment| segment.1.len()).sum());
+        for (segment_dvs_events, segment_trigger_events, state) in segments {
+            dvs_events.extend_from_slice(&segment_dvs_events);
+            trigger_events.extend_from_slice(&segment_trigger_events);
+            self.restore(state);
+        }
+        Events {
+            dvs_events,
+            trigger_events,
+        }
+    }
+
     pub fn events_lengths(&self, slice: &[u8]) -> EventsLengths {
         let mut lengths = EventsLengths::default();
         let mut x = self.x;
@@ -126,72 +276,39 @@ impl Adapter {
                 }
                 0b0100 => {
                     if self.x < self.width && self.y < self.height {
-                        let set = word & ((1 << std::cmp::min(12, self.width - self.x)) - 1);
-                        for bit in 0..12 {
-                            if (set & (1 << bit)) > 0 {
-                                handle_dvs_event(neuromorphic_types::DvsEvent {
-                                    t: self.t,
-                                    x: self.x + bit,
-                                    y: self.y,
-                                    polarity: self.polarity,
-                                });
-                            }
+                        let mut set = word & ((1 << std::cmp::min(12, self.width - self.x)) - 1);
+                        // iterate over set bits only (trailing_zeros compiles to tzcnt / rbit + clz)
+                        while set != 0 {
+                            handle_dvs_event(neuromorphic_types::DvsEvent {
+                                t: self.t,
+                                x: self.x + set.trailing_zeros() as u16,
+                                y: self.y,
+                                polarity: self.polarity,
+                            });
+                            set &= set - 1;
                         }
                         self.x += 12;
                     }
                 }
                 0b0101 => {
                     if self.x < self.width && self.y < self.height {
-                        let set = word & ((1 << std::cmp::min(8, self.width - self.x)) - 1);
-                        for bit in 0..8 {
-                            if (set & (1 << bit)) > 0 {
-                                handle_dvs_event(neuromorphic_types::DvsEvent {
-                                    t: self.t,
-                                    x: self.x + bit,
-                                    y: self.y,
-                                    polarity: self.polarity,
-                                });
-                            }
+                        let mut set = word & ((1 << std::cmp::min(8, self.width - self.x)) - 1);
+                        // iterate over set bits only (trailing_zeros compiles to tzcnt / rbit + clz)
+                        while set != 0 {
+                            handle_dvs_event(neuromorphic_types::DvsEvent {
+                                t: self.t,
+                                x: self.x + set.trailing_zeros() as u16,
+                                y: self.y,
+                                polarity: self.polarity,
+                            });
+                            set &= set - 1;
                         }
                         self.x += 8;
                     }
                 }
-                0b0110 => {
-                    let lsb_t = word & 0b111111111111;
-                    if lsb_t != self.previous_lsb_t {
-                        self.previous_lsb_t = lsb_t;
-                        let t = (((self.previous_lsb_t as u32)
-                            | ((self.previous_msb_t as u32) << 12))
-                            as u64)
-                            | ((self.overflows as u64) << 24);
-                        if t >= self.t {
-                            self.t = t;
-                        }
-                    }
-                }
+                0b0110 => self.time_low(word),
                 0b0111 => (),
-                0b1000 => {
-                    let msb_t = word & 0b111111111111;
-                    if msb_t != self.previous_msb_t {
-                        if msb_t > self.previous_msb_t {
-                            if (msb_t - self.previous_msb_t) < (1 << 11) {
-                                self.previous_lsb_t = 0;
-                                self.previous_msb_t = msb_t;
-                            }
-                        } else if (self.previous_msb_t - msb_t) > (1 << 11) {
-                            self.overflows += 1;
-                            self.previous_lsb_t = 0;
-                            self.previous_msb_t = msb_t;
-                        }
-                        let t = (((self.previous_lsb_t as u32)
-                            | ((self.previous_msb_t as u32) << 12))
-                            as u64)
-                            | ((self.overflows as u64) << 24);
-                        if t >= self.t {
-                            self.t = t;
-                        }
-                    }
-                }
+                0b1000 => self.time_high(word),
                 0b1001 => (),
                 0b1010 => handle_trigger_event(neuromorphic_types::TriggerEvent {
                     t: self.t,
@@ -209,47 +326,292 @@ impl Adapter {
         }
     }
 
-    pub fn consume(&mut self, slice: &[u8]) {
+    /// convert_into writes DVS events directly into dvs_events and returns the number of events written.
+    ///
+    /// dvs_events must be at least events_lengths(slice).dvs long, otherwise this function panics.
+    /// Vectors are expanded by iterating over set bits and writing into a pre-sized sub-slice,
+    /// which avoids a callback and a bounds check per event.
+    pub fn convert_into<HandleTriggerEvent>(
+        &mut self,
+        slice: &[u8],
+        dvs_events: &mut [neuromorphic_types::DvsEvent<u64, u16, u16>],
+        mut handle_trigger_event: HandleTriggerEvent,
+    ) -> usize
+    where
+        HandleTriggerEvent: FnMut(neuromorphic_types::TriggerEvent<u64, u8>),
+    {
+        let mut length = 0;
         for index in 0..slice.len() / 2 {
             let word = u16::from_le_bytes([slice[index * 2], slice[index * 2 + 1]]);
             match word >> 12 {
-                0b0110 => {
-                    let lsb_t = word & 0b111111111111;
-                    if lsb_t != self.previous_lsb_t {
-                        self.previous_lsb_t = lsb_t;
-                        let t = (((self.previous_lsb_t as u32)
-                            | ((self.previous_msb_t as u32) << 12))
-                            as u64)
-                            | ((self.overflows as u64) << 24);
-                        if t >= self.t {
-                            self.t = t;
-                        }
+                0b0000 => {
+                    self.y = word & 0b11111111111;
+                    if self.y < self.height {
+                        self.y = self.height - 1 - self.y;
                     }
                 }
-                0b1000 => {
-                    let msb_t = word & 0b111111111111;
-                    if msb_t != self.previous_msb_t {
-                        if msb_t > self.previous_msb_t {
-                            if (msb_t - self.previous_msb_t) < (1 << 11) {
-                                self.previous_lsb_t = 0;
-                                self.previous_msb_t = msb_t;
-                            }
-                        } else if (self.previous_msb_t - msb_t) > (1 << 11) {
-                            self.overflows += 1;
-                            self.previous_lsb_t = 0;
-                            self.previous_msb_t = msb_t;
-                        }
-                        let t = (((self.previous_lsb_t as u32)
-                            | ((self.previous_msb_t as u32) << 12))
-                            as u64)
-                            | ((self.overflows as u64) << 24);
-                        if t >= self.t {
-                            self.t = t;
-                        }
+                0b0010 | 0b0011 => {
+                    self.x = word & 0b11111111111;
+                    self.polarity = if (word & (1 << 11)) > 0 {
+                        neuromorphic_types::DvsPolarity::On
+                    } else {
+                        neuromorphic_types::DvsPolarity::Off
+                    };
+                    if (word >> 12) == 0b0010 && self.x < self.width && self.y < self.height {
+                        dvs_events[length] = neuromorphic_types::DvsEvent {
+                            t: self.t,
+                            x: self.x,
+                            y: self.y,
+                            polarity: self.polarity,
+                        };
+                        length += 1;
+                    }
+                }
+                0b0100 | 0b0101 if self.x < self.width && self.y < self.height => {
+                    let bits = if (word >> 12) == 0b0100 { 12 } else { 8 };
+                    let mut set = word & ((1 << std::cmp::min(bits, self.width - self.x)) - 1);
+                    let count = set.count_ones() as usize;
+                    for dvs_event in dvs_events[length..length + count].iter_mut() {
+                        *dvs_event = neuromorphic_types::DvsEvent {
+                            t: self.t,
+                            x: self.x + set.trailing_zeros() as u16,
+                            y: self.y,
+                            polarity: self.polarity,
+                        };
+                        set &= set - 1;
                     }
+                    length += count;
+                    self.x += bits;
                 }
+                0b0110 => self.time_low(word),
+                0b1000 => self.time_high(word),
+                0b1010 => handle_trigger_event(neuromorphic_types::TriggerEvent {
+                    t: self.t,
+                    id: ((word >> 8) & 0b1111) as u8,
+                    polarity: if (word & 1) > 0 {
+                        neuromorphic_types::TriggerPolarity::Rising
+                    } else {
+                        neuromorphic_types::TriggerPolarity::Falling
+                    },
+                }),
                 _ => (),
             }
         }
+        length
+    }
+
+    pub fn consume(&mut self, slice: &[u8]) {
+        for index in 0..slice.len() / 2 {
+            let word = u16::from_le_bytes([slice[index * 2], slice[index * 2 + 1]]);
+            match word >> 12 {
+                0b0110 => self.time_low(word),
+                0b1000 => self.time_high(word),
+                _ => (),
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn time_low(&mut self, word: u16) {
+        let lsb_t = word & 0b111111111111;
+        if lsb_t != self.previous_lsb_t {
+            self.previous_lsb_t = lsb_t;
+            let t = (((self.previous_lsb_t as u32) | ((self.previous_msb_t as u32) << 12)) as u64)
+                | ((self.overflows as u64) << 24);
+            if t >= self.t {
+                self.t = t;
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn time_high(&mut self, word: u16) {
+        let msb_t = word & 0b111111111111;
+        if msb_t != self.previous_msb_t {
+            if msb_t > self.previous_msb_t {
+                if (msb_t - self.previous_msb_t) < (1 << 11) {
+                    self.previous_lsb_t = 0;
+                    self.previous_msb_t = msb_t;
+                }
+            } else if (self.previous_msb_t - msb_t) > (1 << 11) {
+                self.overflows += 1;
+                self.previous_lsb_t = 0;
+                self.previous_msb_t = msb_t;
+            }
+            let t = (((self.previous_lsb_t as u32) | ((self.previous_msb_t as u32) << 12)) as u64)
+                | ((self.overflows as u64) << 24);
+            if t >= self.t {
+                self.t = t;
+            }
+        }
+    }
+}
+
+pub struct Encoder {
+    t: u64,
+    msb_t: u64,
+    lsb_t: u16,
+    y: Option<u16>,
+    width: u16,
+    height: u16,
+    pending_t: u64,
+    pending_y: u16,
+    pending_polarity: neuromorphic_types::DvsPolarity,
+    pending_xs: Vec<u16>,
+}
+
+impl Encoder {
+    pub fn from_dimensions(width: u16, height: u16) -> Self {
+        Self {
+            t: 0,
+            msb_t: 0,
+            lsb_t: 0,
+            y: None,
+            width,
+            height,
+            pending_t: 0,
+            pending_y: 0,
+            pending_polarity: neuromorphic_types::DvsPolarity::Off,
+            pending_xs: Vec::new(),
+        }
+    }
+
+    pub fn current_t(&self) -> u64 {
+        self.t
+    }
+
+    /// Events must be sorted by timestamp, earlier timestamps are clamped to the encoder's current time.
+    ///
+    /// Events outside the sensor are skipped. Events with the same timestamp, row, and polarity
+    /// are grouped into vectors, hence the output is only complete after calling flush.
+    pub fn dvs_event(
+        &mut self,
+        event: neuromorphic_types::DvsEvent<u64, u16, u16>,
+        bytes: &mut Vec<u8>,
+    ) {
+        if event.x >= self.width || event.y >= self.height {
+            return;
+        }
+        let t = event.t.max(self.t).max(self.pending_t);
+        if !self.pending_xs.is_empty()
+            && (t != self.pending_t
+                || event.y != self.pending_y
+                || event.polarity as u8 != self.pending_polarity as u8)
+        {
+            self.flush(bytes);
+        }
+        self.pending_t = t;
+        self.pending_y = event.y;
+        self.pending_polarity = event.polarity;
+        self.pending_xs.push(event.x);
+    }
+
+    pub fn trigger_event(
+        &mut self,
+        event: neuromorphic_types::TriggerEvent<u64, u8>,
+        bytes: &mut Vec<u8>,
+    ) {
+        self.flush(bytes);
+        self.push_time(event.t, bytes);
+        push_word(
+            bytes,
+            (0b1010 << 12)
+                | (((event.id & 0b1111) as u16) << 8)
+                | (event.polarity as u16 & 1),
+        );
+    }
+
+    /// encode merges DVS and trigger events by timestamp and flushes the output.
+    pub fn encode(
+        &mut self,
+        dvs_events: &[neuromorphic_types::DvsEvent<u64, u16, u16>],
+        trigger_events: &[neuromorphic_types::TriggerEvent<u64, u8>],
+        bytes: &mut Vec<u8>,
+    ) {
+        let mut trigger_index = 0;
+        for dvs_event in dvs_events {
+            while trigger_index < trigger_events.len()
+                && trigger_events[trigger_index].t <= dvs_event.t
+            {
+                self.trigger_event(trigger_events[trigger_index], bytes);
+                trigger_index += 1;
+            }
+            self.dvs_event(*dvs_event, bytes);
+        }
+        for trigger_event in &trigger_events[trigger_index..] {
+            self.trigger_event(*trigger_event, bytes);
+        }
+        self.flush(bytes);
     }
+
+    pub fn flush(&mut self, bytes: &mut Vec<u8>) {
+        if self.pending_xs.is_empty() {
+            return;
+        }
+        self.push_time(self.pending_t, bytes);
+        let y = self.height - 1 - self.pending_y;
+        if self.y != Some(y) {
+            push_word(bytes, y);
+            self.y = Some(y);
+        }
+        let polarity = (self.pending_polarity as u16 & 1) << 11;
+        let mut xs = std::mem::take(&mut self.pending_xs);
+        xs.sort_unstable();
+        xs.dedup();
+        if xs.len() == 1 {
+            push_word(bytes, (0b0010 << 12) | polarity | xs[0]);
+        } else {
+            let mut index = 0;
+            let mut base: Option<u16> = None;
+            while index < xs.len() {
+                let x = match base {
+                    Some(x) if xs[index] < x + 12 => x,
+                    _ => {
+                        push_word(bytes, (0b0011 << 12) | polarity | xs[index]);
+                        xs[index]
+                    }
+                };
+                if xs[xs.len() - 1] < x + 8 {
+                    let mut set = 0u16;
+                    for x_index in &xs[index..] {
+                        set |= 1 << (x_index - x);
+                    }
+                    push_word(bytes, (0b0101 << 12) | set);
+                    index = xs.len();
+                    base = Some(x + 8);
+                } else {
+                    let mut set = 0u16;
+                    while index < xs.len() && xs[index] < x + 12 {
+                        set |= 1 << (xs[index] - x);
+                        index += 1;
+                    }
+                    push_word(bytes, (0b0100 << 12) | set);
+                    base = Some(x + 12);
+                }
+            }
+        }
+        self.pending_xs = xs;
+        self.pending_xs.clear();
+    }
+
+    fn push_time(&mut self, t: u64, bytes: &mut Vec<u8>) {
+        let t = t.max(self.t);
+        let msb_t = t >> 12;
+        // the adapter ignores forward jumps larger than or equal to 2048 TIME_HIGH ticks
+        while self.msb_t < msb_t {
+            self.msb_t += (msb_t - self.msb_t).min((1 << 11) - 1);
+            push_word(bytes, (0b1000 << 12) | (self.msb_t & 0b111111111111) as u16);
+            self.lsb_t = 0;
+        }
+        let lsb_t = (t & 0b111111111111) as u16;
+        if lsb_t != self.lsb_t {
+            push_word(bytes, (0b0110 << 12) | lsb_t);
+            self.lsb_t = lsb_t;
+        }
+        self.t = t;
+    }
+}
+
+fn push_word(bytes: &mut Vec<u8>, word: u16) {
+    bytes.extend_from_slice(&word.to_le_bytes());
 }
@@ -0,0 +1,252 @@
+use std::io::Write;
+
+use crate::adapters;
+
+pub const SIGNATURE: &[u8; 14] = b"#!AER-DAT4.0\r\n";
+pub const DVS_STREAM_ID: i32 = 0;
+pub const TRIGGER_STREAM_ID: i32 = 1;
+
+// FlatBuffers layout of a size-prefixed EventPacket ("EVTS"), with the elements starting at byte 32
+const DVS_PACKET_HEADER_LENGTH: usize = 32;
+const DVS_EVENT_LENGTH: usize = 16;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Writes uncompressed AEDAT 4.0 files (DVS events and triggers), readable by DV-based tools.
+///
+/// AEDAT uses a top-left origin, y is flipped to match the sensor's orientation.
+/// AEDAT triggers do not have an id, all the trigger events are written to the same stream.
+pub struct Writer {
+    file: std::io::BufWriter<std::fs::File>,
+    height: u16,
+    dvs_packet: Vec<u8>,
+    trigger_events: Vec<neuromorphic_types::TriggerEvent<u64, u8>>,
+}
+
+impl Writer {
+    pub fn create<P: AsRef<std::path::Path>>(
+        path: P,
+        width: u16,
+        height: u16,
+        source: &str,
+    ) -> Result<Self, Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        file.write_all(SIGNATURE)?;
+        file.write_all(&io_header(&info_node(width, height, source)))?;
+        Ok(Self {
+            file,
+            height,
+            dvs_packet: dvs_packet_header(),
+            trigger_events: Vec::new(),
+        })
+    }
+
+    /// Converts a slice of raw bytes and writes the resulting events as one packet per stream.
+    ///
+    /// DVS events are encoded directly by the adapter's callback, without an intermediate vector.
+    pub fn write_slice(
+        &mut self,
+        adapter: &mut adapters::Adapter,
+        slice: &[u8],
+    ) -> Result<(), Error> {
+        let height = self.height;
+        let dvs_packet = &mut self.dvs_packet;
+        let trigger_events = &mut self.trigger_events;
+        dvs_packet.reserve(adapter.events_lengths(slice).dvs * DVS_EVENT_LENGTH);
+        adapter.convert(
+            slice,
+            |dvs_event| push_dvs_event(dvs_packet, height, &dvs_event),
+            |trigger_event| trigger_events.push(trigger_event),
+        );
+        self.write_pending_packets()
+    }
+
+    pub fn write_dvs_events(
+        &mut self,
+        events: &[neuromorphic_types::DvsEvent<u64, u16, u16>],
+    ) -> Result<(), Error> {
+        self.dvs_packet.reserve(events.len() * DVS_EVENT_LENGTH);
+        for event in events {
+            push_dvs_event(&mut self.dvs_packet, self.height, event);
+        }
+        self.write_pending_packets()
+    }
+
+    pub fn write_trigger_events(
+        &mut self,
+        events: &[neuromorphic_types::TriggerEvent<u64, u8>],
+    ) -> Result<(), Error> {
+        self.trigger_events.extend_from_slice(events);
+        self.write_pending_packets()
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.file.flush()?;
+        Ok(())
+    }
+
+    fn write_pending_packets(&mut self) -> Result<(), Error> {
+        if self.dvs_packet.len() > DVS_PACKET_HEADER_LENGTH {
+            let length = self.dvs_packet.len();
+            self.dvs_packet[0..4].copy_from_slice(&((length - 4) as u32).to_le_bytes());
+            self.dvs_packet[28..32].copy_from_slice(
+                &(((length - DVS_PACKET_HEADER_LENGTH) / DVS_EVENT_LENGTH) as u32).to_le_bytes(),
+            );
+            self.file.write_all(&DVS_STREAM_ID.to_le_bytes())?;
+            self.file.write_all(&(length as i32).to_le_bytes())?;
+            self.file.write_all(&self.dvs_packet)?;
+            self.dvs_packet.truncate(DVS_PACKET_HEADER_LENGTH);
+        }
+        if !self.trigger_events.is_empty() {
+            let trigger_packet = trigger_packet(&self.trigger_events);
+            self.file.write_all(&TRIGGER_STREAM_ID.to_le_bytes())?;
+            self.file
+                .write_all(&(trigger_packet.len() as i32).to_le_bytes())?;
+            self.file.write_all(&trigger_packet)?;
+            self.trigger_events.clear();
+        }
+        Ok(())
+    }
+}
+
+fn info_node(width: u16, height: u16, source: &str) -> String {
+    let source = source
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let mut info_node = String::from(
+        "<dv version=\"2.0\">\n    <node name=\"outInfo\" path=\"/mainloop/Recorder/outInfo/\">\n",
+    );
+    for (stream_id, identifier, name, description) in [
+        (
+            DVS_STREAM_ID,
+            "EVTS",
+            "events",
+            "Array of events (polarity ON/OFF).",
+        ),
+        (
+            TRIGGER_STREAM_ID,
+            "TRIG",
+            "triggers",
+            "Array of triggers (special events).",
+        ),
+    ] {
+        info_node.push_str(&format!(
+            concat!(
+                "        <node name=\"{0}\" path=\"/mainloop/Recorder/outInfo/{0}/\">\n",
+                "            <attr key=\"compression\" type=\"string\">NONE</attr>\n",
+                "            <attr key=\"originalModuleName\" type=\"string\">capture</attr>\n",
+                "            <attr key=\"originalOutputName\" type=\"string\">{2}</attr>\n",
+                "            <attr key=\"typeDescription\" type=\"string\">{3}</attr>\n",
+                "            <attr key=\"typeIdentifier\" type=\"string\">{1}</attr>\n",
+                "            <node name=\"info\" path=\"/mainloop/Recorder/outInfo/{0}/info/\">\n",
+                "                <attr key=\"sizeX\" type=\"int\">{4}</attr>\n",
+                "                <attr key=\"sizeY\" type=\"int\">{5}</attr>\n",
+                "                <attr key=\"source\" type=\"string\">{6}</attr>\n",
+                "                <attr key=\"tsOffset\" type=\"long\">0</attr>\n",
+                "            </node>\n",
+                "        </node>\n",
+            ),
+            stream_id, identifier, name, description, width, height, source,
+        ));
+    }
+    info_node.push_str("    </node>\n</dv>\n");
+    info_node
+}
+
+// size-prefixed IOHeader ("IOHE"), compression NONE and no data table
+fn io_header(info_node: &str) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(49 + info_node.len());
+    buffer.extend_from_slice(&[0u8; 4]); // size, written below
+    buffer.extend_from_slice(&20u32.to_le_bytes()); // root table offset (4 + 20 = 24)
+    buffer.extend_from_slice(b"IOHE");
+    // vtable: vtable length, table length, compression, dataTablePosition, infoNode
+    for value in [10u16, 20, 4, 8, 16, 0] {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+    buffer.extend_from_slice(&12i32.to_le_bytes()); // table (24) - vtable (12)
+    buffer.extend_from_slice(&0i32.to_le_bytes()); // compression
+    buffer.extend_from_slice(&(-1i64).to_le_bytes()); // dataTablePosition
+    buffer.extend_from_slice(&4u32.to_le_bytes()); // infoNode offset (40 + 4 = 44)
+    buffer.extend_from_slice(&(info_node.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(info_node.as_bytes());
+    buffer.push(0);
+    let size = (buffer.len() - 4) as u32;
+    buffer[0..4].copy_from_slice(&size.to_le_bytes());
+    buffer
+}
+
+fn dvs_packet_header() -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(DVS_PACKET_HEADER_LENGTH);
+    buffer.extend_from_slice(&[0u8; 4]); // size
+    buffer.extend_from_slice(&16u32.to_le_bytes()); // root table offset (4 + 16 = 20)
+    buffer.extend_from_slice(b"EVTS");
+    // vtable: vtable length, table length, elements (and padding)
+    for value in [6u16, 8, 4, 0] {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+    buffer.extend_from_slice(&8i32.to_le_bytes()); // table (20) - vtable (12)
+    buffer.extend_from_slice(&4u32.to_le_bytes()); // elements offset (24 + 4 = 28)
+    buffer.extend_from_slice(&[0u8; 4]); // elements length
+    buffer
+}
+
+fn push_dvs_event(
+    buffer: &mut Vec<u8>,
+    height: u16,
+    event: &neuromorphic_types::DvsEvent<u64, u16, u16>,
+) {
+    let t = event.t;
+    let x = event.x;
+    let y = event.y;
+    buffer.extend_from_slice(&(t as i64).to_le_bytes());
+    buffer.extend_from_slice(&(x as i16).to_le_bytes());
+    buffer.extend_from_slice(&((height - 1 - y) as i16).to_le_bytes());
+    buffer.extend_from_slice(&[event.polarity as u8, 0, 0, 0]);
+}
+
+// size-prefixed TriggerPacket ("TRIG"), Trigger is a table (not a struct)
+fn trigger_packet(events: &[neuromorphic_types::TriggerEvent<u64, u8>]) -> Vec<u8> {
+    let elements_end = 32 + events.len() * 4;
+    let trigger_vtable = (elements_end + 7) & !7;
+    let first_table = trigger_vtable + 8;
+    let mut buffer = Vec::with_capacity(first_table + events.len() * 24);
+    buffer.extend_from_slice(&[0u8; 4]); // size
+    buffer.extend_from_slice(&16u32.to_le_bytes()); // root table offset (4 + 16 = 20)
+    buffer.extend_from_slice(b"TRIG");
+    for value in [6u16, 8, 4, 0] {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+    buffer.extend_from_slice(&8i32.to_le_bytes());
+    buffer.extend_from_slice(&4u32.to_le_bytes());
+    buffer.extend_from_slice(&(events.len() as u32).to_le_bytes());
+    for index in 0..events.len() {
+        let position = 32 + index * 4;
+        buffer.extend_from_slice(&((first_table + index * 24 - position) as u32).to_le_bytes());
+    }
+    buffer.resize(trigger_vtable, 0);
+    // vtable: vtable length, table length, timestamp, type
+    for value in [8u16, 17, 8, 16] {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+    for (index, event) in events.iter().enumerate() {
+        let t = event.t;
+        buffer
+            .extend_from_slice(&((first_table + index * 24 - trigger_vtable) as i32).to_le_bytes());
+        buffer.extend_from_slice(&[0u8; 4]);
+        buffer.extend_from_slice(&(t as i64).to_le_bytes());
+        // EXTERNAL_SIGNAL_RISING_EDGE = 1, EXTERNAL_SIGNAL_FALLING_EDGE = 2
+        buffer.push(match event.polarity {
+            neuromorphic_types::TriggerPolarity::Rising => 1,
+            neuromorphic_types::TriggerPolarity::Falling => 2,
+        });
+        buffer.extend_from_slice(&[0u8; 7]);
+    }
+    let size = (buffer.len() - 4) as u32;
+    buffer[0..4].copy_from_slice(&size.to_le_bytes());
+    buffer
+}
@@ -3,14 +3,28 @@ pub struct Flagged<Configuration> {
     updated: bool,
 }
 
+/// A configuration applied by the updater thread, and the time at which the device accepted it.
+#[derive(Debug, Clone)]
+pub struct Applied<Configuration> {
+    pub instant: std::time::Instant,
+    pub configuration: Configuration,
+}
+
+/// Subscriber is called with every applied configuration and returns false to unsubscribe.
+type Subscriber<Configuration> = Box<dyn FnMut(&Applied<Configuration>) -> bool + Send>;
+
 pub struct Updater<Configuration> {
     flagged_configuration_and_condition:
         std::sync::Arc<(std::sync::Mutex<Flagged<Configuration>>, std::sync::Condvar)>,
+    subscribers: std::sync::Arc<std::sync::Mutex<Vec<Subscriber<Configuration>>>>,
     thread: Option<std::thread::JoinHandle<()>>,
     running: std::sync::Arc<std::sync::atomic::AtomicBool>,
 }
 
 impl<Configuration: Clone + Send + 'static> Updater<Configuration> {
+    /// update must return the context and whether the configuration was applied.
+    ///
+    /// Subscribers are only notified of configurations that were applied.
     pub fn new<ContextType, Update>(
         initial_configuration: Configuration,
         context: ContextType,
@@ -18,7 +32,25 @@ impl<Configuration: Clone + Send + 'static> Updater<Configuration> {
     ) -> Self
     where
         ContextType: Send + 'static,
-        Update: Fn(ContextType, &Configuration, &Configuration) -> ContextType + Send + 'static,
+        Update:
+            Fn(ContextType, &Configuration, &Configuration) -> (ContextType, bool) + Send + 'static,
+    {
+        Self::with_poll(initial_configuration, context, update, |context, _| context)
+    }
+
+    /// with_poll is like new but also calls poll on the updater thread after every update,
+    /// and at least every 100 ms, with the last applied configuration.
+    pub fn with_poll<ContextType, Update, Poll>(
+        initial_configuration: Configuration,
+        context: ContextType,
+        update: Update,
+        poll: Poll,
+    ) -> Self
+    where
+        ContextType: Send + 'static,
+        Update:
+            Fn(ContextType, &Configuration, &Configuration) -> (ContextType, bool) + Send + 'static,
+        Poll: Fn(ContextType, &Configuration) -> ContextType + Send + 'static,
     {
         let previous_configuration = initial_configuration.clone();
         let flagged_configuration_and_condition = std::sync::Arc::new((
@@ -30,10 +62,14 @@ impl<Configuration: Clone + Send + 'static> Updater<Configuration> {
         ));
         let thread_flagged_configuration_and_condition =
             flagged_configuration_and_condition.clone();
+        let subscribers: std::sync::Arc<std::sync::Mutex<Vec<Subscriber<Configuration>>>> =
+            std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let thread_subscribers = subscribers.clone();
         let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
         let thread_running = running.clone();
         Self {
             flagged_configuration_and_condition,
+            subscribers,
             thread: Some(std::thread::spawn(move || {
                 let mut context = context;
                 let mut previous_configuration = previous_configuration;
@@ -60,9 +96,23 @@ impl<Configuration: Clone + Send + 'static> Updater<Configuration> {
                         }
                     };
                     if let Some(configuration) = configuration {
-                        context = update(context, &previous_configuration, &configuration);
-                        previous_configuration = configuration;
+                        let (new_context, success) =
+                            update(context, &previous_configuration, &configuration);
+                        context = new_context;
+                        let applied = Applied {
+                            instant: std::time::Instant::now(),
+                            configuration,
+                        };
+                        if success {
+                            // unwrap: mutex is not poisoned
+                            thread_subscribers
+                                .lock()
+                                .unwrap()
+                                .retain_mut(|subscriber| subscriber(&applied));
+                        }
+                        previous_configuration = applied.configuration;
                     }
+                    context = poll(context, &previous_configuration);
                 }
             })),
             running,
@@ -77,6 +127,17 @@ impl<Configuration: Clone + Send + 'static> Updater<Configuration> {
         flagged_configuration.updated = true;
         condvar.notify_one();
     }
+
+    /// subscribe calls subscriber on the updater thread after each successful update.
+    ///
+    /// The subscriber is removed when it returns false (for instance, when a channel's receiver was dropped).
+    pub fn subscribe<NewSubscriber>(&self, subscriber: NewSubscriber)
+    where
+        NewSubscriber: FnMut(&Applied<Configuration>) -> bool + Send + 'static,
+    {
+        // unwrap: mutex is not poisoned
+        self.subscribers.lock().unwrap().push(Box::new(subscriber));
+    }
 }
 
 impl<Configuration> Drop for Updater<Configuration> {
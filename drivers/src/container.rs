@@ -0,0 +1,448 @@
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
+
+pub const SIGNATURE: &[u8; 8] = b"NDEVENTS";
+pub const VERSION: u8 = 1;
+pub const INDEX_SIGNATURE: &[u8; 4] = b"NDIX";
+pub const DEFAULT_CHUNK_DURATION_US: u64 = 100000;
+pub const MAXIMUM_CHUNK_LENGTH: usize = 1 << 20;
+
+const HEADER_LENGTH: u64 = 13;
+const CHUNK_HEADER_LENGTH: u64 = 25;
+const INDEX_ENTRY_LENGTH: usize = 28;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("the file does not start with \"NDEVENTS\"")]
+    Signature,
+
+    #[error("unsupported version {0}")]
+    Version(u8),
+
+    #[error("the timestamp {t} is smaller than the previous timestamp {previous_t}")]
+    Timestamp { previous_t: u64, t: u64 },
+
+    #[error("unsupported compression {0} (was the zstd feature enabled?)")]
+    Compression(u8),
+
+    #[error("chunk at offset {0} is corrupted")]
+    Chunk(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+
+    /// Requires the zstd feature.
+    Zstd(i32),
+}
+
+impl Compression {
+    fn code(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Zstd(_) => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkIndexEntry {
+    pub first_t: u64,
+    pub last_t: u64,
+    pub offset: u64,
+    pub length: u32,
+}
+
+/// Writes DVS events in chunks that span at most chunk_duration_us (and MAXIMUM_CHUNK_LENGTH events).
+///
+/// Each chunk stores the time delta (with the polarity in bit 0) and the zigzag-encoded x and y deltas
+/// of every event as varints. The chunk index is written by finish (or on drop).
+pub struct Writer {
+    file: std::io::BufWriter<std::fs::File>,
+    chunk_duration_us: u64,
+    compression: Compression,
+    offset: u64,
+    previous_t: u64,
+    chunk: Vec<neuromorphic_types::DvsEvent<u64, u16, u16>>,
+    bytes: Vec<u8>,
+    index: Vec<ChunkIndexEntry>,
+    finished: bool,
+}
+
+impl Writer {
+    pub fn create<P: AsRef<std::path::Path>>(
+        path: P,
+        width: u16,
+        height: u16,
+        chunk_duration_us: u64,
+        compression: Compression,
+    ) -> Result<Self, Error> {
+        #[cfg(not(feature = "zstd"))]
+        if let Compression::Zstd(_) = compression {
+            return Err(Error::Compression(compression.code()));
+        }
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        file.write_all(SIGNATURE)?;
+        file.write_all(&[VERSION])?;
+        file.write_all(&width.to_le_bytes())?;
+        file.write_all(&height.to_le_bytes())?;
+        Ok(Self {
+            file,
+            chunk_duration_us: chunk_duration_us.max(1),
+            compression,
+            offset: HEADER_LENGTH,
+            previous_t: 0,
+            chunk: Vec::new(),
+            bytes: Vec::new(),
+            index: Vec::new(),
+            finished: false,
+        })
+    }
+
+    pub fn write_dvs_events(
+        &mut self,
+        events: &[neuromorphic_types::DvsEvent<u64, u16, u16>],
+    ) -> Result<(), Error> {
+        for event in events {
+            let t = event.t;
+            if t < self.previous_t {
+                return Err(Error::Timestamp {
+                    previous_t: self.previous_t,
+                    t,
+                });
+            }
+            self.previous_t = t;
+            if let Some(first) = self.chunk.first() {
+                let first_t = first.t;
+                if t - first_t >= self.chunk_duration_us || self.chunk.len() == MAXIMUM_CHUNK_LENGTH
+                {
+                    self.write_chunk()?;
+                }
+            }
+            self.chunk.push(*event);
+        }
+        Ok(())
+    }
+
+    /// Writes the last chunk and the index.
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.write_index()
+    }
+
+    fn write_chunk(&mut self) -> Result<(), Error> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+        self.bytes.clear();
+        let mut previous_t = self.chunk[0].t;
+        let mut previous_x = 0u16;
+        let mut previous_y = 0u16;
+        for event in self.chunk.iter() {
+            let t = event.t;
+            let x = event.x;
+            let y = event.y;
+            push_varint(
+                &mut self.bytes,
+                ((t - previous_t) << 1) | (event.polarity as u64),
+            );
+            push_varint(&mut self.bytes, zigzag(x as i32 - previous_x as i32));
+            push_varint(&mut self.bytes, zigzag(y as i32 - previous_y as i32));
+            previous_t = t;
+            previous_x = x;
+            previous_y = y;
+        }
+        let payload = compress(self.compression, &self.bytes)?;
+        let first_t = self.chunk[0].t;
+        let last_t = self.chunk[self.chunk.len() - 1].t;
+        self.file.write_all(&[self.compression.code()])?;
+        self.file.write_all(&first_t.to_le_bytes())?;
+        self.file.write_all(&last_t.to_le_bytes())?;
+        self.file
+            .write_all(&(self.chunk.len() as u32).to_le_bytes())?;
+        self.file.write_all(&(payload.len() as u32).to_le_bytes())?;
+        self.file.write_all(&payload)?;
+        self.index.push(ChunkIndexEntry {
+            first_t,
+            last_t,
+            offset: self.offset,
+            length: self.chunk.len() as u32,
+        });
+        self.offset += CHUNK_HEADER_LENGTH + payload.len() as u64;
+        self.chunk.clear();
+        Ok(())
+    }
+
+    // the index is followed by its offset and INDEX_SIGNATURE
+    fn write_index(&mut self) -> Result<(), Error> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        self.write_chunk()?;
+        self.file.write_all(&[0xff])?; // distinguishes the index from a chunk when scanning
+        self.file
+            .write_all(&(self.index.len() as u64).to_le_bytes())?;
+        for entry in self.index.iter() {
+            self.file.write_all(&entry.first_t.to_le_bytes())?;
+            self.file.write_all(&entry.last_t.to_le_bytes())?;
+            self.file.write_all(&entry.offset.to_le_bytes())?;
+            self.file.write_all(&entry.length.to_le_bytes())?;
+        }
+        self.file.write_all(&self.offset.to_le_bytes())?;
+        self.file.write_all(INDEX_SIGNATURE)?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        let _ = self.write_index();
+    }
+}
+
+pub struct Reader {
+    file: std::fs::File,
+    width: u16,
+    height: u16,
+    index: Vec<ChunkIndexEntry>,
+}
+
+impl Reader {
+    /// Files without an index (for instance if the writer crashed) are scanned chunk by chunk.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        let mut file = std::fs::File::open(path)?;
+        let mut header = [0u8; HEADER_LENGTH as usize];
+        file.read_exact(&mut header)?;
+        if &header[0..8] != SIGNATURE {
+            return Err(Error::Signature);
+        }
+        if header[8] != VERSION {
+            return Err(Error::Version(header[8]));
+        }
+        let index = match read_index(&mut file)? {
+            Some(index) => index,
+            None => scan_index(&mut file)?,
+        };
+        Ok(Self {
+            file,
+            width: u16::from_le_bytes([header[9], header[10]]),
+            height: u16::from_le_bytes([header[11], header[12]]),
+            index,
+        })
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn index(&self) -> &[ChunkIndexEntry] {
+        &self.index
+    }
+
+    /// Returns the events with t0 <= t < t1, only the chunks that overlap the range are decoded.
+    pub fn read_range(
+        &mut self,
+        t0: u64,
+        t1: u64,
+    ) -> Result<Vec<neuromorphic_types::DvsEvent<u64, u16, u16>>, Error> {
+        let mut events = Vec::new();
+        let start = self.index.partition_point(|entry| entry.last_t < t0);
+        for position in start..self.index.len() {
+            let entry = self.index[position];
+            if entry.first_t >= t1 {
+                break;
+            }
+            self.read_chunk(&entry, |event| {
+                let t = event.t;
+                if t >= t0 && t < t1 {
+                    events.push(event);
+                }
+            })?;
+        }
+        Ok(events)
+    }
+
+    pub fn read_chunk<HandleDvsEvent>(
+        &mut self,
+        entry: &ChunkIndexEntry,
+        mut handle_dvs_event: HandleDvsEvent,
+    ) -> Result<(), Error>
+    where
+        HandleDvsEvent: FnMut(neuromorphic_types::DvsEvent<u64, u16, u16>),
+    {
+        self.file.seek(std::io::SeekFrom::Start(entry.offset))?;
+        let mut header = [0u8; CHUNK_HEADER_LENGTH as usize];
+        self.file.read_exact(&mut header)?;
+        let payload_length = u32::from_le_bytes(header[21..25].try_into().expect("4 bytes"));
+        let mut payload = vec![0u8; payload_length as usize];
+        self.file.read_exact(&mut payload)?;
+        let bytes = decompress(header[0], payload)?;
+        let mut position = 0;
+        let mut t = entry.first_t;
+        let mut x = 0u16;
+        let mut y = 0u16;
+        for _ in 0..entry.length {
+            let (Some(t_and_polarity), Some(x_delta), Some(y_delta)) = (
+                read_varint(&bytes, &mut position),
+                read_varint(&bytes, &mut position),
+                read_varint(&bytes, &mut position),
+            ) else {
+                return Err(Error::Chunk(entry.offset));
+            };
+            t += t_and_polarity >> 1;
+            x = (x as i32 + unzigzag(x_delta)) as u16;
+            y = (y as i32 + unzigzag(y_delta)) as u16;
+            handle_dvs_event(neuromorphic_types::DvsEvent {
+                t,
+                x,
+                y,
+                polarity: if (t_and_polarity & 1) == 1 {
+                    neuromorphic_types::DvsPolarity::On
+                } else {
+                    neuromorphic_types::DvsPolarity::Off
+                },
+            });
+        }
+        Ok(())
+    }
+}
+
+fn read_index(file: &mut std::fs::File) -> Result<Option<Vec<ChunkIndexEntry>>, Error> {
+    let length = file.seek(std::io::SeekFrom::End(0))?;
+    if length < HEADER_LENGTH + 21 {
+        return Ok(None);
+    }
+    let mut footer = [0u8; 12];
+    file.seek(std::io::SeekFrom::End(-12))?;
+    file.read_exact(&mut footer)?;
+    if &footer[8..12] != INDEX_SIGNATURE {
+        return Ok(None);
+    }
+    let offset = u64::from_le_bytes(footer[0..8].try_into().expect("8 bytes"));
+    // the footer comes from the file, hence offset and count may be arbitrary (corrupted file)
+    if offset < HEADER_LENGTH || offset > length - 21 {
+        return Ok(None);
+    }
+    file.seek(std::io::SeekFrom::Start(offset))?;
+    let mut bytes = vec![0u8; (length - offset - 12) as usize];
+    file.read_exact(&mut bytes)?;
+    if bytes[0] != 0xff {
+        return Ok(None);
+    }
+    let count = u64::from_le_bytes(bytes[1..9].try_into().expect("8 bytes"));
+    if usize::try_from(count)
+        .ok()
+        .and_then(|count| count.checked_mul(INDEX_ENTRY_LENGTH))
+        .and_then(|entries_length| entries_length.checked_add(9))
+        != Some(bytes.len())
+    {
+        return Ok(None);
+    }
+    Ok(Some(
+        bytes[9..]
+            .chunks_exact(INDEX_ENTRY_LENGTH)
+            .map(|entry| ChunkIndexEntry {
+                first_t: u64::from_le_bytes(entry[0..8].try_into().expect("8 bytes")),
+                last_t: u64::from_le_bytes(entry[8..16].try_into().expect("8 bytes")),
+                offset: u64::from_le_bytes(entry[16..24].try_into().expect("8 bytes")),
+                length: u32::from_le_bytes(entry[24..28].try_into().expect("4 bytes")),
+            })
+            .collect(),
+    ))
+}
+
+fn scan_index(file: &mut std::fs::File) -> Result<Vec<ChunkIndexEntry>, Error> {
+    let length = file.seek(std::io::SeekFrom::End(0))?;
+    let mut index = Vec::new();
+    let mut offset = HEADER_LENGTH;
+    let mut header = [0u8; CHUNK_HEADER_LENGTH as usize];
+    while offset + CHUNK_HEADER_LENGTH <= length {
+        file.seek(std::io::SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        if header[0] == 0xff {
+            break;
+        }
+        let payload_length = u32::from_le_bytes(header[21..25].try_into().expect("4 bytes")) as u64;
+        if offset + CHUNK_HEADER_LENGTH + payload_length > length {
+            break; // truncated chunk
+        }
+        index.push(ChunkIndexEntry {
+            first_t: u64::from_le_bytes(header[1..9].try_into().expect("8 bytes")),
+            last_t: u64::from_le_bytes(header[9..17].try_into().expect("8 bytes")),
+            offset,
+            length: u32::from_le_bytes(header[17..21].try_into().expect("4 bytes")),
+        });
+        offset += CHUNK_HEADER_LENGTH + payload_length;
+    }
+    Ok(index)
+}
+
+fn push_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], position: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*position)?;
+        *position += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte < 0x80 {
+            return Some(value);
+        }
+        shift += 7;
+        if shift > 63 {
+            return None;
+        }
+    }
+}
+
+fn zigzag(value: i32) -> u64 {
+    ((value << 1) ^ (value >> 31)) as u32 as u64
+}
+
+fn unzigzag(value: u64) -> i32 {
+    ((value >> 1) as i32) ^ -((value & 1) as i32)
+}
+
+#[cfg(feature = "zstd")]
+fn compress(compression: Compression, bytes: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>, Error> {
+    match compression {
+        Compression::None => Ok(std::borrow::Cow::Borrowed(bytes)),
+        Compression::Zstd(level) => {
+            Ok(std::borrow::Cow::Owned(zstd::bulk::compress(bytes, level)?))
+        }
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn compress(compression: Compression, bytes: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>, Error> {
+    match compression {
+        Compression::None => Ok(std::borrow::Cow::Borrowed(bytes)),
+        Compression::Zstd(_) => Err(Error::Compression(compression.code())),
+    }
+}
+
+fn decompress(code: u8, payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+    match code {
+        0 => Ok(payload),
+        #[cfg(feature = "zstd")]
+        1 => Ok(zstd::stream::decode_all(payload.as_slice())?),
+        code => Err(Error::Compression(code)),
+    }
+}
@@ -1,3 +1,4 @@
+use crate::configuration;
 use crate::flag;
 use crate::usb;
 use rusb::UsbContext;
@@ -29,7 +30,13 @@ pub trait Usb: Sized {
     /// This is required even if read_serial does not use bulk transfers.
     fn read_serial(handle: &mut rusb::DeviceHandle<rusb::Context>) -> rusb::Result<String>;
 
-    fn update_configuration(&self, configuration: Self::Configuration);
+    /// update_configuration must reject invalid configurations before passing them to the updater thread.
+    fn update_configuration(&self, configuration: Self::Configuration) -> Result<(), Self::Error>;
+
+    /// subscribe_applied_configurations calls subscriber on the updater thread after each successful update.
+    fn subscribe_applied_configurations<Subscriber>(&self, subscriber: Subscriber)
+    where
+        Subscriber: FnMut(&configuration::Applied<Self::Configuration>) -> bool + Send + 'static;
 
     fn open<IntoError, IntoWarning>(
         serial: &Option<&str>,
@@ -44,6 +51,12 @@ pub trait Usb: Sized {
 
     fn next_with_timeout(&self, timeout: &std::time::Duration) -> Option<usb::BufferView>;
 
+    fn set_maximum_leases(&self, maximum_leases: usize);
+
+    /// register_waker must arrange for the waker to be woken when a buffer becomes available.
+    #[cfg(feature = "async")]
+    fn register_waker(&self, waker: &std::task::Waker);
+
     fn backlog(&self) -> usize;
 
     fn clutch(&self) -> usb::Clutch;
@@ -58,10 +71,14 @@ pub trait Usb: Sized {
 
     fn temperature_celsius(&self) -> Result<TemperatureCelsius, Self::Error>;
 
-    fn list_devices(devices: &rusb::DeviceList<rusb::Context>) -> rusb::Result<Vec<ListedDevice>> {
+    /// devices is None if libusb could not be initialized.
+    fn list_devices(
+        devices: Option<&rusb::DeviceList<rusb::Context>>,
+    ) -> rusb::Result<Vec<ListedDevice>> {
         let mut result = Vec::new();
         for device in devices
-            .iter()
+            .into_iter()
+            .flat_map(|devices| devices.iter())
             .filter(|device| match device.device_descriptor() {
                 Ok(descriptor) => {
                     descriptor.vendor_id() == Self::VENDOR_ID
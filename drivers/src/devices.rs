@@ -1,10 +1,13 @@
 use crate::adapters;
+use crate::configuration;
 use crate::device::TemperatureCelsius;
 use crate::device::Usb;
 use crate::flag;
 use crate::usb;
 use rusb::UsbContext;
 
+pub mod erc;
+
 macro_rules! register {
     ($($module:ident),+) => {
         paste::paste! {
@@ -93,12 +96,11 @@ macro_rules! register {
             }
 
             pub fn list_devices() -> rusb::Result<Vec<ListedDevice>> {
-                let context = rusb::Context::new()?;
-                let devices = context.devices()?;
+                let devices = rusb::Context::new().and_then(|context| context.devices());
                 let mut result = Vec::new();
                 $(
                     result.extend(
-                        $module::Device::list_devices(&devices)?
+                        $module::Device::list_devices(devices.as_ref().ok())?
                             .into_iter()
                             .map(|listed_device| ListedDevice {
                                 device_type: Type::[<$module:camel>],
@@ -107,7 +109,11 @@ macro_rules! register {
                             }),
                     );
                 )+
-                Ok(result)
+                match devices {
+                    // mock devices are listed even if libusb could not be initialized
+                    Err(error) if result.is_empty() => Err(error),
+                    _ => Ok(result),
+                }
             }
 
             pub fn open(
@@ -173,6 +179,32 @@ macro_rules! register {
                 )+
             }
 
+            impl Properties {
+                pub fn name(&self) -> &'static str {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](properties) => properties.name,
+                        )+
+                    }
+                }
+
+                pub fn width(&self) -> u16 {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](properties) => properties.width,
+                        )+
+                    }
+                }
+
+                pub fn height(&self) -> u16 {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](properties) => properties.height,
+                        )+
+                    }
+                }
+            }
+
             impl Device {
                 pub fn adapter(&self) -> adapters::Adapter {
                     match self {
@@ -190,6 +222,35 @@ macro_rules! register {
                     }
                 }
 
+                /// See usb::Ring::set_maximum_leases.
+                pub fn set_maximum_leases(&self, maximum_leases: usize) {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](device) => device.set_maximum_leases(maximum_leases),
+                        )+
+                    }
+                }
+
+                #[cfg(feature = "async")]
+                pub fn register_waker(&self, waker: &std::task::Waker) {
+                    match self {
+                        $(
+                            Self::[<$module:camel>](device) => device.register_waker(waker),
+                        )+
+                    }
+                }
+
+                /// stream returns an asynchronous stream of buffers.
+                ///
+                /// flag must be the flag passed to open. The stream ends once an error is stored in flag,
+                /// the error can then be retrieved with flag.load_error.
+                ///
+                /// Buffers are copied since a stream item cannot borrow the stream.
+                #[cfg(feature = "async")]
+                pub fn stream(&self, flag: flag::Flag<Error, usb::Overflow>) -> Stream<&Self> {
+                    Stream::new(self, flag)
+                }
+
                 pub fn backlog(&self) -> usize {
                     match self {
                         $(
@@ -246,14 +307,35 @@ macro_rules! register {
                     }
                 }
 
+                /// applied_configurations returns a channel that receives the configurations applied from now on.
+                ///
+                /// Configurations whose update failed are not sent.
+                pub fn applied_configurations(&self) -> std::sync::mpsc::Receiver<configuration::Applied<Configuration>> {
+                    let (sender, receiver) = std::sync::mpsc::channel();
+                    match self {
+                        $(
+                            Self::[<$module:camel>](device) => device.subscribe_applied_configurations(
+                                move |applied| {
+                                    sender
+                                        .send(configuration::Applied {
+                                            instant: applied.instant,
+                                            configuration: Configuration::[<$module:camel>](applied.configuration.clone()),
+                                        })
+                                        .is_ok()
+                                },
+                            ),
+                        )+
+                    }
+                    receiver
+                }
+
                 pub fn update_configuration(&self, configuration: Configuration) -> Result<(), Error> {
                     match self {
                         $(
                             Self::[<$module:camel>](device) => match configuration {
-                                Configuration::[<$module:camel>](configuration) => {
-                                    device.update_configuration(configuration);
-                                    Ok(())
-                                },
+                                Configuration::[<$module:camel>](configuration) => device
+                                    .update_configuration(configuration)
+                                    .map_err(|error| error.into()),
                                 configuration => Err(Error::UpdateMismatch {
                                     configuration: configuration.type_name().to_owned(),
                                     device: $module::Device::PROPERTIES.name.to_owned(),
@@ -347,4 +429,51 @@ macro_rules! register {
     };
 }
 
-register! { prophesee_evk3_hd, prophesee_evk4, centuryarks_silkyevcamhd }
+register! { prophesee_evk3_hd, prophesee_evk4, centuryarks_silkyevcamhd, mock }
+
+/// Stream yields the device's buffers as they are received.
+///
+/// The stream ends (yields None) once an error is stored in its flag and no buffer is pending.
+/// The error is left in the flag, use flag.load_error to retrieve it.
+///
+/// The handle may be a reference or a shared pointer (for instance std::sync::Arc<Device>).
+#[cfg(feature = "async")]
+pub struct Stream<Handle: std::ops::Deref<Target = Device>> {
+    device: Handle,
+    flag: flag::Flag<Error, usb::Overflow>,
+}
+
+#[cfg(feature = "async")]
+impl<Handle: std::ops::Deref<Target = Device>> Stream<Handle> {
+    pub fn new(device: Handle, flag: flag::Flag<Error, usb::Overflow>) -> Self {
+        Self { device, flag }
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Handle: std::ops::Deref<Target = Device> + Unpin> futures_core::Stream for Stream<Handle> {
+    type Item = usb::OwnedBuffer;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        context: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        // register the wakers first to avoid missing a buffer or an error received between the calls
+        self.device.register_waker(context.waker());
+        self.flag.register_waker(context.waker());
+        match self.device.next_with_timeout(&std::time::Duration::ZERO) {
+            Some(buffer_view) => std::task::Poll::Ready(Some(buffer_view.to_owned_buffer())),
+            None => {
+                if self.flag.has_error() {
+                    std::task::Poll::Ready(None)
+                } else {
+                    std::task::Poll::Pending
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::adapters;
 use crate::configuration;
 use crate::device;
+use crate::devices::erc;
 use crate::flag;
 use crate::properties;
 use crate::usb;
@@ -37,6 +38,9 @@ pub struct RateLimiter {
     pub maximum_events_per_period: u32,
 }
 
+pub use erc::EventRateControl;
+pub use erc::EventRateControlMode;
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
 pub struct Configuration {
     pub biases: Biases,
@@ -47,9 +51,27 @@ pub struct Configuration {
     pub enable_external_trigger: bool,
     pub clock: Clock,
     pub rate_limiter: Option<RateLimiter>,
+    pub event_rate_control: Option<EventRateControl>,
     pub enable_output: bool,
 }
 
+impl Configuration {
+    /// validate checks the configuration without accessing the device.
+    ///
+    /// open and update_configuration call it before writing registers,
+    /// hence an invalid configuration is rejected without being partially applied.
+    pub fn validate(&self) -> Result<(), Error> {
+        for offset in 0u32..64u32 {
+            let code = pixel_mask_code(&self.pixel_mask, offset);
+            if code != 0 && (code - 1) / 1280 >= 720 {
+                return Err(Error::PixelMask { code, offset });
+            }
+        }
+        erc_from_configuration(self)?;
+        Ok(())
+    }
+}
+
 pub struct Device {
     handle: std::sync::Arc<rusb::DeviceHandle<rusb::Context>>,
     ring: usb::Ring,
@@ -78,6 +100,12 @@ pub enum Error {
     #[error("unsupported mask code ({code}) for pixel mask {offset}")]
     PixelMask { code: u32, offset: u32 },
 
+    #[error("the event rate control (reference period: {reference_period_us} µs, target: {target_event_rate_kev_s} kev/s) must have a period in the range [1, 200] µs and between 1 and 4194303 events per period")]
+    EventRateControl {
+        reference_period_us: u16,
+        target_event_rate_kev_s: u32,
+    },
+
     #[error("the temperature measurement failed")]
     Temperature,
 
@@ -150,6 +178,7 @@ impl device::Usb for Device {
             enable_external_trigger: true,
             clock: Clock::Internal,
             rate_limiter: None,
+            event_rate_control: None,
             enable_output: true,
         },
     };
@@ -182,8 +211,20 @@ impl device::Usb for Device {
         ))
     }
 
-    fn update_configuration(&self, configuration: Self::Configuration) {
+    fn update_configuration(
+        &self,
+        configuration: Self::Configuration,
+    ) -> Result<(), Self::Error> {
+        configuration.validate()?;
         self.configuration_updater.update(configuration);
+        Ok(())
+    }
+
+    fn subscribe_applied_configurations<Subscriber>(&self, subscriber: Subscriber)
+    where
+        Subscriber: FnMut(&configuration::Applied<Self::Configuration>) -> bool + Send + 'static,
+    {
+        self.configuration_updater.subscribe(subscriber);
     }
 
     fn open<IntoError, IntoWarning>(
@@ -197,8 +238,9 @@ impl device::Usb for Device {
         IntoError: From<Self::Error> + Clone + Send + 'static,
         IntoWarning: From<usb::Overflow> + Clone + Send + 'static,
     {
+        configuration.validate()?;
         let (handle, serial) =
-            Self::handle_from_serial(event_loop.context(), serial)?;
+            Self::handle_from_serial(event_loop.context()?, serial)?;
         usb::assert_control_transfer(
             &handle,
             0x80,                      // request type
@@ -655,42 +697,6 @@ impl device::Usb for Device {
 
         // Event Rate Controler (ERC)
         ErcReserved6000 { value: 0x00155400 }.write(&handle)?;
-        match &configuration.rate_limiter {
-            Some(rate_limiter) => {
-                ErcInDropRateControl {
-                    enable: 1,
-                    reserved_1_32: 0,
-                }
-                .write(&handle)?;
-                ErcReferencePeriod {
-                    duration_us: rate_limiter.reference_period_us as u32,
-                    reserved_10_32: 0,
-                }
-                .write(&handle)?;
-                ErcTdTargetEventRate {
-                    maximum_per_period: rate_limiter.maximum_events_per_period,
-                    reserved_22_32: 0,
-                }
-                .write(&handle)?;
-                ErcControl {
-                    enable: 1,
-                    reserved_1_32: 1,
-                }
-                .write(&handle)?;
-            }
-            None => {
-                ErcInDropRateControl {
-                    enable: 0,
-                    reserved_1_32: 0,
-                }
-                .write(&handle)?;
-                ErcControl {
-                    enable: 0,
-                    reserved_1_32: 1,
-                }
-                .write(&handle)?;
-            }
-        }
         ErcReserved602C { value: 0x00000001 }.write(&handle)?;
         for offset in 0..230 {
             ErcReserved6800 { value: 0x08080808 }
@@ -705,21 +711,10 @@ impl device::Usb for Device {
             .offset(offset)
             .write(&handle)?;
         }
-        ErcTDroppingControl {
-            enable: configuration.rate_limiter.is_some() as u32,
-            reserved_1_32: 0,
-        }
-        .write(&handle)?;
-        ErcHDroppingControl {
-            enable: 0,
-            reserved_1_32: 0,
-        }
-        .write(&handle)?;
-        ErcVDroppingControl {
-            enable: 0,
-            reserved_1_32: 0,
-        }
-        .write(&handle)?;
+        erc::write(
+            &ErcRegisters(&handle),
+            &erc_from_configuration(&configuration)?,
+        )?;
         ErcReserved6000 { value: 0x00155401 }.write(&handle)?;
         EdfReserved7004 {
             reserved_0_10: 0b0111111111,
@@ -871,10 +866,11 @@ impl device::Usb for Device {
                             configuration,
                         )
                     };
+                    let success = result.is_ok();
                     if let Err(error) = result {
                         context.flag.store_error_if_not_set(error);
                     }
-                    context
+                    (context, success)
                 },
             ),
             serial,
@@ -890,6 +886,15 @@ impl device::Usb for Device {
         self.ring.next_with_timeout(timeout)
     }
 
+    fn set_maximum_leases(&self, maximum_leases: usize) {
+        self.ring.set_maximum_leases(maximum_leases);
+    }
+
+    #[cfg(feature = "async")]
+    fn register_waker(&self, waker: &std::task::Waker) {
+        self.ring.register_waker(waker);
+    }
+
     fn backlog(&self) -> usize {
         self.ring.backlog()
     }
@@ -943,6 +948,20 @@ impl device::Usb for Device {
 }
 
 impl Device {
+    /// effective_event_rate_control reads back the event rate controller registers.
+    ///
+    /// The returned target rate reflects the sensor's integer number of events per period,
+    /// which may be lower than the configured target. None means that the controller is disabled.
+    pub fn effective_event_rate_control(
+        &self,
+    ) -> Result<Option<EventRateControl>, Error> {
+        let _guard = self
+            .register_mutex
+            .lock()
+            .expect("register mutex is not poisoned");
+        erc::read(&ErcRegisters(&self.handle))
+    }
+
     pub fn illuminance(&self) -> Result<u32, Error> {
         let lifo_status = LifoStatus::read(&self.handle)?;
         if lifo_status.lifo_ton_valid == 1 {
@@ -1090,11 +1109,68 @@ macro_rules! update_bias {
     };
 }
 
+/// erc_from_configuration returns the event rate controller state.
+///
+/// event_rate_control overrides rate_limiter.
+fn erc_from_configuration(
+    configuration: &Configuration,
+) -> Result<Option<erc::Erc>, Error> {
+    match (
+        &configuration.event_rate_control,
+        &configuration.rate_limiter,
+    ) {
+        (Some(event_rate_control), _) => {
+            erc::Erc::from_event_rate_control(event_rate_control)
+                .map(Some)
+                .ok_or(Error::EventRateControl {
+                    reference_period_us: event_rate_control
+                        .reference_period_us,
+                    target_event_rate_kev_s: event_rate_control
+                        .target_event_rate_kev_s,
+                })
+        }
+        (None, Some(rate_limiter)) => Ok(Some(erc::Erc::from_rate_limiter(
+            rate_limiter.reference_period_us,
+            rate_limiter.maximum_events_per_period,
+        ))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// ErcRegisters gives the shared event rate controller code access to the registers.
+struct ErcRegisters<'a>(&'a rusb::DeviceHandle<rusb::Context>);
+
+impl erc::Registers for ErcRegisters<'_> {
+    type Error = Error;
+
+    fn read(&self, address: u32) -> Result<u32, Self::Error> {
+        read_register(self.0, address)
+    }
+
+    fn write(&self, address: u32, value: u32) -> Result<(), Self::Error> {
+        RuntimeRegister { address, value }.write(self.0)
+    }
+}
+
 fn update_configuration(
     handle: &rusb::DeviceHandle<rusb::Context>,
     previous_configuration: Option<&Configuration>,
     configuration: &Configuration,
 ) -> Result<(), Error> {
+    configuration.validate()?;
+    if let Some(previous_configuration) = previous_configuration {
+        if previous_configuration.rate_limiter != configuration.rate_limiter
+            || previous_configuration.event_rate_control
+                != configuration.event_rate_control
+        {
+            ErcReserved6000 { value: 0x00155400 }.write(handle)?;
+            erc::write(
+                &ErcRegisters(handle),
+                &erc_from_configuration(configuration)?,
+            )?;
+            ErcReserved6000 { value: 0x00155401 }.write(handle)?;
+        }
+    }
     if match previous_configuration {
         Some(previous_configuration) => {
             previous_configuration.enable_output != configuration.enable_output
@@ -1278,18 +1354,7 @@ fn update_configuration(
         None => true,
     } {
         for offset in 0u32..64u32 {
-            let code = if offset < 63 {
-                (((configuration.pixel_mask[(offset / 3) as usize])
-                    >> ((offset % 3) * 21))
-                    & 0x1fffff) as u32
-            } else {
-                let mut code: u32 = 0;
-                for bit in 0..21 {
-                    code |=
-                        ((configuration.pixel_mask[bit] >> 63) << bit) as u32;
-                }
-                code
-            };
+            let code = pixel_mask_code(&configuration.pixel_mask, offset);
             if code == 0 {
                 DigitalMask {
                     x: 0,
@@ -1301,11 +1366,9 @@ fn update_configuration(
                 .offset(offset)
                 .write(handle)?;
             } else {
+                // validate guarantees that (code - 1) / 1280 < 720
                 let x = (code - 1) % 1280;
                 let y = 720 - 1 - (code - 1) / 1280;
-                if x >= 1280 || y >= 720 {
-                    return Err(Error::PixelMask { code, offset });
-                }
                 DigitalMask {
                     x,
                     reserved_11_16: 0,
@@ -1321,6 +1384,20 @@ fn update_configuration(
     Ok(())
 }
 
+/// pixel_mask_code returns the code of a digital mask slot (0 if unused).
+fn pixel_mask_code(pixel_mask: &[u64; 21], offset: u32) -> u32 {
+    if offset < 63 {
+        ((pixel_mask[(offset / 3) as usize] >> ((offset % 3) * 21)) & 0x1fffff)
+            as u32
+    } else {
+        let mut code: u32 = 0;
+        for (bit, slots) in pixel_mask.iter().enumerate() {
+            code |= ((slots >> 63) << bit) as u32;
+        }
+        code
+    }
+}
+
 struct ConfigurationUpdaterContext<IntoError, IntoWarning>
 where
     IntoError: From<Error> + Clone + Send,
@@ -1331,6 +1408,43 @@ where
     register_mutex: std::sync::Arc<std::sync::Mutex<()>>,
 }
 
+fn read_register(
+    handle: &rusb::DeviceHandle<rusb::Context>,
+    address: u32,
+) -> Result<u32, Error> {
+    let buffer = [
+        0x02,
+        0x01,
+        0x01,
+        0x00,
+        0x0c,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        (address & 0xff) as u8,
+        ((address >> 8) & 0xff) as u8,
+        ((address >> 16) & 0xff) as u8,
+        ((address >> 24) & 0xff) as u8,
+        0x01,
+        0x00,
+        0x00,
+        0x00,
+    ];
+    let result = request(handle, &buffer, TIMEOUT)?;
+    if result.len() != buffer.len() {
+        return Err(Error::RegisterReadShortResponse(address));
+    }
+    if result[0..16] != buffer[0..16] {
+        return Err(Error::RegisterReadMismatch(address));
+    }
+    // unwrap: slice has the right number of bytes
+    Ok(u32::from_le_bytes(result[16..20].try_into().unwrap()))
+}
+
 struct RuntimeRegister {
     address: u32,
     value: u32,
@@ -1425,37 +1539,7 @@ macro_rules! register {
         impl $name {
             #[allow(dead_code)]
             fn read(handle: &rusb::DeviceHandle<rusb::Context>) -> Result<Self, Error> {
-                let buffer = [
-                    0x02,
-                    0x01,
-                    0x01,
-                    0x00,
-                    0x0c,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    ($address & 0xff) as u8,
-                    (($address >> 8) & 0xff) as u8,
-                    (($address >> 16) & 0xff) as u8,
-                    (($address >> 24) & 0xff) as u8,
-                    0x01,
-                    0x00,
-                    0x00,
-                    0x00,
-                ];
-                let result = request(handle, &buffer, TIMEOUT)?;
-                if result.len() != buffer.len() {
-                    return Err(Error::RegisterReadShortResponse($address));
-                }
-                if result[0..16] != buffer[0..16] {
-                    return Err(Error::RegisterReadMismatch($address));
-                }
-                // unwrap: slice has the right number of bytes
-                let value = u32::from_le_bytes(result[16..20].try_into().unwrap());
+                let value = read_register(handle, $address)?;
                 Ok(Self {
                     $(
                         $subname: (value >> $substart) & (((1u64 << ($subend - $substart)) - 1) as u32),
@@ -1714,35 +1798,7 @@ register! { Unknown1104, 0x1104, { value: 0..32 } }
 register! { TdRoiX, 0x2000, { value: 0..32 } }
 register! { TdRoiY, 0x4000, { value: 0..32 } }
 register! { ErcReserved6000, 0x6000, { value: 0..32 } }
-register! { ErcInDropRateControl, 0x6004, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
-register! { ErcReferencePeriod, 0x6008, {
-    duration_us: 0..10,
-    reserved_10_32: 10..32,
-} }
-register! { ErcTdTargetEventRate, 0x600C, {
-    maximum_per_period: 0..22,
-    reserved_22_32: 22..32,
-} }
-register! { ErcControl, 0x6028, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
 register! { ErcReserved602C, 0x602C, { value: 0..32 } }
-register! { ErcTDroppingControl, 0x6050, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
-register! { ErcHDroppingControl, 0x6060, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
-register! { ErcVDroppingControl, 0x6070, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
 register! { TDropLut, 0x6400, { value: 0..32 } }
 register! { ErcReserved6800, 0x6800, { value: 0..32 } }
 register! { EdfPipelineControl, 0x7000, { value: 0..32 } }
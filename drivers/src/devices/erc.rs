@@ -0,0 +1,143 @@
+/// EventRateControlMode selects how the event rate controller drops events.
+///
+/// Temporal drops events uniformly over time, Horizontal drops rows and Vertical drops columns.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum EventRateControlMode {
+    Temporal = 0,
+    Horizontal = 1,
+    Vertical = 2,
+}
+
+/// EventRateControl overrides rate_limiter when set.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct EventRateControl {
+    pub mode: EventRateControlMode,
+
+    /// reference_period_us must be in the range [1, 200].
+    pub reference_period_us: u16,
+
+    /// target_event_rate_kev_s is in thousands of events per second (1000 is 1 Mev/s).
+    pub target_event_rate_kev_s: u32,
+}
+
+/// Registers gives the event rate controller code access to a camera's registers.
+///
+/// The Prophesee EVK4 and the CenturyArks SilkyEvCamHD use the same sensor (IMX636),
+/// hence the same event rate controller registers.
+pub(crate) trait Registers {
+    type Error;
+
+    fn read(&self, address: u32) -> Result<u32, Self::Error>;
+
+    fn write(&self, address: u32, value: u32) -> Result<(), Self::Error>;
+}
+
+const IN_DROP_RATE_CONTROL: u32 = 0x6004;
+const REFERENCE_PERIOD: u32 = 0x6008;
+const TD_TARGET_EVENT_RATE: u32 = 0x600C;
+const CONTROL: u32 = 0x6028;
+const T_DROPPING_CONTROL: u32 = 0x6050;
+const H_DROPPING_CONTROL: u32 = 0x6060;
+const V_DROPPING_CONTROL: u32 = 0x6070;
+
+/// Erc is the event rate controller state programmed for a configuration.
+pub(crate) struct Erc {
+    mode: EventRateControlMode,
+    reference_period_us: u32,
+    maximum_events_per_period: u32,
+}
+
+impl Erc {
+    /// from_event_rate_control returns None if the period or the number of events per period is out of range.
+    pub(crate) fn from_event_rate_control(event_rate_control: &EventRateControl) -> Option<Self> {
+        let maximum_events_per_period = event_rate_control.target_event_rate_kev_s as u64
+            * event_rate_control.reference_period_us as u64
+            / 1000;
+        if event_rate_control.reference_period_us == 0
+            || event_rate_control.reference_period_us > 200
+            || maximum_events_per_period == 0
+            || maximum_events_per_period >= (1 << 22)
+        {
+            None
+        } else {
+            Some(Self {
+                mode: event_rate_control.mode.clone(),
+                reference_period_us: event_rate_control.reference_period_us as u32,
+                maximum_events_per_period: maximum_events_per_period as u32,
+            })
+        }
+    }
+
+    /// from_rate_limiter programs the temporal mode with a fixed number of events per period.
+    pub(crate) fn from_rate_limiter(
+        reference_period_us: u16,
+        maximum_events_per_period: u32,
+    ) -> Self {
+        Self {
+            mode: EventRateControlMode::Temporal,
+            reference_period_us: reference_period_us as u32,
+            maximum_events_per_period,
+        }
+    }
+}
+
+/// write enables (Some) or disables (None) the event rate controller.
+pub(crate) fn write<R: Registers>(registers: &R, erc: &Option<Erc>) -> Result<(), R::Error> {
+    match erc {
+        Some(erc) => {
+            registers.write(IN_DROP_RATE_CONTROL, 1)?;
+            registers.write(REFERENCE_PERIOD, erc.reference_period_us & 0x3ff)?;
+            registers.write(
+                TD_TARGET_EVENT_RATE,
+                erc.maximum_events_per_period & 0x3fffff,
+            )?;
+            registers.write(CONTROL, 1 | (1 << 1))?;
+        }
+        None => {
+            registers.write(IN_DROP_RATE_CONTROL, 0)?;
+            registers.write(CONTROL, 1 << 1)?;
+        }
+    }
+    let mode = erc.as_ref().map(|erc| &erc.mode);
+    registers.write(
+        T_DROPPING_CONTROL,
+        (mode == Some(&EventRateControlMode::Temporal)) as u32,
+    )?;
+    registers.write(
+        H_DROPPING_CONTROL,
+        (mode == Some(&EventRateControlMode::Horizontal)) as u32,
+    )?;
+    registers.write(
+        V_DROPPING_CONTROL,
+        (mode == Some(&EventRateControlMode::Vertical)) as u32,
+    )?;
+    Ok(())
+}
+
+/// read returns the event rate controller's state, None means that the controller is disabled.
+///
+/// The returned target rate reflects the sensor's integer number of events per period,
+/// which may be lower than the configured target.
+pub(crate) fn read<R: Registers>(registers: &R) -> Result<Option<EventRateControl>, R::Error> {
+    if registers.read(CONTROL)? & 1 == 0 {
+        return Ok(None);
+    }
+    let mode = if registers.read(H_DROPPING_CONTROL)? & 1 == 1 {
+        EventRateControlMode::Horizontal
+    } else if registers.read(V_DROPPING_CONTROL)? & 1 == 1 {
+        EventRateControlMode::Vertical
+    } else {
+        EventRateControlMode::Temporal
+    };
+    let reference_period_us = registers.read(REFERENCE_PERIOD)? & 0x3ff;
+    let maximum_per_period = registers.read(TD_TARGET_EVENT_RATE)? & 0x3fffff;
+    Ok(Some(EventRateControl {
+        mode,
+        reference_period_us: reference_period_us as u16,
+        target_event_rate_kev_s: if reference_period_us == 0 {
+            0
+        } else {
+            (maximum_per_period as u64 * 1000 / reference_period_us as u64) as u32
+        },
+    }))
+}
@@ -0,0 +1,495 @@
+use crate::adapters;
+use crate::configuration;
+use crate::device;
+use crate::flag;
+use crate::properties;
+use crate::usb;
+
+use device::Usb;
+
+/// Comma-separated list of mock serials, for instance "MOCK0,MOCK1".
+///
+/// Mock devices are only listed and opened if this variable is set.
+pub const SERIALS_VARIABLE: &str = "NEUROMORPHIC_DRIVERS_MOCK";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum Source {
+    Synthetic {
+        events_per_second: u32,
+        trigger_period_us: Option<u32>,
+    },
+    File {
+        path: String,
+        repeat: bool,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct Configuration {
+    pub source: Source,
+    pub packet_duration_us: u32,
+    pub enable_output: bool,
+}
+
+impl Configuration {
+    /// validate checks that the file source (if any) can be opened.
+    ///
+    /// update_configuration calls it before passing the configuration to the updater thread.
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Source::File { path, .. } = &self.source {
+            let metadata = std::fs::File::open(path)
+                .and_then(|file| file.metadata())
+                .map_err(|error| Error::File {
+                    path: path.clone(),
+                    message: error.to_string(),
+                })?;
+            if !metadata.is_file() {
+                return Err(Error::File {
+                    path: path.clone(),
+                    message: "not a file".to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Device {
+    ring: usb::VirtualRing,
+    configuration_updater: configuration::Updater<Configuration>,
+    serial: String,
+    running: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Error {
+    #[error(transparent)]
+    Usb(#[from] usb::Error),
+
+    #[error("reading \"{path}\" failed ({message})")]
+    File { path: String, message: String },
+}
+
+impl From<rusb::Error> for Error {
+    fn from(error: rusb::Error) -> Self {
+        usb::Error::from(error).into()
+    }
+}
+
+pub const PROPERTIES: properties::Camera<Configuration> = Device::PROPERTIES;
+pub const DEFAULT_CONFIGURATION: Configuration = Configuration {
+    source: Source::Synthetic {
+        events_per_second: 1000000,
+        trigger_period_us: None,
+    },
+    packet_duration_us: 1000,
+    enable_output: true,
+};
+pub const DEFAULT_USB_CONFIGURATION: usb::Configuration = Device::DEFAULT_USB_CONFIGURATION;
+pub fn open<IntoError, IntoWarning>(
+    serial: &Option<&str>,
+    configuration: Configuration,
+    usb_configuration: &usb::Configuration,
+    event_loop: std::sync::Arc<usb::EventLoop>,
+    flag: flag::Flag<IntoError, IntoWarning>,
+) -> Result<Device, Error>
+where
+    IntoError: From<Error> + Clone + Send + 'static,
+    IntoWarning: From<usb::Overflow> + Clone + Send + 'static,
+{
+    Device::open(serial, configuration, usb_configuration, event_loop, flag)
+}
+
+fn serials() -> Vec<String> {
+    match std::env::var(SERIALS_VARIABLE) {
+        Ok(serials) => serials
+            .split(',')
+            .map(|serial| serial.trim())
+            .filter(|serial| !serial.is_empty())
+            .map(|serial| serial.to_owned())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+impl device::Usb for Device {
+    type Adapter = adapters::evt3::Adapter;
+
+    type Configuration = Configuration;
+
+    type Error = Error;
+
+    type Properties = properties::Camera<Self::Configuration>;
+
+    const VENDOR_ID: u16 = 0x0000;
+
+    const PRODUCT_ID: u16 = 0x0000;
+
+    const PROPERTIES: Self::Properties = Self::Properties {
+        name: "Mock",
+        width: 1280,
+        height: 720,
+        default_configuration: DEFAULT_CONFIGURATION,
+    };
+
+    const DEFAULT_USB_CONFIGURATION: usb::Configuration = usb::Configuration {
+        buffer_length: 1 << 17,
+        ring_length: 1 << 12,
+        transfer_queue_length: 1 << 5,
+        allow_dma: false,
+    };
+
+    fn read_serial(_handle: &mut rusb::DeviceHandle<rusb::Context>) -> rusb::Result<String> {
+        Err(rusb::Error::NotSupported)
+    }
+
+    fn list_devices(
+        _devices: Option<&rusb::DeviceList<rusb::Context>>,
+    ) -> rusb::Result<Vec<device::ListedDevice>> {
+        Ok(serials()
+            .into_iter()
+            .map(|serial| device::ListedDevice {
+                speed: usb::Speed::Unknown,
+                serial: Ok(serial),
+            })
+            .collect())
+    }
+
+    fn update_configuration(&self, configuration: Self::Configuration) -> Result<(), Self::Error> {
+        configuration.validate()?;
+        self.configuration_updater.update(configuration);
+        Ok(())
+    }
+
+    fn subscribe_applied_configurations<Subscriber>(&self, subscriber: Subscriber)
+    where
+        Subscriber: FnMut(&configuration::Applied<Self::Configuration>) -> bool + Send + 'static,
+    {
+        self.configuration_updater.subscribe(subscriber);
+    }
+
+    fn open<IntoError, IntoWarning>(
+        serial: &Option<&str>,
+        configuration: Self::Configuration,
+        usb_configuration: &usb::Configuration,
+        _event_loop: std::sync::Arc<usb::EventLoop>,
+        flag: flag::Flag<IntoError, IntoWarning>,
+    ) -> Result<Self, Self::Error>
+    where
+        IntoError: From<Self::Error> + Clone + Send + 'static,
+        IntoWarning: From<usb::Overflow> + Clone + Send + 'static,
+    {
+        let serials = serials();
+        let serial = match serial {
+            Some(serial) => {
+                if serials.iter().any(|candidate| candidate == serial) {
+                    (*serial).to_owned()
+                } else {
+                    return Err(usb::Error::Serial((*serial).to_owned()).into());
+                }
+            }
+            None => match serials.into_iter().next() {
+                Some(serial) => serial,
+                None => return Err(usb::Error::Device.into()),
+            },
+        };
+        let generator = Generator::new(&configuration, seed(&serial))?;
+        let warning_flag = flag.clone();
+        let (ring, writer) = usb::VirtualRing::new(usb_configuration, move |overflow| {
+            warning_flag.store_warning_if_not_set(overflow);
+        })?;
+        let shared_configuration =
+            std::sync::Arc::new(std::sync::Mutex::new(configuration.clone()));
+        let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let thread_running = running.clone();
+        let thread_configuration = shared_configuration.clone();
+        let thread_flag = flag.clone();
+        let thread_seed = seed(&serial);
+        Ok(Device {
+            ring,
+            configuration_updater: configuration::Updater::new(
+                configuration,
+                shared_configuration,
+                |shared_configuration, _previous_configuration, configuration| {
+                    *shared_configuration
+                        .lock()
+                        .expect("configuration mutex is not poisoned") = configuration.clone();
+                    (shared_configuration, true)
+                },
+            ),
+            serial,
+            running,
+            thread: Some(std::thread::spawn(move || {
+                let mut generator = generator;
+                let mut bytes = Vec::new();
+                let start = std::time::Instant::now();
+                while thread_running.load(std::sync::atomic::Ordering::Acquire) {
+                    let configuration = thread_configuration
+                        .lock()
+                        .expect("configuration mutex is not poisoned")
+                        .clone();
+                    std::thread::sleep(std::time::Duration::from_micros(
+                        configuration.packet_duration_us.max(1) as u64,
+                    ));
+                    if generator.source != configuration.source {
+                        match Generator::new(&configuration, thread_seed) {
+                            Ok(mut new_generator) => {
+                                new_generator.t = generator.t;
+                                generator = new_generator;
+                            }
+                            Err(error) => {
+                                // the error is fatal, retrying on every packet would not help
+                                thread_flag.store_error_if_not_set(error);
+                                break;
+                            }
+                        }
+                    }
+                    bytes.clear();
+                    generator.fill(start.elapsed().as_micros() as u64, &mut bytes);
+                    if configuration.enable_output {
+                        let buffer_length = writer.buffer_length() & !1;
+                        for chunk in bytes.chunks(buffer_length.max(2)) {
+                            writer.write(chunk);
+                        }
+                    }
+                }
+            })),
+        })
+    }
+
+    fn next_with_timeout(&self, timeout: &std::time::Duration) -> Option<usb::BufferView<'_>> {
+        self.ring.next_with_timeout(timeout)
+    }
+
+    fn set_maximum_leases(&self, maximum_leases: usize) {
+        self.ring.set_maximum_leases(maximum_leases);
+    }
+
+    #[cfg(feature = "async")]
+    fn register_waker(&self, waker: &std::task::Waker) {
+        self.ring.register_waker(waker);
+    }
+
+    fn backlog(&self) -> usize {
+        self.ring.backlog()
+    }
+
+    fn clutch(&self) -> usb::Clutch {
+        self.ring.clutch()
+    }
+
+    fn serial(&self) -> String {
+        self.serial.clone()
+    }
+
+    fn chip_firmware_configuration(&self) -> Self::Configuration {
+        DEFAULT_CONFIGURATION
+    }
+
+    fn speed(&self) -> usb::Speed {
+        usb::Speed::Unknown
+    }
+
+    fn adapter(&self) -> Self::Adapter {
+        Self::Adapter::from_dimensions(Self::PROPERTIES.width, Self::PROPERTIES.height)
+    }
+
+    fn temperature_celsius(&self) -> Result<device::TemperatureCelsius, Self::Error> {
+        Ok(device::TemperatureCelsius(25.0))
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        self.running
+            .store(false, std::sync::atomic::Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            // unwrap: not joining self
+            thread.join().unwrap();
+        }
+    }
+}
+
+fn seed(serial: &str) -> u64 {
+    // FNV-1a
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in serial.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash | 1
+}
+
+enum State {
+    Synthetic {
+        state: u64,
+        remainder: u64,
+        next_trigger_t: u64,
+        trigger_rising: bool,
+        encoder: adapters::evt3::Encoder,
+    },
+    File {
+        bytes: Vec<u8>,
+        offset: usize,
+        adapter: adapters::evt3::Adapter,
+        origin: Option<(u64, u64)>,
+    },
+}
+
+struct Generator {
+    source: Source,
+    state: State,
+    t: u64,
+}
+
+impl Generator {
+    fn new(configuration: &Configuration, seed: u64) -> Result<Self, Error> {
+        Ok(Self {
+            source: configuration.source.clone(),
+            state: match &configuration.source {
+                Source::Synthetic { .. } => State::Synthetic {
+                    state: seed,
+                    remainder: 0,
+                    next_trigger_t: 0,
+                    trigger_rising: true,
+                    encoder: adapters::evt3::Encoder::from_dimensions(
+                        Device::PROPERTIES.width,
+                        Device::PROPERTIES.height,
+                    ),
+                },
+                Source::File { path, .. } => State::File {
+                    bytes: read_evt3(path)?,
+                    offset: 0,
+                    adapter: adapters::evt3::Adapter::from_dimensions(
+                        Device::PROPERTIES.width,
+                        Device::PROPERTIES.height,
+                    ),
+                    origin: None,
+                },
+            },
+            t: 0,
+        })
+    }
+
+    /// fill appends the EVT3 words between the previous call and t to bytes.
+    fn fill(&mut self, t: u64, bytes: &mut Vec<u8>) {
+        if t <= self.t {
+            return;
+        }
+        let previous_t = self.t;
+        self.t = t;
+        match (&self.source, &mut self.state) {
+            (
+                Source::Synthetic {
+                    events_per_second,
+                    trigger_period_us,
+                },
+                State::Synthetic {
+                    state,
+                    remainder,
+                    next_trigger_t,
+                    trigger_rising,
+                    encoder,
+                },
+            ) => {
+                let numerator = (t - previous_t) * (*events_per_second as u64) + *remainder;
+                let count = numerator / 1_000_000;
+                *remainder = numerator % 1_000_000;
+                for index in 0..count {
+                    let event_t = previous_t + (index * (t - previous_t)) / count;
+                    if let Some(trigger_period_us) = trigger_period_us {
+                        while *next_trigger_t <= event_t {
+                            encoder.trigger_event(
+                                neuromorphic_types::TriggerEvent {
+                                    t: *next_trigger_t,
+                                    id: 0,
+                                    polarity: if *trigger_rising {
+                                        neuromorphic_types::TriggerPolarity::Rising
+                                    } else {
+                                        neuromorphic_types::TriggerPolarity::Falling
+                                    },
+                                },
+                                bytes,
+                            );
+                            *trigger_rising = !*trigger_rising;
+                            *next_trigger_t += (*trigger_period_us).max(1) as u64;
+                        }
+                    }
+                    *state ^= *state << 13;
+                    *state ^= *state >> 7;
+                    *state ^= *state << 17;
+                    encoder.dvs_event(
+                        neuromorphic_types::DvsEvent {
+                            t: event_t,
+                            x: ((*state >> 16) % Device::PROPERTIES.width as u64) as u16,
+                            y: ((*state >> 32) % Device::PROPERTIES.height as u64) as u16,
+                            polarity: if (*state & 1) == 1 {
+                                neuromorphic_types::DvsPolarity::On
+                            } else {
+                                neuromorphic_types::DvsPolarity::Off
+                            },
+                        },
+                        bytes,
+                    );
+                }
+                encoder.flush(bytes);
+            }
+            (
+                Source::File { repeat, .. },
+                State::File {
+                    bytes: file_bytes,
+                    offset,
+                    adapter,
+                    origin,
+                },
+            ) => loop {
+                if *offset >= file_bytes.len() {
+                    if *repeat && !file_bytes.is_empty() {
+                        *offset = 0;
+                        *adapter = adapters::evt3::Adapter::from_dimensions(
+                            Device::PROPERTIES.width,
+                            Device::PROPERTIES.height,
+                        );
+                        *origin = None;
+                    } else {
+                        break;
+                    }
+                }
+                // origin maps the file's first timestamp to the mock clock
+                if let Some((file_t, mock_t)) = origin {
+                    if adapter.current_t().saturating_sub(*file_t) + *mock_t > t {
+                        break;
+                    }
+                }
+                let end = (*offset + 1024).min(file_bytes.len());
+                let chunk = &file_bytes[*offset..end];
+                adapter.consume(chunk);
+                bytes.extend_from_slice(chunk);
+                *offset = end;
+                if origin.is_none() {
+                    *origin = Some((adapter.current_t(), previous_t));
+                }
+            },
+            _ => unreachable!("the generator state always matches its source"),
+        }
+    }
+}
+
+fn read_evt3(path: &str) -> Result<Vec<u8>, Error> {
+    let bytes = std::fs::read(path).map_err(|error| Error::File {
+        path: path.to_owned(),
+        message: error.to_string(),
+    })?;
+    // skip the ASCII header of Metavision RAW files (lines that start with '%')
+    let mut offset = 0;
+    while offset < bytes.len() && bytes[offset] == b'%' {
+        match bytes[offset..].iter().position(|byte| *byte == b'\n') {
+            Some(position) => offset += position + 1,
+            None => offset = bytes.len(),
+        }
+    }
+    let mut bytes = bytes[offset..].to_vec();
+    bytes.truncate(bytes.len() & !1);
+    Ok(bytes)
+}
@@ -142,8 +142,16 @@ impl device::Usb for Device {
         ))
     }
 
-    fn update_configuration(&self, configuration: Self::Configuration) {
+    fn update_configuration(&self, configuration: Self::Configuration) -> Result<(), Self::Error> {
         self.configuration_updater.update(configuration);
+        Ok(())
+    }
+
+    fn subscribe_applied_configurations<Subscriber>(&self, subscriber: Subscriber)
+    where
+        Subscriber: FnMut(&configuration::Applied<Self::Configuration>) -> bool + Send + 'static,
+    {
+        self.configuration_updater.subscribe(subscriber);
     }
 
     fn open<IntoError, IntoWarning>(
@@ -157,7 +165,7 @@ impl device::Usb for Device {
         IntoError: From<Self::Error> + Clone + Send + 'static,
         IntoWarning: From<crate::usb::Overflow> + Clone + Send + 'static,
     {
-        let (handle, serial) = Self::handle_from_serial(event_loop.context(), serial)?;
+        let (handle, serial) = Self::handle_from_serial(event_loop.context()?, serial)?;
         std::thread::sleep(std::time::Duration::from_millis(150));
         request(
             &handle,
@@ -380,14 +388,16 @@ impl device::Usb for Device {
                 configuration,
                 ConfigurationUpdaterContext { handle, flag },
                 |context, previous_configuration, configuration| {
-                    if let Err(error) = update_configuration(
+                    let result = update_configuration(
                         &context.handle,
                         Some(previous_configuration),
                         configuration,
-                    ) {
+                    );
+                    let success = result.is_ok();
+                    if let Err(error) = result {
                         context.flag.store_error_if_not_set(error);
                     }
-                    context
+                    (context, success)
                 },
             ),
             serial,
@@ -398,6 +408,15 @@ impl device::Usb for Device {
         self.ring.next_with_timeout(timeout)
     }
 
+    fn set_maximum_leases(&self, maximum_leases: usize) {
+        self.ring.set_maximum_leases(maximum_leases);
+    }
+
+    #[cfg(feature = "async")]
+    fn register_waker(&self, waker: &std::task::Waker) {
+        self.ring.register_waker(waker);
+    }
+
     fn backlog(&self) -> usize {
         self.ring.backlog()
     }
@@ -1,6 +1,7 @@
 use crate::adapters;
 use crate::configuration;
 use crate::device;
+use crate::devices::erc;
 use crate::flag;
 use crate::properties;
 use crate::usb;
@@ -37,19 +38,165 @@ pub struct RateLimiter {
     pub maximum_events_per_period: u32,
 }
 
+pub use erc::EventRateControl;
+pub use erc::EventRateControlMode;
+
+/// BurstFilterMode selects the on-chip filter applied to bursts (same-polarity events at a pixel).
+///
+/// Trail keeps the first event of each burst, StcCutTrail keeps the second event only,
+/// and StcKeepTrail drops the first event and keeps the others.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum BurstFilterMode {
+    Trail = 0,
+    StcCutTrail = 1,
+    StcKeepTrail = 2,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct BurstFilter {
+    pub mode: BurstFilterMode,
+
+    /// threshold_us must be in the range [1, 524287].
+    pub threshold_us: u32,
+}
+
+/// BandStop drops the events whose frequency is in the band, BandPass keeps only those.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum AntiFlickerMode {
+    BandStop = 0,
+    BandPass = 1,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct AntiFlicker {
+    /// low_frequency and high_frequency are in Hz, in the range [50, 520].
+    pub low_frequency: u16,
+    pub high_frequency: u16,
+
+    /// duty_cycle is in percent, in the range [1, 100].
+    pub duty_cycle: u8,
+    pub mode: AntiFlickerMode,
+}
+
+/// AreaCounters splits the sensor into a 4 × 4 grid of regions whose events are counted on-chip.
+///
+/// Region (row, column) covers x[column] <= x < x[column + 1] and y[row] <= y < y[row + 1],
+/// in event coordinates. Boundaries must be sorted; equal boundaries create empty regions.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct AreaCounters {
+    pub x: [u16; 5],
+    pub y: [u16; 5],
+}
+
+/// RoiWindow is a rectangle in event coordinates.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct RoiWindow {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Include keeps only the events inside the windows, Exclude drops them.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum RoiMode {
+    Include = 0,
+    Exclude = 1,
+}
+
+/// Roi overrides x_mask, y_mask and mask_intersection_only when set.
+///
+/// A single Include window uses the sensor's window registers. Other cases are programmed
+/// with row and column masks, which can only represent unions of windows that form a grid
+/// (the union must be the product of the windows' x ranges and y ranges).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct Roi {
+    pub mode: RoiMode,
+    pub windows: Vec<RoiWindow>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct Pixel {
+    pub x: u16,
+    pub y: u16,
+}
+
+/// Maximum number of pixels masked by the digital mask (pixel_mask and masked_pixels combined).
+pub const DIGITAL_MASK_CAPACITY: usize = 64;
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
 pub struct Configuration {
     pub biases: Biases,
     pub x_mask: [u64; 20],
     pub y_mask: [u64; 12],
     pub pixel_mask: [u64; 21],
+
+    /// masked_pixels are added to pixel_mask, in event coordinates.
+    pub masked_pixels: Vec<Pixel>,
     pub mask_intersection_only: bool,
+    pub roi: Option<Roi>,
+
+    /// digital_crop drops the events outside the window, after the analog front end.
+    pub digital_crop: Option<RoiWindow>,
     pub enable_external_trigger: bool,
     pub clock: Clock,
     pub rate_limiter: Option<RateLimiter>,
+    pub event_rate_control: Option<EventRateControl>,
+    pub burst_filter: Option<BurstFilter>,
+    pub anti_flicker: Option<AntiFlicker>,
+    pub area_counters: Option<AreaCounters>,
     pub enable_output: bool,
 }
 
+impl Configuration {
+    /// validate checks the configuration without accessing the device.
+    ///
+    /// open and update_configuration call it before writing registers, hence an invalid
+    /// configuration is rejected without being partially applied.
+    pub fn validate(&self) -> Result<(), Error> {
+        digital_mask(self)?;
+        Masks::from_configuration(self)?;
+        if let Some(digital_crop) = &self.digital_crop {
+            if !window_in_sensor(digital_crop) {
+                return Err(Error::DigitalCrop(digital_crop.clone()));
+            }
+        }
+        erc_from_configuration(self)?;
+        if let Some(burst_filter) = &self.burst_filter {
+            if !(1..1 << 19).contains(&burst_filter.threshold_us) {
+                return Err(Error::BurstFilterThreshold(burst_filter.threshold_us));
+            }
+        }
+        if let Some(anti_flicker) = &self.anti_flicker {
+            if anti_flicker.low_frequency < 50
+                || anti_flicker.high_frequency > 520
+                || anti_flicker.low_frequency >= anti_flicker.high_frequency
+            {
+                return Err(Error::AntiFlickerFrequencies {
+                    low_frequency: anti_flicker.low_frequency,
+                    high_frequency: anti_flicker.high_frequency,
+                });
+            }
+            if !(1..=100).contains(&anti_flicker.duty_cycle) {
+                return Err(Error::AntiFlickerDutyCycle(anti_flicker.duty_cycle));
+            }
+        }
+        if let Some(area_counters) = &self.area_counters {
+            if !area_counters.x.windows(2).all(|pair| pair[0] <= pair[1])
+                || !area_counters.y.windows(2).all(|pair| pair[0] <= pair[1])
+                || area_counters.x[4] > Device::PROPERTIES.width
+                || area_counters.y[4] > Device::PROPERTIES.height
+            {
+                return Err(Error::AreaCounters {
+                    x: area_counters.x,
+                    y: area_counters.y,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
 pub struct Device {
     handle: std::sync::Arc<rusb::DeviceHandle<rusb::Context>>,
     ring: usb::Ring,
@@ -57,6 +204,7 @@ pub struct Device {
     serial: String,
     chip_firmware_configuration: Configuration,
     register_mutex: std::sync::Arc<std::sync::Mutex<()>>,
+    area_counts: std::sync::Arc<std::sync::Mutex<AreaCounts>>,
 }
 
 #[derive(thiserror::Error, Debug, Clone)]
@@ -73,9 +221,55 @@ pub enum Error {
     #[error("bytes mismatch while reading register {0}")]
     RegisterReadMismatch(u32),
 
-    #[error("unsupported mask code ({code}) for pixel mask {offset}")]
+    #[error("the pixel mask code {code} (slot {offset}) is outside the sensor")]
     PixelMask { code: u32, offset: u32 },
 
+    #[error("the masked pixel ({x}, {y}) is outside the sensor (1280 x 720)")]
+    MaskedPixel { x: u16, y: u16 },
+
+    #[error("{pixels} pixels are masked but the digital mask has only {capacity} slots (pixel_mask and masked_pixels combined)")]
+    DigitalMaskCapacity { pixels: usize, capacity: usize },
+
+    #[error("the digital crop window {0:?} is empty or outside the sensor (1280 x 720)")]
+    DigitalCrop(RoiWindow),
+
+    #[error("the event rate control (reference period: {reference_period_us} µs, target: {target_event_rate_kev_s} kev/s) must have a period in the range [1, 200] µs and between 1 and 4194303 events per period")]
+    EventRateControl {
+        reference_period_us: u16,
+        target_event_rate_kev_s: u32,
+    },
+
+    #[error("the burst filter threshold ({0} µs) must be in the range [1, 524287]")]
+    BurstFilterThreshold(u32),
+
+    #[error(
+        "the anti-flicker band [{low_frequency}, {high_frequency}] Hz must be within [50, 520] Hz"
+    )]
+    AntiFlickerFrequencies {
+        low_frequency: u16,
+        high_frequency: u16,
+    },
+
+    #[error("the anti-flicker duty cycle ({0} %) must be in the range [1, 100]")]
+    AntiFlickerDutyCycle(u8),
+
+    #[error("the anti-flicker memory initialization timed out")]
+    AntiFlickerInitialization,
+
+    #[error(
+        "the area counters boundaries (x: {x:?}, y: {y:?}) must be sorted and within the sensor"
+    )]
+    AreaCounters { x: [u16; 5], y: [u16; 5] },
+
+    #[error("the ROI window {0:?} is empty or outside the sensor (1280 x 720)")]
+    RoiWindow(RoiWindow),
+
+    #[error("the ROI windows cannot be represented with row and column masks")]
+    RoiWindows,
+
+    #[error("the ROI mode is Include but there are no windows (all the events would be dropped)")]
+    RoiEmpty,
+
     #[error("the temperature measurement failed")]
     Temperature,
 
@@ -90,7 +284,38 @@ impl From<rusb::Error> for Error {
 }
 
 pub const PROPERTIES: properties::Camera<Configuration> = Device::PROPERTIES;
-pub const DEFAULT_CONFIGURATION: Configuration = Device::PROPERTIES.default_configuration;
+pub const DEFAULT_CONFIGURATION: Configuration = Configuration {
+    biases: Biases {
+        pr: 0x7C,
+        fo: 0x53,
+        hpf: 0x00,
+        diff_on: 0x66,
+        diff: 0x4D,
+        diff_off: 0x49,
+        inv: 0x5B,
+        refr: 0x14,
+        reqpuy: 0x8C,
+        reqpux: 0x7C,
+        sendreqpdy: 0x94,
+        unknown_1: 0x74,
+        unknown_2: 0x51,
+    },
+    x_mask: [0; 20],
+    y_mask: [0; 12],
+    pixel_mask: [0; 21],
+    masked_pixels: Vec::new(),
+    mask_intersection_only: false,
+    roi: None,
+    digital_crop: None,
+    enable_external_trigger: true,
+    clock: Clock::Internal,
+    rate_limiter: None,
+    event_rate_control: None,
+    burst_filter: None,
+    anti_flicker: None,
+    area_counters: None,
+    enable_output: true,
+};
 pub const DEFAULT_USB_CONFIGURATION: usb::Configuration = Device::DEFAULT_USB_CONFIGURATION;
 pub fn open<IntoError, IntoWarning>(
     serial: &Option<&str>,
@@ -123,31 +348,7 @@ impl device::Usb for Device {
         name: "Prophesee EVK4",
         width: 1280,
         height: 720,
-        default_configuration: Self::Configuration {
-            biases: Biases {
-                pr: 0x7C,
-                fo: 0x53,
-                hpf: 0x00,
-                diff_on: 0x66,
-                diff: 0x4D,
-                diff_off: 0x49,
-                inv: 0x5B,
-                refr: 0x14,
-                reqpuy: 0x8C,
-                reqpux: 0x7C,
-                sendreqpdy: 0x94,
-                unknown_1: 0x74,
-                unknown_2: 0x51,
-            },
-            x_mask: [0; 20],
-            y_mask: [0; 12],
-            pixel_mask: [0; 21],
-            mask_intersection_only: false,
-            enable_external_trigger: true,
-            clock: Clock::Internal,
-            rate_limiter: None,
-            enable_output: true,
-        },
+        default_configuration: DEFAULT_CONFIGURATION,
     };
 
     const DEFAULT_USB_CONFIGURATION: usb::Configuration = usb::Configuration {
@@ -172,8 +373,17 @@ impl device::Usb for Device {
         ))
     }
 
-    fn update_configuration(&self, configuration: Self::Configuration) {
+    fn update_configuration(&self, configuration: Self::Configuration) -> Result<(), Self::Error> {
+        configuration.validate()?;
         self.configuration_updater.update(configuration);
+        Ok(())
+    }
+
+    fn subscribe_applied_configurations<Subscriber>(&self, subscriber: Subscriber)
+    where
+        Subscriber: FnMut(&configuration::Applied<Self::Configuration>) -> bool + Send + 'static,
+    {
+        self.configuration_updater.subscribe(subscriber);
     }
 
     fn open<IntoError, IntoWarning>(
@@ -187,7 +397,8 @@ impl device::Usb for Device {
         IntoError: From<Self::Error> + Clone + Send + 'static,
         IntoWarning: From<usb::Overflow> + Clone + Send + 'static,
     {
-        let (handle, serial) = Self::handle_from_serial(event_loop.context(), serial)?;
+        configuration.validate()?;
+        let (handle, serial) = Self::handle_from_serial(event_loop.context()?, serial)?;
         usb::assert_control_transfer(
             &handle,
             0x80,
@@ -631,42 +842,6 @@ impl device::Usb for Device {
 
         // Event Rate Controler (ERC)
         ErcReserved6000 { value: 0x00155400 }.write(&handle)?;
-        match &configuration.rate_limiter {
-            Some(rate_limiter) => {
-                ErcInDropRateControl {
-                    enable: 1,
-                    reserved_1_32: 0,
-                }
-                .write(&handle)?;
-                ErcReferencePeriod {
-                    duration_us: rate_limiter.reference_period_us as u32,
-                    reserved_10_32: 0,
-                }
-                .write(&handle)?;
-                ErcTdTargetEventRate {
-                    maximum_per_period: rate_limiter.maximum_events_per_period,
-                    reserved_22_32: 0,
-                }
-                .write(&handle)?;
-                ErcControl {
-                    enable: 1,
-                    reserved_1_32: 1,
-                }
-                .write(&handle)?;
-            }
-            None => {
-                ErcInDropRateControl {
-                    enable: 0,
-                    reserved_1_32: 0,
-                }
-                .write(&handle)?;
-                ErcControl {
-                    enable: 0,
-                    reserved_1_32: 1,
-                }
-                .write(&handle)?;
-            }
-        }
         ErcReserved602C { value: 0x00000001 }.write(&handle)?;
         for offset in 0..230 {
             ErcReserved6800 { value: 0x08080808 }
@@ -681,21 +856,10 @@ impl device::Usb for Device {
             .offset(offset)
             .write(&handle)?;
         }
-        ErcTDroppingControl {
-            enable: configuration.rate_limiter.is_some() as u32,
-            reserved_1_32: 0,
-        }
-        .write(&handle)?;
-        ErcHDroppingControl {
-            enable: 0,
-            reserved_1_32: 0,
-        }
-        .write(&handle)?;
-        ErcVDroppingControl {
-            enable: 0,
-            reserved_1_32: 0,
-        }
-        .write(&handle)?;
+        erc::write(
+            &ErcRegisters(&handle),
+            &erc_from_configuration(&configuration)?,
+        )?;
         ErcReserved6000 { value: 0x00155401 }.write(&handle)?;
         EdfReserved7004 {
             reserved_0_10: 0b0111111111,
@@ -732,7 +896,7 @@ impl device::Usb for Device {
         MipiControl { value: 0x000002f9 }.write(&handle)?;
         if configuration.enable_output {
             RoCtrl {
-                area_count_enable: 0,
+                area_count_enable: configuration.area_counters.is_some() as u32,
                 output_disable: 0,
                 keep_timer_high: 0,
             }
@@ -797,7 +961,7 @@ impl device::Usb for Device {
             td_enable: 1,
             reserved_2_5: 0,
             td_shadow_trigger: 0,
-            td_roni_n_en: (!configuration.mask_intersection_only) as u32,
+            td_roni_n_en: (!Masks::from_configuration(&configuration)?.intersection_only) as u32,
             reserved_7_10: 0,
             td_rstn: 1,
             reserved_11_32: 0x1e000a,
@@ -809,6 +973,7 @@ impl device::Usb for Device {
         let error_flag = flag.clone();
         let warning_flag = flag.clone();
         let register_mutex = std::sync::Arc::new(std::sync::Mutex::new(()));
+        let area_counts = std::sync::Arc::new(std::sync::Mutex::new(AreaCounts::default()));
         Ok(Device {
             handle: handle.clone(),
             ring: usb::Ring::new(
@@ -826,12 +991,13 @@ impl device::Usb for Device {
                     timeout: std::time::Duration::default(),
                 },
             )?,
-            configuration_updater: configuration::Updater::new(
+            configuration_updater: configuration::Updater::with_poll(
                 configuration,
                 ConfigurationUpdaterContext {
                     handle,
                     flag,
                     register_mutex: register_mutex.clone(),
+                    area_counts: area_counts.clone(),
                 },
                 |context, previous_configuration, configuration| {
                     let result = {
@@ -845,15 +1011,44 @@ impl device::Usb for Device {
                             configuration,
                         )
                     };
+                    if previous_configuration.area_counters != configuration.area_counters {
+                        // update_configuration resets the on-chip counters
+                        *context
+                            .area_counts
+                            .lock()
+                            .expect("area counts mutex is not poisoned") = AreaCounts::default();
+                    }
+                    let success = result.is_ok();
                     if let Err(error) = result {
                         context.flag.store_error_if_not_set(error);
                     }
+                    (context, success)
+                },
+                |context, configuration| {
+                    if configuration.area_counters.is_some() {
+                        let result = {
+                            let _guard = context
+                                .register_mutex
+                                .lock()
+                                .expect("register mutex is not poisoned");
+                            read_area_counters(&context.handle)
+                        };
+                        match result {
+                            Ok(counters) => context
+                                .area_counts
+                                .lock()
+                                .expect("area counts mutex is not poisoned")
+                                .accumulate(counters),
+                            Err(error) => context.flag.store_error_if_not_set(error),
+                        }
+                    }
                     context
                 },
             ),
             serial,
             chip_firmware_configuration,
             register_mutex,
+            area_counts,
         })
     }
 
@@ -861,6 +1056,15 @@ impl device::Usb for Device {
         self.ring.next_with_timeout(timeout)
     }
 
+    fn set_maximum_leases(&self, maximum_leases: usize) {
+        self.ring.set_maximum_leases(maximum_leases);
+    }
+
+    #[cfg(feature = "async")]
+    fn register_waker(&self, waker: &std::task::Waker) {
+        self.ring.register_waker(waker);
+    }
+
     fn backlog(&self) -> usize {
         self.ring.backlog()
     }
@@ -909,6 +1113,39 @@ impl device::Usb for Device {
 }
 
 impl Device {
+    /// area_counts returns the number of events in each region since the previous call.
+    ///
+    /// Counts are in row-major order (index = row * 4 + column), see AreaCounters.
+    /// They are zero if the configuration's area_counters is None.
+    ///
+    /// The updater thread polls the on-chip counters (at least every 100 ms) and accumulates
+    /// their increments without resetting them, hence no events are lost between calls but
+    /// the counts lag by up to one poll period. The counts are reset when area_counters changes.
+    ///
+    /// This method is named area_counts (rather than area_counters) to distinguish the counts
+    /// from the regions set in Configuration::area_counters.
+    pub fn area_counts(&self) -> [u64; 16] {
+        std::mem::take(
+            &mut self
+                .area_counts
+                .lock()
+                .expect("area counts mutex is not poisoned")
+                .accumulated,
+        )
+    }
+
+    /// effective_event_rate_control reads back the event rate controller registers.
+    ///
+    /// The returned target rate reflects the sensor's integer number of events per period,
+    /// which may be lower than the configured target. None means that the controller is disabled.
+    pub fn effective_event_rate_control(&self) -> Result<Option<EventRateControl>, Error> {
+        let _guard = self
+            .register_mutex
+            .lock()
+            .expect("register mutex is not poisoned");
+        erc::read(&ErcRegisters(&self.handle))
+    }
+
     pub fn illuminance(&self) -> Result<u32, Error> {
         let lifo_status = LifoStatus::read(&self.handle)?;
         if lifo_status.lifo_ton_valid == 1 {
@@ -1056,19 +1293,293 @@ macro_rules! update_bias {
     };
 }
 
+fn window_in_sensor(window: &RoiWindow) -> bool {
+    window.width > 0
+        && window.height > 0
+        && window.x as u32 + window.width as u32 <= Device::PROPERTIES.width as u32
+        && window.y as u32 + window.height as u32 <= Device::PROPERTIES.height as u32
+}
+
+/// digital_mask returns the masked pixels in sensor coordinates, without duplicates.
+fn digital_mask(configuration: &Configuration) -> Result<Vec<(u16, u16)>, Error> {
+    let width = Device::PROPERTIES.width as u32;
+    let height = Device::PROPERTIES.height as u32;
+    let mut pixels = Vec::new();
+    for offset in 0u32..64u32 {
+        let code = if offset < 63 {
+            (((configuration.pixel_mask[(offset / 3) as usize]) >> ((offset % 3) * 21)) & 0x1fffff)
+                as u32
+        } else {
+            let mut code: u32 = 0;
+            for bit in 0..21 {
+                code |= ((configuration.pixel_mask[bit] >> 63) << bit) as u32;
+            }
+            code
+        };
+        if code != 0 {
+            if (code - 1) / width >= height {
+                return Err(Error::PixelMask { code, offset });
+            }
+            pixels.push((
+                ((code - 1) % width) as u16,
+                (height - 1 - (code - 1) / width) as u16,
+            ));
+        }
+    }
+    for pixel in configuration.masked_pixels.iter() {
+        if pixel.x >= Device::PROPERTIES.width || pixel.y >= Device::PROPERTIES.height {
+            return Err(Error::MaskedPixel {
+                x: pixel.x,
+                y: pixel.y,
+            });
+        }
+        pixels.push((pixel.x, Device::PROPERTIES.height - 1 - pixel.y));
+    }
+    pixels.sort_unstable();
+    pixels.dedup();
+    if pixels.len() > DIGITAL_MASK_CAPACITY {
+        return Err(Error::DigitalMaskCapacity {
+            pixels: pixels.len(),
+            capacity: DIGITAL_MASK_CAPACITY,
+        });
+    }
+    Ok(pixels)
+}
+
+/// Masks are the row and column masks (and window) programmed for a configuration.
+///
+/// Masked lines drop events. If intersection_only is true, only the pixels at the
+/// intersection of a masked row and a masked column are dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Masks {
+    pub x_mask: [u64; 20],
+    pub y_mask: [u64; 12],
+    pub intersection_only: bool,
+    pub window: Option<RoiWindow>,
+}
+
+impl Masks {
+    pub fn from_configuration(configuration: &Configuration) -> Result<Self, Error> {
+        let roi = match &configuration.roi {
+            Some(roi) => roi,
+            None => {
+                return Ok(Self {
+                    x_mask: configuration.x_mask,
+                    y_mask: configuration.y_mask,
+                    intersection_only: configuration.mask_intersection_only,
+                    window: None,
+                })
+            }
+        };
+        if roi.mode == RoiMode::Include && roi.windows.is_empty() {
+            return Err(Error::RoiEmpty);
+        }
+        for window in roi.windows.iter() {
+            if !window_in_sensor(window) {
+                return Err(Error::RoiWindow(window.clone()));
+            }
+        }
+        if roi.mode == RoiMode::Include && roi.windows.len() == 1 {
+            return Ok(Self {
+                x_mask: [0; 20],
+                y_mask: [0; 12],
+                intersection_only: false,
+                window: Some(roi.windows[0].clone()),
+            });
+        }
+        let mut columns = vec![false; Device::PROPERTIES.width as usize];
+        let mut rows = vec![false; Device::PROPERTIES.height as usize];
+        for window in roi.windows.iter() {
+            columns[window.x as usize..(window.x + window.width) as usize].fill(true);
+            rows[window.y as usize..(window.y + window.height) as usize].fill(true);
+        }
+        // every pixel in the product of covered columns and covered rows must belong to a window
+        let mut xs: Vec<u16> = roi
+            .windows
+            .iter()
+            .flat_map(|window| [window.x, window.x + window.width])
+            .collect();
+        let mut ys: Vec<u16> = roi
+            .windows
+            .iter()
+            .flat_map(|window| [window.y, window.y + window.height])
+            .collect();
+        xs.sort_unstable();
+        xs.dedup();
+        ys.sort_unstable();
+        ys.dedup();
+        for x in xs.iter().filter(|x| (**x as usize) < columns.len()) {
+            if !columns[*x as usize] {
+                continue;
+            }
+            for y in ys.iter().filter(|y| (**y as usize) < rows.len()) {
+                if rows[*y as usize]
+                    && !roi.windows.iter().any(|window| {
+                        (window.x..window.x + window.width).contains(x)
+                            && (window.y..window.y + window.height).contains(y)
+                    })
+                {
+                    return Err(Error::RoiWindows);
+                }
+            }
+        }
+        // Include masks the lines outside the windows (union), Exclude masks the windows (intersection)
+        let include = roi.mode == RoiMode::Include;
+        let mut masks = Self {
+            x_mask: [0; 20],
+            y_mask: [0; 12],
+            intersection_only: !include,
+            window: None,
+        };
+        for (x, covered) in columns.iter().enumerate() {
+            if *covered != include {
+                masks.x_mask[x / 64] |= 1 << (x % 64);
+            }
+        }
+        for (y, covered) in rows.iter().enumerate() {
+            if *covered != include {
+                masks.y_mask[y / 64] |= 1 << (y % 64);
+            }
+        }
+        Ok(masks)
+    }
+}
+
+/// BurstFilterParameters are the STC and TRAIL filter parameters programmed for a configuration.
+///
+/// A threshold is None if the corresponding filter is disabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BurstFilterParameters {
+    pub stc_threshold: Option<u32>,
+    pub disable_cut_trail: bool,
+    pub trail_threshold: Option<u32>,
+}
+
+impl BurstFilterParameters {
+    pub fn from_configuration(configuration: &Configuration) -> Self {
+        let (stc_threshold, disable_cut_trail, trail_threshold) = match &configuration.burst_filter
+        {
+            Some(BurstFilter {
+                mode: BurstFilterMode::Trail,
+                threshold_us,
+            }) => (None, true, Some(*threshold_us)),
+            Some(BurstFilter {
+                mode: BurstFilterMode::StcCutTrail,
+                threshold_us,
+            }) => (Some(*threshold_us), false, None),
+            Some(BurstFilter {
+                mode: BurstFilterMode::StcKeepTrail,
+                threshold_us,
+            }) => (Some(*threshold_us), true, None),
+            None => (None, true, None),
+        };
+        Self {
+            stc_threshold,
+            disable_cut_trail,
+            trail_threshold,
+        }
+    }
+}
+
+/// erc_from_configuration returns the event rate controller state.
+///
+/// event_rate_control overrides rate_limiter.
+fn erc_from_configuration(configuration: &Configuration) -> Result<Option<erc::Erc>, Error> {
+    match (
+        &configuration.event_rate_control,
+        &configuration.rate_limiter,
+    ) {
+        (Some(event_rate_control), _) => erc::Erc::from_event_rate_control(event_rate_control)
+            .map(Some)
+            .ok_or(Error::EventRateControl {
+                reference_period_us: event_rate_control.reference_period_us,
+                target_event_rate_kev_s: event_rate_control.target_event_rate_kev_s,
+            }),
+        (None, Some(rate_limiter)) => Ok(Some(erc::Erc::from_rate_limiter(
+            rate_limiter.reference_period_us,
+            rate_limiter.maximum_events_per_period,
+        ))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// ErcRegisters gives the shared event rate controller code access to the registers.
+struct ErcRegisters<'a>(&'a rusb::DeviceHandle<rusb::Context>);
+
+impl erc::Registers for ErcRegisters<'_> {
+    type Error = Error;
+
+    fn read(&self, address: u32) -> Result<u32, Self::Error> {
+        read_register(self.0, address)
+    }
+
+    fn write(&self, address: u32, value: u32) -> Result<(), Self::Error> {
+        RuntimeRegister { address, value }.write(self.0)
+    }
+}
+
 fn update_configuration(
     handle: &rusb::DeviceHandle<rusb::Context>,
     previous_configuration: Option<&Configuration>,
     configuration: &Configuration,
 ) -> Result<(), Error> {
+    configuration.validate()?;
+    if let Some(previous_configuration) = previous_configuration {
+        if previous_configuration.rate_limiter != configuration.rate_limiter
+            || previous_configuration.event_rate_control != configuration.event_rate_control
+        {
+            ErcReserved6000 { value: 0x00155400 }.write(handle)?;
+            erc::write(
+                &ErcRegisters(handle),
+                &erc_from_configuration(configuration)?,
+            )?;
+            ErcReserved6000 { value: 0x00155401 }.write(handle)?;
+        }
+    }
+    if match previous_configuration {
+        Some(previous_configuration) => {
+            previous_configuration.area_counters != configuration.area_counters
+        }
+        None => true,
+    } {
+        if let Some(area_counters) = &configuration.area_counters {
+            for (offset, x) in area_counters.x.iter().enumerate() {
+                AreaX0Addr { value: *x as u32 }
+                    .offset(offset as u32)
+                    .write(handle)?;
+            }
+            // the sensor's y axis is flipped with respect to event coordinates
+            for (offset, y) in area_counters.y.iter().rev().enumerate() {
+                AreaY0Addr {
+                    value: (Device::PROPERTIES.height - y) as u32,
+                }
+                .offset(offset as u32)
+                .write(handle)?;
+            }
+        }
+        CounterCtrl {
+            enable: configuration.area_counters.is_some() as u32,
+            use_timer: 0,
+            reset: 1,
+        }
+        .write(handle)?;
+        CounterCtrl {
+            enable: configuration.area_counters.is_some() as u32,
+            use_timer: 0,
+            reset: 0,
+        }
+        .write(handle)?;
+    }
     if match previous_configuration {
         Some(previous_configuration) => {
             previous_configuration.enable_output != configuration.enable_output
+                || previous_configuration.area_counters.is_some()
+                    != configuration.area_counters.is_some()
         }
         None => false,
     } {
         RoCtrl {
-            area_count_enable: 0,
+            area_count_enable: configuration.area_counters.is_some() as u32,
             output_disable: if configuration.enable_output { 0 } else { 1 },
             keep_timer_high: 0,
         }
@@ -1144,36 +1655,37 @@ fn update_configuration(
             configuration.biases
         );
     }
+    let masks = Masks::from_configuration(configuration)?;
+    let digital_mask = digital_mask(configuration)?;
     if match previous_configuration {
         Some(previous_configuration) => {
             previous_configuration.x_mask != configuration.x_mask
                 || previous_configuration.y_mask != configuration.y_mask
                 || previous_configuration.mask_intersection_only
                     != configuration.mask_intersection_only
+                || previous_configuration.roi != configuration.roi
         }
         None => true,
     } {
-        for offset in 0..((configuration.x_mask.len() as u32) * 2) {
+        for offset in 0..((masks.x_mask.len() as u32) * 2) {
             let register = TdRoiX {
                 value: if (offset % 2) == 0 {
-                    (configuration.x_mask[(offset / 2) as usize] & 0xffffffffu64) as u32
+                    (masks.x_mask[(offset / 2) as usize] & 0xffffffffu64) as u32
                 } else {
-                    ((configuration.x_mask[(offset / 2) as usize] & 0xffffffff00000000u64) >> 32)
-                        as u32
+                    ((masks.x_mask[(offset / 2) as usize] & 0xffffffff00000000u64) >> 32) as u32
                 },
             }
             .offset(offset);
             register.write(handle)?;
         }
-        for offset in 0..((configuration.y_mask.len() as u32) * 2 - 1) {
+        for offset in 0..((masks.y_mask.len() as u32) * 2 - 1) {
             let register = TdRoiY {
                 value: if (offset % 2) == 0 {
-                    let [byte2, byte3, _, _, _, _, _, _] = configuration.y_mask
-                        [configuration.y_mask.len() - 1 - (offset / 2) as usize]
-                        .to_le_bytes();
-                    if offset < (configuration.y_mask.len() as u32) * 2 - 2 {
-                        let [_, _, _, _, _, _, byte0, byte1] = configuration.y_mask
-                            [configuration.y_mask.len() - 2 - (offset / 2) as usize]
+                    let [byte2, byte3, _, _, _, _, _, _] =
+                        masks.y_mask[masks.y_mask.len() - 1 - (offset / 2) as usize].to_le_bytes();
+                    if offset < (masks.y_mask.len() as u32) * 2 - 2 {
+                        let [_, _, _, _, _, _, byte0, byte1] = masks.y_mask
+                            [masks.y_mask.len() - 2 - (offset / 2) as usize]
                             .to_le_bytes();
                         u32::from_le_bytes([
                             byte3.reverse_bits(),
@@ -1185,9 +1697,8 @@ fn update_configuration(
                         u32::from_le_bytes([byte3.reverse_bits(), byte2.reverse_bits(), 0xff, 0x00])
                     }
                 } else {
-                    let [_, _, byte0, byte1, byte2, byte3, _, _] = configuration.y_mask
-                        [configuration.y_mask.len() - 2 - (offset / 2) as usize]
-                        .to_le_bytes();
+                    let [_, _, byte0, byte1, byte2, byte3, _, _] =
+                        masks.y_mask[masks.y_mask.len() - 2 - (offset / 2) as usize].to_le_bytes();
                     u32::from_le_bytes([
                         byte3.reverse_bits(),
                         byte2.reverse_bits(),
@@ -1199,12 +1710,39 @@ fn update_configuration(
             .offset(offset);
             register.write(handle)?;
         }
+        match &masks.window {
+            Some(window) => {
+                // the sensor's y axis is flipped with respect to event coordinates
+                RoiWinStartAddr {
+                    x: window.x as u32,
+                    y: (Device::PROPERTIES.height - window.y - window.height) as u32,
+                }
+                .write(handle)?;
+                RoiWinEndAddr {
+                    x: (window.x + window.width - 1) as u32,
+                    y: (Device::PROPERTIES.height - 1 - window.y) as u32,
+                }
+                .write(handle)?;
+                RoiWinCtrl {
+                    master_enable: 1,
+                    done: 0,
+                }
+                .write(handle)?;
+            }
+            None => {
+                RoiWinCtrl {
+                    master_enable: 0,
+                    done: 0,
+                }
+                .write(handle)?;
+            }
+        }
         RoiCtrl {
             reserved_0_1: 0,
             td_enable: 1,
             reserved_2_5: 0,
             td_shadow_trigger: 1,
-            td_roni_n_en: (!configuration.mask_intersection_only) as u32,
+            td_roni_n_en: (!masks.intersection_only) as u32,
             reserved_7_10: 0,
             td_rstn: previous_configuration.is_some() as u32,
             reserved_11_32: 0x1e000a,
@@ -1213,48 +1751,140 @@ fn update_configuration(
     }
     if match previous_configuration {
         Some(previous_configuration) => {
-            previous_configuration.pixel_mask != configuration.pixel_mask
+            previous_configuration.burst_filter != configuration.burst_filter
         }
         None => true,
     } {
-        for offset in 0u32..64u32 {
-            let code = if offset < 63 {
-                (((configuration.pixel_mask[(offset / 3) as usize]) >> ((offset % 3) * 21))
-                    & 0x1fffff) as u32
-            } else {
-                let mut code: u32 = 0;
-                for bit in 0..21 {
-                    code |= ((configuration.pixel_mask[bit] >> 63) << bit) as u32;
+        BurstPipelineControl {
+            reserved_0_2: 1,
+            bypass: 1,
+        }
+        .write(handle)?;
+        let burst_filter_parameters = BurstFilterParameters::from_configuration(configuration);
+        // the default thresholds match the values written at initialization
+        StcParam {
+            enable: burst_filter_parameters.stc_threshold.is_some() as u32,
+            threshold: burst_filter_parameters.stc_threshold.unwrap_or(1480),
+            reserved_20_24: 0,
+            disable_cut_trail: burst_filter_parameters.disable_cut_trail as u32,
+        }
+        .write(handle)?;
+        TrailParam {
+            enable: burst_filter_parameters.trail_threshold.is_some() as u32,
+            threshold: burst_filter_parameters.trail_threshold.unwrap_or(100000),
+        }
+        .write(handle)?;
+        if configuration.burst_filter.is_some() {
+            BurstPipelineControl {
+                reserved_0_2: 1,
+                bypass: 0,
+            }
+            .write(handle)?;
+        }
+    }
+    if match previous_configuration {
+        Some(previous_configuration) => {
+            previous_configuration.anti_flicker != configuration.anti_flicker
+        }
+        None => true,
+    } {
+        AfkPipelineControl {
+            reserved_0_2: 1,
+            bypass: 1,
+        }
+        .write(handle)?;
+        if let Some(anti_flicker) = &configuration.anti_flicker {
+            AfkInitialization {
+                request: 1,
+                busy: 0,
+                done: 0,
+            }
+            .write(handle)?;
+            let mut initialized = false;
+            for _ in 0..10 {
+                if AfkInitialization::read(handle)?.done == 1 {
+                    initialized = true;
+                    break;
                 }
-                code
-            };
-            if code == 0 {
-                DigitalMask {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            if !initialized {
+                return Err(Error::AntiFlickerInitialization);
+            }
+            AfkParam {
+                counter_low: 4,
+                counter_high: 6,
+                invert: (anti_flicker.mode == AntiFlickerMode::BandPass) as u32,
+                drop_disable: 0,
+            }
+            .write(handle)?;
+            // periods are expressed in units of 128 µs
+            let period =
+                |frequency: u16| (1_000_000 + frequency as u32 * 64) / (frequency as u32 * 128);
+            AfkPeriod {
+                min_cutoff_period: period(anti_flicker.high_frequency),
+                max_cutoff_period: period(anti_flicker.low_frequency),
+                inverted_duty_cycle: (16 - (anti_flicker.duty_cycle as u32 * 16 + 50) / 100)
+                    .min(15),
+            }
+            .write(handle)?;
+            AfkPipelineControl {
+                reserved_0_2: 1,
+                bypass: 0,
+            }
+            .write(handle)?;
+        }
+    }
+    if match previous_configuration {
+        Some(previous_configuration) => {
+            previous_configuration.pixel_mask != configuration.pixel_mask
+                || previous_configuration.masked_pixels != configuration.masked_pixels
+        }
+        None => true,
+    } {
+        for offset in 0..DIGITAL_MASK_CAPACITY {
+            match digital_mask.get(offset) {
+                Some((x, y)) => DigitalMask {
+                    x: *x as u32,
+                    reserved_11_16: 0,
+                    y: *y as u32,
+                    reserved_26_31: 0,
+                    enable: 1,
+                },
+                None => DigitalMask {
                     x: 0,
                     reserved_11_16: 0,
                     y: 0,
                     reserved_26_31: 0,
                     enable: 0,
-                }
-                .offset(offset)
-                .write(handle)?;
-            } else {
-                let x = (code - 1) % 1280;
-                let y = 720 - 1 - (code - 1) / 1280;
-                if x >= 1280 || y >= 720 {
-                    return Err(Error::PixelMask { code, offset });
-                }
-                DigitalMask {
-                    x,
-                    reserved_11_16: 0,
-                    y,
-                    reserved_26_31: 0,
-                    enable: 1,
-                }
-                .offset(offset)
-                .write(handle)?;
+                },
+            }
+            .offset(offset as u32)
+            .write(handle)?;
+        }
+    }
+    if match previous_configuration {
+        Some(previous_configuration) => {
+            previous_configuration.digital_crop != configuration.digital_crop
+        }
+        None => true,
+    } {
+        if let Some(digital_crop) = &configuration.digital_crop {
+            // the sensor's y axis is flipped with respect to event coordinates
+            DigStartPos {
+                x: digital_crop.x as u32,
+                y: (Device::PROPERTIES.height - digital_crop.y - digital_crop.height) as u32,
+            }
+            .write(handle)?;
+            DigEndPos {
+                x: (digital_crop.x + digital_crop.width - 1) as u32,
+                y: (Device::PROPERTIES.height - 1 - digital_crop.y) as u32,
             }
+            .write(handle)?;
         }
+        let mut dig_ctrl = DigCtrl::read(handle)?;
+        dig_ctrl.crop_enable = configuration.digital_crop.is_some() as u32;
+        dig_ctrl.write(handle)?;
     }
     Ok(())
 }
@@ -1267,6 +1897,90 @@ where
     handle: std::sync::Arc<rusb::DeviceHandle<rusb::Context>>,
     flag: flag::Flag<IntoError, IntoWarning>,
     register_mutex: std::sync::Arc<std::sync::Mutex<()>>,
+    area_counts: std::sync::Arc<std::sync::Mutex<AreaCounts>>,
+}
+
+/// AreaCounts accumulates the increments of the free-running 32-bit on-chip counters.
+#[derive(Default)]
+struct AreaCounts {
+    previous: [u32; 16],
+    accumulated: [u64; 16],
+}
+
+impl AreaCounts {
+    fn accumulate(&mut self, counters: [u32; 16]) {
+        for ((accumulated, counter), previous) in self
+            .accumulated
+            .iter_mut()
+            .zip(counters.iter())
+            .zip(self.previous.iter())
+        {
+            *accumulated += counter.wrapping_sub(*previous) as u64;
+        }
+        self.previous = counters;
+    }
+}
+
+/// read_area_counters returns the on-chip counters in row-major event coordinates.
+fn read_area_counters(handle: &rusb::DeviceHandle<rusb::Context>) -> Result<[u32; 16], Error> {
+    let sensor_counters = [
+        AreaCnt00::read(handle)?.value,
+        AreaCnt01::read(handle)?.value,
+        AreaCnt02::read(handle)?.value,
+        AreaCnt03::read(handle)?.value,
+        AreaCnt04::read(handle)?.value,
+        AreaCnt05::read(handle)?.value,
+        AreaCnt06::read(handle)?.value,
+        AreaCnt07::read(handle)?.value,
+        AreaCnt08::read(handle)?.value,
+        AreaCnt09::read(handle)?.value,
+        AreaCnt10::read(handle)?.value,
+        AreaCnt11::read(handle)?.value,
+        AreaCnt12::read(handle)?.value,
+        AreaCnt13::read(handle)?.value,
+        AreaCnt14::read(handle)?.value,
+        AreaCnt15::read(handle)?.value,
+    ];
+    // the sensor's rows are flipped with respect to event coordinates
+    let mut counters = [0u32; 16];
+    for (index, counter) in sensor_counters.into_iter().enumerate() {
+        counters[(3 - index / 4) * 4 + index % 4] = counter;
+    }
+    Ok(counters)
+}
+
+fn read_register(handle: &rusb::DeviceHandle<rusb::Context>, address: u32) -> Result<u32, Error> {
+    let buffer = [
+        0x02,
+        0x01,
+        0x01,
+        0x00,
+        0x0c,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        (address & 0xff) as u8,
+        ((address >> 8) & 0xff) as u8,
+        ((address >> 16) & 0xff) as u8,
+        ((address >> 24) & 0xff) as u8,
+        0x01,
+        0x00,
+        0x00,
+        0x00,
+    ];
+    let result = request(handle, &buffer, TIMEOUT)?;
+    if result.len() != buffer.len() {
+        return Err(Error::RegisterReadShortResponse(address));
+    }
+    if result[0..16] != buffer[0..16] {
+        return Err(Error::RegisterReadMismatch(address));
+    }
+    // unwrap: slice has the right number of bytes
+    Ok(u32::from_le_bytes(result[16..20].try_into().unwrap()))
 }
 
 struct RuntimeRegister {
@@ -1360,37 +2074,7 @@ macro_rules! register {
         impl $name {
             #[allow(dead_code)]
             fn read(handle: &rusb::DeviceHandle<rusb::Context>) -> Result<Self, Error> {
-                let buffer = [
-                    0x02,
-                    0x01,
-                    0x01,
-                    0x00,
-                    0x0c,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    0x00,
-                    ($address & 0xff) as u8,
-                    (($address >> 8) & 0xff) as u8,
-                    (($address >> 16) & 0xff) as u8,
-                    (($address >> 24) & 0xff) as u8,
-                    0x01,
-                    0x00,
-                    0x00,
-                    0x00,
-                ];
-                let result = request(handle, &buffer, TIMEOUT)?;
-                if result.len() != buffer.len() {
-                    return Err(Error::RegisterReadShortResponse($address));
-                }
-                if result[0..16] != buffer[0..16] {
-                    return Err(Error::RegisterReadMismatch($address));
-                }
-                // unwrap: slice has the right number of bytes
-                let value = u32::from_le_bytes(result[16..20].try_into().unwrap());
+                let value = read_register(handle, $address)?;
                 Ok(Self {
                     $(
                         $subname: (value >> $substart) & (((1u64 << ($subend - $substart)) - 1) as u32),
@@ -1428,9 +2112,18 @@ register! { Spare0, 0x0018, { value: 0..32 } }
 register! { Unknown001C, 0x001C, { value: 0..32 } }
 register! { RefractoryCtrl, 0x0020, { value: 0..32 } }
 register! { Unknown002C, 0x002C, { value: 0..32 } }
-register! { RoiWinCtrl, 0x0034, { value: 0..32 } }
-register! { RoiWinStartAddr, 0x0038, { value: 0..32 } }
-register! { RoiWinEndAddr, 0x003C, { value: 0..32 } }
+register! { RoiWinCtrl, 0x0034, {
+    master_enable: 0..1,
+    done: 1..2,
+} }
+register! { RoiWinStartAddr, 0x0038, {
+    x: 0..11,
+    y: 16..26,
+} }
+register! { RoiWinEndAddr, 0x003C, {
+    x: 0..11,
+    y: 16..26,
+} }
 register! { DigPad2Ctrl, 0x0044, {
     reserved_0_16: 0..16,
     sync: 16..20,
@@ -1649,35 +2342,7 @@ register! { Unknown1104, 0x1104, { value: 0..32 } }
 register! { TdRoiX, 0x2000, { value: 0..32 } }
 register! { TdRoiY, 0x4000, { value: 0..32 } }
 register! { ErcReserved6000, 0x6000, { value: 0..32 } }
-register! { ErcInDropRateControl, 0x6004, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
-register! { ErcReferencePeriod, 0x6008, {
-    duration_us: 0..10,
-    reserved_10_32: 10..32,
-} }
-register! { ErcTdTargetEventRate, 0x600C, {
-    maximum_per_period: 0..22,
-    reserved_22_32: 22..32,
-} }
-register! { ErcControl, 0x6028, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
 register! { ErcReserved602C, 0x602C, { value: 0..32 } }
-register! { ErcTDroppingControl, 0x6050, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
-register! { ErcHDroppingControl, 0x6060, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
-register! { ErcVDroppingControl, 0x6070, {
-    enable: 0..1,
-    reserved_1_32: 1..32,
-} }
 register! { TDropLut, 0x6400, { value: 0..32 } }
 register! { ErcReserved6800, 0x6800, { value: 0..32 } }
 register! { EdfPipelineControl, 0x7000, { value: 0..32 } }
@@ -1700,9 +2365,18 @@ register! { TimeBaseCtrl, 0x9008, {
     external_enable: 3..4,
     reserved_4_32: 4..32,
 } }
-register! { DigCtrl, 0x900C, { value: 0..32 } }
-register! { DigStartPos, 0x9010, { value: 0..32 } }
-register! { DigEndPos, 0x9014, { value: 0..32 } }
+register! { DigCtrl, 0x900C, {
+    crop_enable: 0..1,
+    reserved_1_32: 1..32,
+} }
+register! { DigStartPos, 0x9010, {
+    x: 0..11,
+    y: 16..26,
+} }
+register! { DigEndPos, 0x9014, {
+    x: 0..11,
+    y: 16..26,
+} }
 register! { RoCtrl, 0x9028, {
     area_count_enable: 0..1,
     output_disable: 1..2,
@@ -1718,7 +2392,11 @@ register! { AreaY1Addr, 0x9044, { value: 0..32 } }
 register! { AreaY2Addr, 0x9048, { value: 0..32 } }
 register! { AreaY3Addr, 0x904C, { value: 0..32 } }
 register! { AreaY4Addr, 0x9050, { value: 0..32 } }
-register! { CounterCtrl, 0x9054, { value: 0..32 } }
+register! { CounterCtrl, 0x9054, {
+    enable: 0..1,
+    use_timer: 1..2,
+    reset: 2..3,
+} }
 register! { CounterTimerThreshold, 0x9058, { value: 0..32 } }
 register! { DigitalMask, 0x9100, {
     x: 0..11,
@@ -1783,14 +2461,23 @@ register! { AfkPipelineControl, 0xC000, {
     reserved_0_2: 0..2,
     bypass: 2..3,
 } }
-register! { ReservedC004, 0xC004, { value: 0..32 } }
+register! { AfkParam, 0xC004, {
+    counter_low: 0..3,
+    counter_high: 3..6,
+    invert: 6..7,
+    drop_disable: 7..8,
+} }
 register! { AfkPeriod, 0xC008, {
     min_cutoff_period: 0..8,
     max_cutoff_period: 8..16,
     inverted_duty_cycle: 16..20,
 } }
 register! { Invalidation, 0xC0C0, { value: 0..32 } }
-register! { AfkInitialization, 0xC0C4, { value: 0..32 } }
+register! { AfkInitialization, 0xC0C4, {
+    request: 0..1,
+    busy: 1..2,
+    done: 2..3,
+} }
 register! { BurstPipelineControl, 0xD000, {
     reserved_0_2: 0..2,
     bypass: 2..3,
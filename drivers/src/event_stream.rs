@@ -0,0 +1,518 @@
+use std::io::Read;
+use std::io::Write;
+
+pub const SIGNATURE: &[u8; 12] = b"Event Stream";
+pub const VERSION: [u8; 3] = [2, 0, 0];
+pub const CHUNK_LENGTH: usize = 1 << 16;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("the file does not start with \"Event Stream\"")]
+    Signature,
+
+    #[error("unsupported version {major}.{minor}.{patch} (only 2.x.y files are supported)")]
+    Version { major: u8, minor: u8, patch: u8 },
+
+    #[error("unsupported event type {0}")]
+    Type(u8),
+
+    #[error("the stream contains {stream:?} events but a {event:?} event was written")]
+    TypeMismatch { stream: Type, event: Type },
+
+    #[error("the timestamp {t} is smaller than the previous timestamp {previous_t}")]
+    Timestamp { previous_t: u64, t: u64 },
+
+    #[error("the coordinates ({x}, {y}) are outside the sensor ({width} x {height})")]
+    Coordinates {
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Generic = 0,
+    Dvs = 1,
+    Atis = 2,
+}
+
+impl Type {
+    fn from_byte(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0 => Ok(Type::Generic),
+            1 => Ok(Type::Dvs),
+            2 => Ok(Type::Atis),
+            byte => Err(Error::Type(byte)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub version: [u8; 3],
+    pub event_type: Type,
+
+    /// width and height are zero for generic streams.
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericEvent {
+    pub t: u64,
+    pub bytes: Vec<u8>,
+}
+
+impl GenericEvent {
+    /// Trigger events are stored as generic events with two bytes (id and polarity).
+    pub fn from_trigger_event(event: neuromorphic_types::TriggerEvent<u64, u8>) -> Self {
+        Self {
+            t: event.t,
+            bytes: vec![event.id, event.polarity as u8],
+        }
+    }
+
+    pub fn to_trigger_event(&self) -> Option<neuromorphic_types::TriggerEvent<u64, u8>> {
+        match self.bytes[..] {
+            [id, polarity] if polarity < 2 => Some(neuromorphic_types::TriggerEvent {
+                t: self.t,
+                id,
+                polarity: if polarity == 1 {
+                    neuromorphic_types::TriggerPolarity::Rising
+                } else {
+                    neuromorphic_types::TriggerPolarity::Falling
+                },
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub enum Events {
+    Generic(Vec<GenericEvent>),
+    Dvs(Vec<neuromorphic_types::DvsEvent<u64, u16, u16>>),
+    Atis(Vec<neuromorphic_types::AtisEvent<u64, u16, u16>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    Byte0,
+    Byte1,
+    Byte2,
+    Byte3,
+}
+
+/// Stateful decoder, events may span several slices.
+pub struct Decoder {
+    header: Header,
+    previous_t: u64,
+    state: State,
+    t: u64,
+    x: u16,
+    y: u16,
+    flags: u8,
+    size: usize,
+    size_index: u32,
+    bytes: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn new(header: Header) -> Self {
+        Self {
+            header,
+            previous_t: 0,
+            state: State::Idle,
+            t: 0,
+            x: 0,
+            y: 0,
+            flags: 0,
+            size: 0,
+            size_index: 0,
+            bytes: Vec::new(),
+        }
+    }
+
+    pub fn current_t(&self) -> u64 {
+        self.previous_t
+    }
+
+    pub fn decode(&mut self, slice: &[u8]) -> Events {
+        match self.header.event_type {
+            Type::Generic => Events::Generic(self.decode_generic(slice)),
+            Type::Dvs => {
+                let mut events = Vec::with_capacity(slice.len() / 5);
+                self.decode_polarity_events(slice, 1, |t, x, y, flags| {
+                    events.push(neuromorphic_types::DvsEvent {
+                        t,
+                        x,
+                        y,
+                        polarity: if flags == 1 {
+                            neuromorphic_types::DvsPolarity::On
+                        } else {
+                            neuromorphic_types::DvsPolarity::Off
+                        },
+                    })
+                });
+                Events::Dvs(events)
+            }
+            Type::Atis => {
+                let mut events = Vec::with_capacity(slice.len() / 5);
+                self.decode_polarity_events(slice, 2, |t, x, y, flags| {
+                    events.push(neuromorphic_types::AtisEvent {
+                        t,
+                        x,
+                        y,
+                        // bit 0 is "is_threshold_crossing", bit 1 is "polarity"
+                        polarity: match flags {
+                            0b00 => neuromorphic_types::AtisPolarity::Off,
+                            0b10 => neuromorphic_types::AtisPolarity::On,
+                            0b01 => neuromorphic_types::AtisPolarity::ExposureStart,
+                            _ => neuromorphic_types::AtisPolarity::ExposureEnd,
+                        },
+                    })
+                });
+                Events::Atis(events)
+            }
+        }
+    }
+
+    // DVS: 0b11111111 adds 0b1111111 to t, 0b11111110 is a reset byte
+    // ATIS: 0b111111nn adds nn * 0b111111 to t (0b11111100 is a reset byte)
+    fn decode_polarity_events<HandleEvent>(
+        &mut self,
+        slice: &[u8],
+        flags_bits: u32,
+        mut handle_event: HandleEvent,
+    ) where
+        HandleEvent: FnMut(u64, u16, u16, u8),
+    {
+        for byte in slice.iter().copied() {
+            match self.state {
+                State::Idle => {
+                    if flags_bits == 1 {
+                        if byte == 0b11111111 {
+                            self.previous_t += 0b1111111;
+                            continue;
+                        } else if byte == 0b11111110 {
+                            continue;
+                        }
+                    } else if (byte & 0b11111100) == 0b11111100 {
+                        self.previous_t += 0b111111 * (byte & 0b11) as u64;
+                        continue;
+                    }
+                    self.t = self.previous_t + (byte >> flags_bits) as u64;
+                    self.flags = byte & ((1 << flags_bits) - 1);
+                    self.state = State::Byte0;
+                }
+                State::Byte0 => {
+                    self.x = byte as u16;
+                    self.state = State::Byte1;
+                }
+                State::Byte1 => {
+                    self.x |= (byte as u16) << 8;
+                    self.state = State::Byte2;
+                }
+                State::Byte2 => {
+                    self.y = byte as u16;
+                    self.state = State::Byte3;
+                }
+                State::Byte3 => {
+                    self.y |= (byte as u16) << 8;
+                    self.previous_t = self.t;
+                    self.state = State::Idle;
+                    // out-of-bounds events are skipped, like in the adapters
+                    if self.x < self.header.width && self.y < self.header.height {
+                        handle_event(self.t, self.x, self.y, self.flags);
+                    }
+                }
+            }
+        }
+    }
+
+    // 0b11111111 adds 0b11111110 to t, 0b11111110 is a reset byte
+    // the payload size is encoded with 7 bits per byte, bit 0 flags a continuation
+    fn decode_generic(&mut self, slice: &[u8]) -> Vec<GenericEvent> {
+        let mut events = Vec::new();
+        for byte in slice.iter().copied() {
+            match self.state {
+                State::Idle => {
+                    if byte == 0b11111111 {
+                        self.previous_t += 0b11111110;
+                    } else if byte != 0b11111110 {
+                        self.t = self.previous_t + byte as u64;
+                        self.size = 0;
+                        self.size_index = 0;
+                        self.state = State::Byte0;
+                    }
+                }
+                State::Byte0 => {
+                    self.size |= ((byte >> 1) as usize) << (7 * self.size_index);
+                    self.size_index += 1;
+                    if (byte & 1) == 0 {
+                        self.previous_t = self.t;
+                        if self.size == 0 {
+                            events.push(GenericEvent {
+                                t: self.t,
+                                bytes: Vec::new(),
+                            });
+                            self.state = State::Idle;
+                        } else {
+                            self.bytes = Vec::with_capacity(self.size);
+                            self.state = State::Byte1;
+                        }
+                    }
+                }
+                _ => {
+                    self.bytes.push(byte);
+                    if self.bytes.len() == self.size {
+                        events.push(GenericEvent {
+                            t: self.t,
+                            bytes: std::mem::take(&mut self.bytes),
+                        });
+                        self.state = State::Idle;
+                    }
+                }
+            }
+        }
+        events
+    }
+}
+
+pub struct Reader {
+    file: std::fs::File,
+    decoder: Decoder,
+    buffer: Vec<u8>,
+}
+
+impl Reader {
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        let mut file = std::fs::File::open(path)?;
+        let mut signature_and_version = [0u8; 16];
+        file.read_exact(&mut signature_and_version)?;
+        if &signature_and_version[0..12] != SIGNATURE {
+            return Err(Error::Signature);
+        }
+        if signature_and_version[12] != VERSION[0] {
+            return Err(Error::Version {
+                major: signature_and_version[12],
+                minor: signature_and_version[13],
+                patch: signature_and_version[14],
+            });
+        }
+        let event_type = Type::from_byte(signature_and_version[15])?;
+        let (width, height) = if event_type == Type::Generic {
+            (0, 0)
+        } else {
+            let mut dimensions = [0u8; 4];
+            file.read_exact(&mut dimensions)?;
+            (
+                u16::from_le_bytes([dimensions[0], dimensions[1]]),
+                u16::from_le_bytes([dimensions[2], dimensions[3]]),
+            )
+        };
+        Ok(Self {
+            file,
+            decoder: Decoder::new(Header {
+                version: [
+                    signature_and_version[12],
+                    signature_and_version[13],
+                    signature_and_version[14],
+                ],
+                event_type,
+                width,
+                height,
+            }),
+            buffer: vec![0u8; CHUNK_LENGTH],
+        })
+    }
+
+    pub fn header(&self) -> Header {
+        self.decoder.header
+    }
+
+    pub fn current_t(&self) -> u64 {
+        self.decoder.current_t()
+    }
+
+    /// Returns None at the end of the file.
+    pub fn next_chunk(&mut self) -> Result<Option<Events>, Error> {
+        let length = self.file.read(&mut self.buffer)?;
+        if length == 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.decoder.decode(&self.buffer[0..length])))
+    }
+}
+
+impl Iterator for Reader {
+    type Item = Result<Events, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_chunk().transpose()
+    }
+}
+
+pub struct Writer {
+    file: std::io::BufWriter<std::fs::File>,
+    header: Header,
+    previous_t: u64,
+}
+
+impl Writer {
+    /// width and height are ignored for generic streams.
+    pub fn create<P: AsRef<std::path::Path>>(
+        path: P,
+        event_type: Type,
+        width: u16,
+        height: u16,
+    ) -> Result<Self, Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        file.write_all(SIGNATURE)?;
+        file.write_all(&VERSION)?;
+        file.write_all(&[event_type as u8])?;
+        let (width, height) = if event_type == Type::Generic {
+            (0, 0)
+        } else {
+            file.write_all(&width.to_le_bytes())?;
+            file.write_all(&height.to_le_bytes())?;
+            (width, height)
+        };
+        Ok(Self {
+            file,
+            header: Header {
+                version: VERSION,
+                event_type,
+                width,
+                height,
+            },
+            previous_t: 0,
+        })
+    }
+
+    pub fn header(&self) -> Header {
+        self.header
+    }
+
+    pub fn write_dvs_event(
+        &mut self,
+        event: neuromorphic_types::DvsEvent<u64, u16, u16>,
+    ) -> Result<(), Error> {
+        let relative_t = self.relative_t(Type::Dvs, event.t)?;
+        self.check_coordinates(event.x, event.y)?;
+        let overflows = relative_t / 0b1111111;
+        for _ in 0..overflows {
+            self.file.write_all(&[0b11111111])?;
+        }
+        self.write_polarity_event(
+            (((relative_t - overflows * 0b1111111) as u8) << 1) | (event.polarity as u8),
+            event.x,
+            event.y,
+        )?;
+        self.previous_t = event.t;
+        Ok(())
+    }
+
+    pub fn write_atis_event(
+        &mut self,
+        event: neuromorphic_types::AtisEvent<u64, u16, u16>,
+    ) -> Result<(), Error> {
+        let relative_t = self.relative_t(Type::Atis, event.t)?;
+        self.check_coordinates(event.x, event.y)?;
+        let overflows = relative_t / 0b111111;
+        for _ in 0..overflows / 3 {
+            self.file.write_all(&[0b11111111])?;
+        }
+        if overflows % 3 > 0 {
+            self.file.write_all(&[0b11111100 | (overflows % 3) as u8])?;
+        }
+        let flags = match event.polarity {
+            neuromorphic_types::AtisPolarity::Off => 0b00,
+            neuromorphic_types::AtisPolarity::On => 0b10,
+            neuromorphic_types::AtisPolarity::ExposureStart => 0b01,
+            neuromorphic_types::AtisPolarity::ExposureEnd => 0b11,
+        };
+        self.write_polarity_event(
+            (((relative_t - overflows * 0b111111) as u8) << 2) | flags,
+            event.x,
+            event.y,
+        )?;
+        self.previous_t = event.t;
+        Ok(())
+    }
+
+    pub fn write_generic_event(&mut self, event: &GenericEvent) -> Result<(), Error> {
+        let relative_t = self.relative_t(Type::Generic, event.t)?;
+        let overflows = relative_t / 0b11111110;
+        for _ in 0..overflows {
+            self.file.write_all(&[0b11111111])?;
+        }
+        self.file
+            .write_all(&[(relative_t - overflows * 0b11111110) as u8])?;
+        let mut size = event.bytes.len();
+        loop {
+            let continuation = if (size >> 7) > 0 { 1 } else { 0 };
+            self.file
+                .write_all(&[(((size & 0b1111111) as u8) << 1) | continuation])?;
+            size >>= 7;
+            if size == 0 {
+                break;
+            }
+        }
+        self.file.write_all(&event.bytes)?;
+        self.previous_t = event.t;
+        Ok(())
+    }
+
+    pub fn write_trigger_event(
+        &mut self,
+        event: neuromorphic_types::TriggerEvent<u64, u8>,
+    ) -> Result<(), Error> {
+        self.write_generic_event(&GenericEvent::from_trigger_event(event))
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.file.flush()?;
+        Ok(())
+    }
+
+    fn relative_t(&self, event_type: Type, t: u64) -> Result<u64, Error> {
+        if event_type != self.header.event_type {
+            return Err(Error::TypeMismatch {
+                stream: self.header.event_type,
+                event: event_type,
+            });
+        }
+        if t < self.previous_t {
+            return Err(Error::Timestamp {
+                previous_t: self.previous_t,
+                t,
+            });
+        }
+        Ok(t - self.previous_t)
+    }
+
+    fn check_coordinates(&self, x: u16, y: u16) -> Result<(), Error> {
+        if x >= self.header.width || y >= self.header.height {
+            return Err(Error::Coordinates {
+                x,
+                y,
+                width: self.header.width,
+                height: self.header.height,
+            });
+        }
+        Ok(())
+    }
+
+    fn write_polarity_event(&mut self, first_byte: u8, x: u16, y: u16) -> Result<(), Error> {
+        let x = x.to_le_bytes();
+        let y = y.to_le_bytes();
+        self.file.write_all(&[first_byte, x[0], x[1], y[0], y[1]])?;
+        Ok(())
+    }
+}
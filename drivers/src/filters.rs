@@ -0,0 +1,288 @@
+/// Filter decides, event by event, whether DVS events are kept.
+///
+/// Filters keep per-pixel state, events must be inside the sensor and should be sorted by timestamp
+/// (out-of-order events do not panic but may be classified incorrectly).
+pub trait Filter: Send {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool;
+
+    fn retain(&mut self, events: &mut Vec<neuromorphic_types::DvsEvent<u64, u16, u16>>) {
+        events.retain(|event| self.keep(*event));
+    }
+}
+
+fn index(width: u16, height: u16, x: u16, y: u16) -> usize {
+    assert!(
+        x < width && y < height,
+        "the coordinates ({}, {}) are outside the sensor ({} x {})",
+        x,
+        y,
+        width,
+        height
+    );
+    y as usize * width as usize + x as usize
+}
+
+/// Chain keeps events that pass all its filters, in insertion order.
+///
+/// Filters after the first rejection do not see the event.
+#[derive(Default)]
+pub struct Chain {
+    filters: Vec<Box<dyn Filter>>,
+}
+
+impl Chain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<F: Filter + 'static>(mut self, filter: F) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    pub fn push<F: Filter + 'static>(&mut self, filter: F) {
+        self.filters.push(Box::new(filter));
+    }
+}
+
+impl Filter for Chain {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool {
+        self.filters.iter_mut().all(|filter| filter.keep(event))
+    }
+}
+
+/// BackgroundActivity keeps events that have support from one of their 8 neighbours.
+///
+/// An event is supported if a neighbour emitted an event less than `duration` µs before it.
+pub struct BackgroundActivity {
+    width: u16,
+    height: u16,
+    duration: u64,
+    deadlines: Vec<u64>,
+}
+
+impl BackgroundActivity {
+    pub fn new(width: u16, height: u16, duration: u64) -> Self {
+        Self {
+            width,
+            height,
+            duration,
+            deadlines: vec![0; width as usize * height as usize],
+        }
+    }
+}
+
+impl Filter for BackgroundActivity {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool {
+        let (t, x, y) = (event.t, event.x, event.y);
+        let keep = t < self.deadlines[index(self.width, self.height, x, y)];
+        let deadline = t + self.duration;
+        for neighbour_y in y.saturating_sub(1)..(y + 2).min(self.height) {
+            for neighbour_x in x.saturating_sub(1)..(x + 2).min(self.width) {
+                if neighbour_x != x || neighbour_y != y {
+                    self.deadlines
+                        [neighbour_y as usize * self.width as usize + neighbour_x as usize] =
+                        deadline;
+                }
+            }
+        }
+        keep
+    }
+}
+
+/// Refractory drops events less than `period` µs after the previous kept event of the same pixel.
+pub struct Refractory {
+    width: u16,
+    height: u16,
+    period: u64,
+    deadlines: Vec<u64>,
+}
+
+impl Refractory {
+    pub fn new(width: u16, height: u16, period: u64) -> Self {
+        Self {
+            width,
+            height,
+            period,
+            deadlines: vec![0; width as usize * height as usize],
+        }
+    }
+}
+
+impl Filter for Refractory {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool {
+        let t = event.t;
+        let deadline = &mut self.deadlines[index(self.width, self.height, event.x, event.y)];
+        if t < *deadline {
+            false
+        } else {
+            *deadline = t + self.period;
+            true
+        }
+    }
+}
+
+/// HotPixel drops the events of pixels that fire more than `maximum_count` times during a window.
+///
+/// Windows have a fixed duration (`window`, in µs) and start with the first event.
+/// Counts are reset at the beginning of every window, hence hot pixels are re-evaluated continuously.
+pub struct HotPixel {
+    width: u16,
+    height: u16,
+    window: u64,
+    maximum_count: u32,
+    window_end: Option<u64>,
+    counts: Vec<u32>,
+}
+
+impl HotPixel {
+    pub fn new(width: u16, height: u16, window: u64, maximum_count: u32) -> Self {
+        assert!(window > 0, "the window duration must be larger than 0");
+        Self {
+            width,
+            height,
+            window,
+            maximum_count,
+            window_end: None,
+            counts: vec![0; width as usize * height as usize],
+        }
+    }
+}
+
+impl Filter for HotPixel {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool {
+        let t = event.t;
+        let window_end = self.window_end.get_or_insert(t + self.window);
+        if t >= *window_end {
+            *window_end += (t - *window_end) / self.window * self.window + self.window;
+            self.counts.fill(0);
+        }
+        let count = &mut self.counts[index(self.width, self.height, event.x, event.y)];
+        *count = count.saturating_add(1);
+        *count <= self.maximum_count
+    }
+}
+
+/// Mask drops the events of a fixed set of pixels.
+pub struct Mask {
+    width: u16,
+    height: u16,
+    masked: Vec<bool>,
+}
+
+impl Mask {
+    pub fn new(width: u16, height: u16, pixels: &[(u16, u16)]) -> Self {
+        let mut masked = vec![false; width as usize * height as usize];
+        for (x, y) in pixels.iter() {
+            masked[index(width, height, *x, *y)] = true;
+        }
+        Self {
+            width,
+            height,
+            masked,
+        }
+    }
+}
+
+impl Filter for Mask {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool {
+        !self.masked[index(self.width, self.height, event.x, event.y)]
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Burst {
+    t: u64,
+    polarity: i8,
+    length: u32,
+}
+
+/// Bursts tracks, for each pixel, sequences of same-polarity events separated by at most `threshold` µs.
+struct Bursts {
+    width: u16,
+    height: u16,
+    threshold: u64,
+    bursts: Vec<Burst>,
+}
+
+impl Bursts {
+    fn new(width: u16, height: u16, threshold: u64) -> Self {
+        Self {
+            width,
+            height,
+            threshold,
+            bursts: vec![
+                Burst {
+                    t: 0,
+                    polarity: 0,
+                    length: 0,
+                };
+                width as usize * height as usize
+            ],
+        }
+    }
+
+    /// update returns the position of the event in its burst (0 for the first event).
+    fn update(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> u32 {
+        let t = event.t;
+        let polarity = match event.polarity {
+            neuromorphic_types::DvsPolarity::Off => -1,
+            neuromorphic_types::DvsPolarity::On => 1,
+        };
+        let burst = &mut self.bursts[index(self.width, self.height, event.x, event.y)];
+        if burst.polarity == polarity && t.saturating_sub(burst.t) <= self.threshold {
+            burst.length = burst.length.saturating_add(1);
+        } else {
+            burst.polarity = polarity;
+            burst.length = 0;
+        }
+        burst.t = t;
+        burst.length
+    }
+}
+
+/// SpatioTemporalContrast is a host-side equivalent of the EVK4's STC filter.
+///
+/// The first event of each burst is dropped. If `cut_trail` is true, only the second event is kept.
+pub struct SpatioTemporalContrast {
+    bursts: Bursts,
+    cut_trail: bool,
+}
+
+impl SpatioTemporalContrast {
+    pub fn new(width: u16, height: u16, threshold: u64, cut_trail: bool) -> Self {
+        Self {
+            bursts: Bursts::new(width, height, threshold),
+            cut_trail,
+        }
+    }
+}
+
+impl Filter for SpatioTemporalContrast {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool {
+        match self.bursts.update(event) {
+            0 => false,
+            1 => true,
+            _ => !self.cut_trail,
+        }
+    }
+}
+
+/// Trail is a host-side equivalent of the EVK4's trail filter, it keeps only the first event of each burst.
+pub struct Trail {
+    bursts: Bursts,
+}
+
+impl Trail {
+    pub fn new(width: u16, height: u16, threshold: u64) -> Self {
+        Self {
+            bursts: Bursts::new(width, height, threshold),
+        }
+    }
+}
+
+impl Filter for Trail {
+    fn keep(&mut self, event: neuromorphic_types::DvsEvent<u64, u16, u16>) -> bool {
+        self.bursts.update(event) == 0
+    }
+}
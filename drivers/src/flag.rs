@@ -6,6 +6,8 @@ where
 {
     pub error: Option<IntoError>,
     pub warning: Option<IntoWarning>,
+    #[cfg(feature = "async")]
+    pub waker: Option<std::task::Waker>,
 }
 
 #[derive(Debug, Clone)]
@@ -22,21 +24,21 @@ where
     IntoWarning: Clone + Send,
 {
     pub fn new() -> Self {
-        Self(std::sync::Arc::new(std::sync::Mutex::new(Inner {
-            error: None,
-            warning: None,
-        })))
+        Self::default()
     }
 
     pub fn store_error_if_not_set<Error>(&self, error: Error)
     where
         Error: Into<IntoError>,
     {
-        self.0
-            .lock()
-            .expect("mutex is not poisoned")
-            .error
-            .get_or_insert(error.into());
+        let mut inner = self.0.lock().expect("mutex is not poisoned");
+        if inner.error.is_none() {
+            inner.error = Some(error.into());
+            #[cfg(feature = "async")]
+            if let Some(waker) = inner.waker.as_ref() {
+                waker.wake_by_ref();
+            }
+        }
     }
 
     pub fn store_warning_if_not_set<Warning>(&self, warning: Warning)
@@ -50,6 +52,28 @@ where
             .get_or_insert(warning.into());
     }
 
+    /// has_error returns true if an error is stored, without taking it.
+    pub fn has_error(&self) -> bool {
+        self.0
+            .lock()
+            .expect("mutex is not poisoned")
+            .error
+            .is_some()
+    }
+
+    /// register_waker arranges for the waker to be woken when an error is stored.
+    #[cfg(feature = "async")]
+    pub fn register_waker(&self, waker: &std::task::Waker) {
+        let mut inner = self.0.lock().expect("mutex is not poisoned");
+        if !inner
+            .waker
+            .as_ref()
+            .is_some_and(|stored_waker| stored_waker.will_wake(waker))
+        {
+            inner.waker = Some(waker.clone());
+        }
+    }
+
     pub fn load_error(&self) -> Result<(), IntoError> {
         match self.0.lock().expect("mutex is not poisoned").error.take() {
             Some(error) => Err(error),
@@ -71,6 +95,8 @@ where
         Self(std::sync::Arc::new(std::sync::Mutex::new(Inner {
             error: None,
             warning: None,
+            #[cfg(feature = "async")]
+            waker: None,
         })))
     }
 }
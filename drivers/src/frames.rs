@@ -0,0 +1,234 @@
+use crate::properties;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    /// Duration frames cover consecutive time windows (in µs).
+    ///
+    /// Windows without events still produce frames so that the frame rate stays constant.
+    Duration(u64),
+
+    /// Count frames are emitted every `n` events.
+    Count(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Histogram counts ON and OFF events separately.
+    Histogram,
+
+    /// SignedSum adds 1 for ON events and subtracts 1 for OFF events.
+    SignedSum,
+
+    /// TimeSurface is exp(-(t - t_pixel) / tau), signed with the polarity of the pixel's last event.
+    ///
+    /// Unlike the other modes, pixels are not reset between frames; tau is in µs.
+    TimeSurface { tau: f32 },
+
+    /// LastPolarity is 1 (ON), -1 (OFF) or 0 (no events during the window).
+    LastPolarity,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pixels {
+    Histogram { on: Vec<u32>, off: Vec<u32> },
+    SignedSum(Vec<i32>),
+    TimeSurface(Vec<f32>),
+    LastPolarity(Vec<i8>),
+}
+
+impl Pixels {
+    fn new(mode: Mode, length: usize) -> Self {
+        match mode {
+            Mode::Histogram => Pixels::Histogram {
+                on: vec![0; length],
+                off: vec![0; length],
+            },
+            Mode::SignedSum => Pixels::SignedSum(vec![0; length]),
+            Mode::TimeSurface { .. } => Pixels::TimeSurface(vec![0.0; length]),
+            Mode::LastPolarity => Pixels::LastPolarity(vec![0; length]),
+        }
+    }
+}
+
+/// Frame pixels are stored in row-major order (index = y * width + x).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// t is the end of the window for duration frames, and the timestamp of the last event for count frames.
+    pub t: u64,
+    pub width: u16,
+    pub height: u16,
+    pub pixels: Pixels,
+}
+
+pub struct Accumulator {
+    window: Window,
+    mode: Mode,
+    frame: Frame,
+    window_start: Option<u64>,
+    count: usize,
+    last_t: Vec<u64>,
+    last_polarity: Vec<i8>,
+}
+
+impl Accumulator {
+    pub fn new(width: u16, height: u16, window: Window, mode: Mode) -> Self {
+        match window {
+            Window::Duration(duration) => {
+                assert!(duration > 0, "the window duration must be larger than 0")
+            }
+            Window::Count(count) => assert!(count > 0, "the window count must be larger than 0"),
+        }
+        let length = width as usize * height as usize;
+        let (last_t, last_polarity) = match mode {
+            Mode::TimeSurface { .. } => (vec![0; length], vec![0; length]),
+            _ => (Vec::new(), Vec::new()),
+        };
+        Self {
+            window,
+            mode,
+            frame: Frame {
+                t: 0,
+                width,
+                height,
+                pixels: Pixels::new(mode, length),
+            },
+            window_start: None,
+            count: 0,
+            last_t,
+            last_polarity,
+        }
+    }
+
+    pub fn from_camera<Configuration>(
+        camera: &properties::Camera<Configuration>,
+        window: Window,
+        mode: Mode,
+    ) -> Self {
+        Self::new(camera.width, camera.height, window, mode)
+    }
+
+    pub fn window(&self) -> Window {
+        self.window
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// push accumulates an event and calls handle_frame for every frame completed before or by it.
+    ///
+    /// Events must be sorted by timestamp and inside the frame (this function panics otherwise).
+    pub fn push<HandleFrame>(
+        &mut self,
+        event: neuromorphic_types::DvsEvent<u64, u16, u16>,
+        mut handle_frame: HandleFrame,
+    ) where
+        HandleFrame: FnMut(&Frame),
+    {
+        // DvsEvent is packed, fields must be copied before use
+        let (t, x, y) = (event.t, event.x, event.y);
+        if let Window::Duration(duration) = self.window {
+            let window_start = *self.window_start.get_or_insert(t);
+            let mut window_end = window_start + duration;
+            while t >= window_end {
+                self.emit(window_end, &mut handle_frame);
+                window_end += duration;
+            }
+            self.window_start = Some(window_end - duration);
+        }
+        assert!(
+            x < self.frame.width && y < self.frame.height,
+            "the coordinates ({}, {}) are outside the frame ({} x {})",
+            x,
+            y,
+            self.frame.width,
+            self.frame.height
+        );
+        let index = y as usize * self.frame.width as usize + x as usize;
+        let on = matches!(event.polarity, neuromorphic_types::DvsPolarity::On);
+        match &mut self.frame.pixels {
+            Pixels::Histogram { on: ons, off: offs } => {
+                if on {
+                    ons[index] += 1;
+                } else {
+                    offs[index] += 1;
+                }
+            }
+            Pixels::SignedSum(sums) => {
+                sums[index] += if on { 1 } else { -1 };
+            }
+            Pixels::TimeSurface(_) => {
+                self.last_t[index] = t;
+                self.last_polarity[index] = if on { 1 } else { -1 };
+            }
+            Pixels::LastPolarity(polarities) => {
+                polarities[index] = if on { 1 } else { -1 };
+            }
+        }
+        if let Window::Count(count) = self.window {
+            self.count += 1;
+            if self.count == count {
+                self.emit(t, &mut handle_frame);
+            }
+        }
+    }
+
+    pub fn extend<Events, HandleFrame>(&mut self, events: Events, mut handle_frame: HandleFrame)
+    where
+        Events: IntoIterator<Item = neuromorphic_types::DvsEvent<u64, u16, u16>>,
+        HandleFrame: FnMut(&Frame),
+    {
+        for event in events {
+            self.push(event, &mut handle_frame);
+        }
+    }
+
+    /// flush emits the current (partial) frame, if it contains at least one event.
+    ///
+    /// For duration frames, the next window starts at the next event.
+    pub fn flush<HandleFrame>(&mut self, t: u64, mut handle_frame: HandleFrame)
+    where
+        HandleFrame: FnMut(&Frame),
+    {
+        let started = match self.window {
+            Window::Duration(_) => self.window_start.take().is_some(),
+            Window::Count(_) => self.count > 0,
+        };
+        if started {
+            self.emit(t, &mut handle_frame);
+        }
+    }
+
+    fn emit<HandleFrame>(&mut self, t: u64, handle_frame: &mut HandleFrame)
+    where
+        HandleFrame: FnMut(&Frame),
+    {
+        self.frame.t = t;
+        if let (Mode::TimeSurface { tau }, Pixels::TimeSurface(values)) =
+            (self.mode, &mut self.frame.pixels)
+        {
+            for ((value, last_t), last_polarity) in values
+                .iter_mut()
+                .zip(self.last_t.iter())
+                .zip(self.last_polarity.iter())
+            {
+                *value = if *last_polarity == 0 {
+                    0.0
+                } else {
+                    *last_polarity as f32 * (-(t.saturating_sub(*last_t) as f32) / tau).exp()
+                };
+            }
+        }
+        handle_frame(&self.frame);
+        match &mut self.frame.pixels {
+            Pixels::Histogram { on, off } => {
+                on.fill(0);
+                off.fill(0);
+            }
+            Pixels::SignedSum(sums) => sums.fill(0),
+            Pixels::TimeSurface(_) => {}
+            Pixels::LastPolarity(polarities) => polarities.fill(0),
+        }
+        self.count = 0;
+    }
+}
@@ -0,0 +1,290 @@
+use crate::devices;
+use crate::filters;
+use crate::flag;
+use crate::usb;
+
+use crate::devices::prophesee_evk4;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Device(#[from] devices::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("the mask ({mask_width} x {mask_height}) and the sensor ({width} x {height}) have different dimensions")]
+    Dimensions {
+        mask_width: u16,
+        mask_height: u16,
+        width: u16,
+        height: u16,
+    },
+
+    #[error("the pixel ({x}, {y}) is outside the mask ({width} x {height})")]
+    Pixel {
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    },
+
+    #[error("{0} pixels need row and column masks, which are not available with roi or mask_intersection_only")]
+    RowColumnMask(usize),
+}
+
+/// Counts stores the number of events of each pixel, in row-major order (index = y * width + x).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counts {
+    pub width: u16,
+    pub height: u16,
+
+    /// duration_us is the span of the sensor's timestamps during the recording.
+    pub duration_us: u64,
+    pub counts: Vec<u64>,
+}
+
+/// record counts the events of each pixel for the given duration.
+///
+/// The sensor should not see any light (lens capped) so that every event is noise.
+/// record must be the only consumer of the device's buffers while it runs.
+pub fn record(
+    device: &devices::Device,
+    flag: &flag::Flag<devices::Error, usb::Overflow>,
+    duration: std::time::Duration,
+) -> Result<Counts, Error> {
+    let properties = device.properties();
+    let width = properties.width();
+    let height = properties.height();
+    let mut counts = vec![0u64; width as usize * height as usize];
+    let mut adapter = device.adapter();
+    let mut begin_t = None;
+    let start = std::time::Instant::now();
+    while start.elapsed() < duration {
+        if let Some(buffer_view) = device.next_with_timeout(&std::time::Duration::from_millis(100))
+        {
+            if begin_t.is_none() {
+                // the new adapter's timestamp is only valid once it has parsed the timestamp's high bits
+                // (EVT3 encodes the low 12 bits separately), buffers are used to synchronise it until then
+                // and are not counted
+                adapter.consume(buffer_view.slice);
+                if adapter.current_t() >= 1 << 12 {
+                    begin_t = Some(adapter.current_t());
+                }
+            } else {
+                adapter.convert(
+                    buffer_view.slice,
+                    |event| {
+                        if let Some(count) =
+                            counts.get_mut(event.y as usize * width as usize + event.x as usize)
+                        {
+                            *count += 1;
+                        }
+                    },
+                    |_| {},
+                );
+            }
+        }
+        flag.load_error()?;
+    }
+    Ok(Counts {
+        width,
+        height,
+        duration_us: adapter
+            .current_t()
+            .saturating_sub(begin_t.unwrap_or_default()),
+        counts,
+    })
+}
+
+impl Counts {
+    /// ranked returns the pixels with at least one event and their counts, from most to least active.
+    pub fn ranked(&self) -> Vec<((u16, u16), u64)> {
+        let mut ranked: Vec<((u16, u16), u64)> = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| {
+                (
+                    (
+                        (index % self.width as usize) as u16,
+                        (index / self.width as usize) as u16,
+                    ),
+                    *count,
+                )
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// mask selects the pixels whose event rate (in events/s) is larger than hot_pixel_rate.
+    ///
+    /// If maximum_pixels is set, only the most active hot pixels are kept.
+    pub fn mask(&self, hot_pixel_rate: f64, maximum_pixels: Option<usize>) -> Mask {
+        let threshold = (hot_pixel_rate * self.duration_us as f64 / 1e6) as u64;
+        let mut pixels: Vec<(u16, u16)> = self
+            .ranked()
+            .into_iter()
+            .take_while(|(_, count)| *count > threshold)
+            .map(|(pixel, _)| pixel)
+            .collect();
+        if let Some(maximum_pixels) = maximum_pixels {
+            pixels.truncate(maximum_pixels);
+        }
+        Mask {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+}
+
+/// Mask lists hot pixels in event coordinates, from most to least active.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct Mask {
+    pub width: u16,
+    pub height: u16,
+    pub pixels: Vec<(u16, u16)>,
+}
+
+/// path returns the location of a device's mask in the given directory.
+pub fn path<P: AsRef<std::path::Path>>(directory: P, serial: &str) -> std::path::PathBuf {
+    directory.as_ref().join(format!("{}.json", serial))
+}
+
+impl Mask {
+    /// save writes the mask to the device's path in the directory (see path).
+    pub fn save<P: AsRef<std::path::Path>>(&self, directory: P, serial: &str) -> Result<(), Error> {
+        std::fs::create_dir_all(directory.as_ref())?;
+        std::fs::write(path(directory, serial), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// load reads the device's mask from the directory, it returns None if the device has no mask.
+    ///
+    /// The mask is validated since the file may have been edited by hand.
+    pub fn load<P: AsRef<std::path::Path>>(
+        directory: P,
+        serial: &str,
+    ) -> Result<Option<Self>, Error> {
+        match std::fs::read(path(directory, serial)) {
+            Ok(bytes) => {
+                let mask: Self = serde_json::from_slice(&bytes)?;
+                mask.validate()?;
+                Ok(Some(mask))
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// validate checks that the mask's pixels are within its dimensions.
+    pub fn validate(&self) -> Result<(), Error> {
+        match self
+            .pixels
+            .iter()
+            .find(|(x, y)| *x >= self.width || *y >= self.height)
+        {
+            Some((x, y)) => Err(Error::Pixel {
+                x: *x,
+                y: *y,
+                width: self.width,
+                height: self.height,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// filter returns a host-side filter that drops the events of the mask's pixels.
+    ///
+    /// filter panics if a pixel is outside the mask, see validate.
+    pub fn filter(&self) -> filters::Mask {
+        filters::Mask::new(self.width, self.height, &self.pixels)
+    }
+
+    /// apply_to_prophesee_evk4 replaces the configuration's pixel_mask and masked_pixels with the mask.
+    ///
+    /// If the mask has more pixels than the digital mask's slots, the rows and columns that
+    /// contain the most hot pixels are added to x_mask and y_mask until the remaining pixels fit.
+    /// apply_to_prophesee_evk4 returns the number of rows and columns added this way, since a large
+    /// number indicates a bad mask (for instance, recorded with the lens uncapped).
+    pub fn apply_to_prophesee_evk4(
+        &self,
+        configuration: &mut prophesee_evk4::Configuration,
+    ) -> Result<usize, Error> {
+        let width = prophesee_evk4::PROPERTIES.width;
+        let height = prophesee_evk4::PROPERTIES.height;
+        if self.width != width || self.height != height {
+            return Err(Error::Dimensions {
+                mask_width: self.width,
+                mask_height: self.height,
+                width,
+                height,
+            });
+        }
+        self.validate()?;
+        let row_column_masks = configuration.roi.is_none() && !configuration.mask_intersection_only;
+        let mut x_mask = configuration.x_mask;
+        let mut y_mask = configuration.y_mask;
+        let mut pixels = self.pixels.clone();
+        let mut masked_lines = 0;
+        if row_column_masks {
+            pixels.retain(|(x, y)| {
+                (x_mask[*x as usize / 64] >> (*x % 64)) & 1 == 0
+                    && (y_mask[*y as usize / 64] >> (*y % 64)) & 1 == 0
+            });
+        }
+        if pixels.len() > prophesee_evk4::DIGITAL_MASK_CAPACITY {
+            if !row_column_masks {
+                return Err(Error::RowColumnMask(pixels.len()));
+            }
+            let mut column_counts = vec![0usize; width as usize];
+            let mut row_counts = vec![0usize; height as usize];
+            while pixels.len() > prophesee_evk4::DIGITAL_MASK_CAPACITY {
+                column_counts.fill(0);
+                row_counts.fill(0);
+                for (x, y) in pixels.iter() {
+                    column_counts[*x as usize] += 1;
+                    row_counts[*y as usize] += 1;
+                }
+                let (column, column_count) = most_hot_pixels(&column_counts);
+                let (row, row_count) = most_hot_pixels(&row_counts);
+                if column_count >= row_count {
+                    x_mask[column / 64] |= 1 << (column % 64);
+                    pixels.retain(|(x, _)| *x as usize != column);
+                } else {
+                    y_mask[row / 64] |= 1 << (row % 64);
+                    pixels.retain(|(_, y)| *y as usize != row);
+                }
+                masked_lines += 1;
+            }
+        }
+        configuration.x_mask = x_mask;
+        configuration.y_mask = y_mask;
+        configuration.pixel_mask = [0; 21];
+        configuration.masked_pixels = pixels
+            .into_iter()
+            .map(|(x, y)| prophesee_evk4::Pixel { x, y })
+            .collect();
+        Ok(masked_lines)
+    }
+}
+
+/// most_hot_pixels returns the index of the largest count (the first one if there are ties).
+fn most_hot_pixels(counts: &[usize]) -> (usize, usize) {
+    counts
+        .iter()
+        .enumerate()
+        .fold((0, 0), |best, (index, count)| {
+            if *count > best.1 {
+                (index, *count)
+            } else {
+                best
+            }
+        })
+}
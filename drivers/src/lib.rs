@@ -1,9 +1,17 @@
 pub mod adapters;
+pub mod aedat4;
 pub mod configuration;
+pub mod container;
 pub mod device;
 pub mod devices;
+pub mod event_stream;
+pub mod filters;
 pub mod flag;
+pub mod frames;
+pub mod hot_pixels;
 pub mod properties;
+pub mod raw;
+pub mod tuning;
 pub mod usb;
 
 pub use adapters::Adapter;
@@ -0,0 +1,669 @@
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
+
+use crate::adapters;
+use crate::configuration;
+use crate::devices;
+use crate::usb;
+
+pub const CHUNK_LENGTH: usize = 1 << 20;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("unsupported event format \"{0}\"")]
+    Format(String),
+
+    #[error("the header does not specify the event format")]
+    MissingFormat,
+
+    #[error("the header does not specify the sensor geometry")]
+    MissingGeometry,
+
+    #[error("parsing the header entry \"{key}\" failed (value \"{value}\")")]
+    Entry { key: String, value: String },
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Evt2,
+    Evt21,
+    Evt3,
+}
+
+impl Format {
+    pub fn adapter(self, width: u16, height: u16) -> adapters::Adapter {
+        match self {
+            Format::Evt2 => adapters::evt2::Adapter::from_dimensions(width, height).into(),
+            Format::Evt21 => adapters::evt21::Adapter::from_dimensions(width, height).into(),
+            Format::Evt3 => adapters::evt3::Adapter::from_dimensions(width, height).into(),
+        }
+    }
+
+    /// Name used by the "format" header entry.
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Evt2 => "EVT2",
+            Format::Evt21 => "EVT21",
+            Format::Evt3 => "EVT3",
+        }
+    }
+
+    /// Version used by the "evt" header entry (older Metavision files).
+    pub fn version(self) -> &'static str {
+        match self {
+            Format::Evt2 => "2.0",
+            Format::Evt21 => "2.1",
+            Format::Evt3 => "3.0",
+        }
+    }
+
+    /// Word length in bytes.
+    pub fn word_length(self) -> usize {
+        match self {
+            Format::Evt2 => 4,
+            Format::Evt21 => 8,
+            Format::Evt3 => 2,
+        }
+    }
+
+    /// split_word returns the type (4 most significant bits) and the 12 least significant bits of a word.
+    fn split_word(self, word: &[u8]) -> (u8, u16) {
+        match self {
+            Format::Evt2 => {
+                let word = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+                ((word >> 28) as u8, (word & 0xfff) as u16)
+            }
+            Format::Evt21 => {
+                let word = u64::from_le_bytes([
+                    word[0], word[1], word[2], word[3], word[4], word[5], word[6], word[7],
+                ]);
+                ((word >> 60) as u8, (word & 0xfff) as u16)
+            }
+            Format::Evt3 => {
+                let word = u16::from_le_bytes([word[0], word[1]]);
+                ((word >> 12) as u8, word & 0xfff)
+            }
+        }
+    }
+
+    fn push_word(self, word_type: u8, payload: u16, bytes: &mut Vec<u8>) {
+        match self {
+            Format::Evt2 => bytes
+                .extend_from_slice(&(((word_type as u32) << 28) | payload as u32).to_le_bytes()),
+            Format::Evt21 => bytes
+                .extend_from_slice(&(((word_type as u64) << 60) | payload as u64).to_le_bytes()),
+            Format::Evt3 => {
+                bytes.extend_from_slice(&(((word_type as u16) << 12) | payload).to_le_bytes())
+            }
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Self, Error> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "EVT2" | "EVT20" | "EVT2.0" | "2.0" => Ok(Format::Evt2),
+            "EVT21" | "EVT2.1" | "2.1" => Ok(Format::Evt21),
+            "EVT3" | "EVT30" | "EVT3.0" | "3.0" => Ok(Format::Evt3),
+            _ => Err(Error::Format(name.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Header {
+    pub format: Format,
+    pub width: u16,
+    pub height: u16,
+    pub serial: Option<String>,
+    pub date: Option<String>,
+
+    /// All the header entries, in file order, including those parsed into the fields above.
+    pub entries: Vec<(String, String)>,
+}
+
+impl From<&adapters::Adapter> for Format {
+    fn from(adapter: &adapters::Adapter) -> Self {
+        match adapter {
+            adapters::Adapter::Evt3(_) => Format::Evt3,
+            adapters::Adapter::Evt2(_) => Format::Evt2,
+            adapters::Adapter::Evt21(_) => Format::Evt21,
+        }
+    }
+}
+
+const STANDARD_KEYS: [&str; 5] = ["date", "evt", "format", "geometry", "serial_number"];
+
+impl Header {
+    /// Describes a device's stream, with the configuration stored as JSON in the entry
+    /// "neuromorphic_drivers_configuration".
+    pub fn from_device(
+        device: &devices::Device,
+        configuration: &devices::Configuration,
+    ) -> Result<Self, Error> {
+        let properties = device.properties();
+        Ok(Header {
+            format: (&device.adapter()).into(),
+            width: properties.width(),
+            height: properties.height(),
+            serial: Some(device.serial()),
+            date: Some(utc_date(std::time::SystemTime::now())),
+            entries: vec![
+                (
+                    "camera_integrator_name".to_owned(),
+                    properties.name().to_owned(),
+                ),
+                (
+                    "neuromorphic_drivers_configuration".to_owned(),
+                    serde_json::to_string(configuration)?,
+                ),
+            ],
+        })
+    }
+
+    /// Writes the header, terminated by "% end".
+    ///
+    /// The standard entries are generated from the fields, the other entries are copied as is.
+    pub fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if let Some(date) = self.date.as_ref() {
+            writeln!(writer, "% date {}", date)?;
+        }
+        writeln!(writer, "% evt {}", self.format.version())?;
+        writeln!(
+            writer,
+            "% format {};height={};width={}",
+            self.format.name(),
+            self.height,
+            self.width
+        )?;
+        writeln!(writer, "% geometry {}x{}", self.width, self.height)?;
+        if let Some(serial) = self.serial.as_ref() {
+            writeln!(writer, "% serial_number {}", serial)?;
+        }
+        for (key, value) in self.entries.iter() {
+            if !STANDARD_KEYS.contains(&key.as_str()) {
+                writeln!(writer, "% {} {}", key, value.replace('\n', " "))?;
+            }
+        }
+        writeln!(writer, "% end")
+    }
+
+    /// Parses a Metavision header (lines that start with "% ") and returns it with its length in bytes.
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<(Self, u64), Error> {
+        let mut length = 0u64;
+        let mut entries = Vec::new();
+        let mut line = Vec::new();
+        loop {
+            if reader.fill_buf()?.first() != Some(&b'%') {
+                break;
+            }
+            line.clear();
+            length += reader.read_until(b'\n', &mut line)? as u64;
+            let line = String::from_utf8_lossy(&line);
+            let line = line[1..].trim();
+            if line == "end" {
+                break;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            entries.push((key.to_owned(), value.trim().to_owned()));
+        }
+        let mut format = None;
+        let mut width = None;
+        let mut height = None;
+        let mut serial = None;
+        let mut date = None;
+        let parse = |key: &str, value: &str| -> Result<u16, Error> {
+            value.trim().parse().map_err(|_| Error::Entry {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            })
+        };
+        for (key, value) in entries.iter() {
+            match key.as_str() {
+                // recent files, for instance "format EVT3;height=720;width=1280"
+                "format" => {
+                    let mut parts = value.split(';');
+                    format = Some(Format::from_name(parts.next().unwrap_or(""))?);
+                    for part in parts {
+                        match part.split_once('=') {
+                            Some(("width", option)) => width = Some(parse(key, option)?),
+                            Some(("height", option)) => height = Some(parse(key, option)?),
+                            _ => (),
+                        }
+                    }
+                }
+                "evt" if format.is_none() => format = Some(Format::from_name(value)?),
+                "geometry" => {
+                    let (geometry_width, geometry_height) =
+                        value.split_once('x').ok_or_else(|| Error::Entry {
+                            key: key.clone(),
+                            value: value.clone(),
+                        })?;
+                    width = Some(parse(key, geometry_width)?);
+                    height = Some(parse(key, geometry_height)?);
+                }
+                "Width" | "width" => width = Some(parse(key, value)?),
+                "Height" | "height" => height = Some(parse(key, value)?),
+                "serial_number" | "serial" => serial = Some(value.clone()),
+                "date" | "Date" => date = Some(value.clone()),
+                _ => (),
+            }
+        }
+        Ok((
+            Header {
+                format: format.ok_or(Error::MissingFormat)?,
+                width: width.ok_or(Error::MissingGeometry)?,
+                height: height.ok_or(Error::MissingGeometry)?,
+                serial,
+                date,
+                entries,
+            },
+            length,
+        ))
+    }
+}
+
+pub struct Chunk {
+    pub dvs_events: Vec<neuromorphic_types::DvsEvent<u64, u16, u16>>,
+    pub trigger_events: Vec<neuromorphic_types::TriggerEvent<u64, u8>>,
+
+    /// Configuration changes that end in this chunk, see CONFIGURATION_CHANGE_SUBTYPE.
+    pub configuration_changes: Vec<ConfigurationChange>,
+}
+
+struct IndexEntry {
+    t: u64,
+    offset: u64,
+    adapter: adapters::Adapter,
+}
+
+pub struct Reader {
+    file: std::fs::File,
+    header: Header,
+    adapter: adapters::Adapter,
+    offset: u64,
+    minimum_t: u64,
+    buffer: Vec<u8>,
+    configuration_change: Option<PartialConfigurationChange>,
+
+    // the index has one entry per chunk boundary, with the adapter state at that boundary
+    index: Vec<IndexEntry>,
+    index_complete: bool,
+}
+
+impl Reader {
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        let mut file = std::fs::File::open(&path)?;
+        let (header, length) = Header::read(&mut std::io::BufReader::new(&mut file))?;
+        Ok(Self::from_file(file, header, length))
+    }
+
+    /// Opens a file that contains raw USB bytes without a header, for instance a file written by
+    /// python/examples/any_record_raw.py.
+    pub fn open_headerless<P: AsRef<std::path::Path>>(
+        path: P,
+        format: Format,
+        width: u16,
+        height: u16,
+    ) -> Result<Self, Error> {
+        Ok(Self::from_file(
+            std::fs::File::open(&path)?,
+            Header {
+                format,
+                width,
+                height,
+                serial: None,
+                date: None,
+                entries: Vec::new(),
+            },
+            0,
+        ))
+    }
+
+    fn from_file(file: std::fs::File, header: Header, data_offset: u64) -> Self {
+        let adapter = header.format.adapter(header.width, header.height);
+        Self {
+            file,
+            index: vec![IndexEntry {
+                t: 0,
+                offset: data_offset,
+                adapter: adapter.clone(),
+            }],
+            index_complete: false,
+            header,
+            adapter,
+            offset: data_offset,
+            minimum_t: 0,
+            buffer: vec![0u8; CHUNK_LENGTH],
+            configuration_change: None,
+        }
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn current_t(&self) -> u64 {
+        self.adapter.current_t()
+    }
+
+    /// Returns None at the end of the file.
+    ///
+    /// Chunks start at fixed byte offsets and may be empty after a call to seek.
+    pub fn next_chunk(&mut self) -> Result<Option<Chunk>, Error> {
+        let length = self.read_chunk(self.offset)?;
+        if length == 0 {
+            return Ok(None);
+        }
+        let slice = &self.buffer[0..length];
+        let events_lengths = self.adapter.events_lengths(slice);
+        let mut chunk = Chunk {
+            dvs_events: Vec::with_capacity(events_lengths.dvs),
+            trigger_events: Vec::with_capacity(events_lengths.trigger),
+            configuration_changes: Vec::new(),
+        };
+        let minimum_t = self.minimum_t;
+        let format = self.header.format;
+        let word_length = format.word_length();
+        // the slice is converted up to each configuration change to read its timestamp
+        let mut converted = 0;
+        for offset in (0..slice.len() - slice.len() % word_length).step_by(word_length) {
+            let (word_type, payload) = format.split_word(&slice[offset..offset + word_length]);
+            if word_type == OTHERS && payload == CONFIGURATION_CHANGE_SUBTYPE {
+                convert(
+                    &mut self.adapter,
+                    &slice[converted..offset],
+                    minimum_t,
+                    &mut chunk,
+                );
+                converted = offset;
+                self.configuration_change = Some(PartialConfigurationChange {
+                    t: self.adapter.current_t(),
+                    bytes: Vec::new(),
+                });
+            } else if let Some(configuration_change) = self.configuration_change.as_mut() {
+                if word_type == CONTINUED {
+                    if configuration_change.push(payload as u8) {
+                        let configuration_change = self
+                            .configuration_change
+                            .take()
+                            .expect("configuration_change is not None");
+                        if configuration_change.t >= minimum_t {
+                            chunk.configuration_changes.push(ConfigurationChange {
+                                t: configuration_change.t,
+                                configuration: serde_json::from_slice(
+                                    &configuration_change.bytes[4..],
+                                )?,
+                            });
+                        }
+                    }
+                } else {
+                    // truncated change (for instance after a seek)
+                    self.configuration_change = None;
+                }
+            }
+        }
+        convert(
+            &mut self.adapter,
+            &slice[converted..],
+            minimum_t,
+            &mut chunk,
+        );
+        self.push_index_entry(self.offset, length, self.adapter.clone());
+        self.offset += length as u64;
+        Ok(Some(chunk))
+    }
+
+    /// Moves the reader so that the next chunks only contain events with a timestamp larger than or equal to t.
+    ///
+    /// The first seek past the indexed region scans the file (without converting events) to extend the index.
+    pub fn seek(&mut self, t: u64) -> Result<(), Error> {
+        loop {
+            let last = self.index.last().expect("the index is not empty");
+            if last.t >= t || self.index_complete {
+                break;
+            }
+            let offset = last.offset;
+            let mut adapter = last.adapter.clone();
+            let length = self.read_chunk(offset)?;
+            adapter.consume(&self.buffer[0..length]);
+            self.push_index_entry(offset, length, adapter);
+        }
+        // events before an index entry have a timestamp smaller than or equal to the entry's
+        let position = self.index.partition_point(|entry| entry.t < t);
+        let entry = &self.index[position.saturating_sub(1)];
+        self.offset = entry.offset;
+        self.adapter = entry.adapter.clone();
+        self.minimum_t = t;
+        self.configuration_change = None;
+        Ok(())
+    }
+
+    fn push_index_entry(&mut self, offset: u64, length: usize, adapter: adapters::Adapter) {
+        let last = self.index.last().expect("the index is not empty");
+        if last.offset == offset && !self.index_complete {
+            if length == CHUNK_LENGTH {
+                self.index.push(IndexEntry {
+                    t: adapter.current_t(),
+                    offset: offset + length as u64,
+                    adapter,
+                });
+            } else {
+                self.index_complete = true;
+            }
+        }
+    }
+
+    fn read_chunk(&mut self, offset: u64) -> Result<usize, Error> {
+        self.file.seek(std::io::SeekFrom::Start(offset))?;
+        let mut length = 0;
+        while length < CHUNK_LENGTH {
+            let read = self.file.read(&mut self.buffer[length..])?;
+            if read == 0 {
+                break;
+            }
+            length += read;
+        }
+        Ok(length)
+    }
+}
+
+fn convert(adapter: &mut adapters::Adapter, slice: &[u8], minimum_t: u64, chunk: &mut Chunk) {
+    adapter.convert(
+        slice,
+        |dvs_event| {
+            if dvs_event.t >= minimum_t {
+                chunk.dvs_events.push(dvs_event);
+            }
+        },
+        |trigger_event| {
+            if trigger_event.t >= minimum_t {
+                chunk.trigger_events.push(trigger_event);
+            }
+        },
+    );
+}
+
+impl Iterator for Reader {
+    type Item = Result<Chunk, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_chunk().transpose()
+    }
+}
+
+/// A configuration that took effect at the given timestamp (in the recording's time base).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConfigurationChange {
+    pub t: u64,
+    pub configuration: devices::Configuration,
+}
+
+/// Configuration changes are stored in the event stream as an OTHERS word with this subtype,
+/// followed by CONTINUED words that carry one byte each: the length of the JSON-encoded
+/// configuration (four little-endian bytes), then the JSON bytes.
+///
+/// Decoders skip CONTINUED words and OTHERS words with an unknown subtype, hence recordings with
+/// configuration changes remain readable by other tools. A change's timestamp is the timestamp
+/// of the stream before the OTHERS word.
+pub const CONFIGURATION_CHANGE_SUBTYPE: u16 = 0xCF0;
+
+const OTHERS: u8 = 0b1110;
+const CONTINUED: u8 = 0b1111;
+
+struct PartialConfigurationChange {
+    t: u64,
+    bytes: Vec<u8>,
+}
+
+impl PartialConfigurationChange {
+    /// push returns true once the change is complete (length and JSON bytes).
+    fn push(&mut self, byte: u8) -> bool {
+        self.bytes.push(byte);
+        self.bytes.len() >= 4
+            && self.bytes.len() - 4
+                == u32::from_le_bytes([self.bytes[0], self.bytes[1], self.bytes[2], self.bytes[3]])
+                    as usize
+    }
+}
+
+pub struct Recorder {
+    file: std::fs::File,
+    format: Format,
+    adapter: adapters::Adapter,
+    applied_configurations:
+        Option<std::sync::mpsc::Receiver<configuration::Applied<devices::Configuration>>>,
+    pending_configurations:
+        std::collections::VecDeque<configuration::Applied<devices::Configuration>>,
+}
+
+impl Recorder {
+    pub fn create<P: AsRef<std::path::Path>>(path: P, header: &Header) -> Result<Self, Error> {
+        let mut file = std::fs::File::create(&path)?;
+        let mut bytes = Vec::new();
+        header.write(&mut bytes)?;
+        file.write_all(&bytes)?;
+        Ok(Self {
+            file,
+            format: header.format,
+            adapter: header.format.adapter(header.width, header.height),
+            applied_configurations: None,
+            pending_configurations: std::collections::VecDeque::new(),
+        })
+    }
+
+    /// Creates a recorder with Header::from_device that records the device's configuration changes.
+    ///
+    /// Changes applied by the device's updater thread are recorded by write, before the first buffer
+    /// view received after the change.
+    pub fn from_device<P: AsRef<std::path::Path>>(
+        path: P,
+        device: &devices::Device,
+        configuration: &devices::Configuration,
+    ) -> Result<Self, Error> {
+        let mut recorder = Self::create(path, &Header::from_device(device, configuration)?)?;
+        recorder.applied_configurations = Some(device.applied_configurations());
+        Ok(recorder)
+    }
+
+    /// Writes the view's bytes directly to the file (without an intermediate copy).
+    ///
+    /// Pending configurations applied before the view's instant are recorded first,
+    /// with the timestamp of the last event written so far.
+    pub fn write(&mut self, buffer_view: &usb::BufferView) -> Result<(), Error> {
+        if let Some(applied_configurations) = self.applied_configurations.as_ref() {
+            self.pending_configurations
+                .extend(applied_configurations.try_iter());
+        }
+        while self
+            .pending_configurations
+            .front()
+            .is_some_and(|applied| applied.instant <= buffer_view.instant)
+        {
+            let applied = self
+                .pending_configurations
+                .pop_front()
+                .expect("pending_configurations is not empty");
+            self.write_configuration_change(&applied.configuration)?;
+        }
+        self.write_slice(buffer_view.slice)
+    }
+
+    pub fn write_slice(&mut self, slice: &[u8]) -> Result<(), Error> {
+        self.file.write_all(slice)?;
+        self.adapter.consume(slice);
+        Ok(())
+    }
+
+    /// Queues configurations applied by a device that was not passed to from_device.
+    ///
+    /// They are recorded by the first call to write with a later buffer view.
+    pub fn push_applied_configurations(
+        &mut self,
+        applied_configurations: Vec<configuration::Applied<devices::Configuration>>,
+    ) {
+        self.pending_configurations.extend(applied_configurations);
+    }
+
+    /// Records a configuration change at the current timestamp.
+    pub fn write_configuration_change(
+        &mut self,
+        configuration: &devices::Configuration,
+    ) -> Result<(), Error> {
+        let json = serde_json::to_vec(configuration)?;
+        let mut bytes = Vec::with_capacity((json.len() + 5) * self.format.word_length());
+        self.format
+            .push_word(OTHERS, CONFIGURATION_CHANGE_SUBTYPE, &mut bytes);
+        for byte in (json.len() as u32).to_le_bytes().iter().chain(json.iter()) {
+            self.format.push_word(CONTINUED, *byte as u16, &mut bytes);
+        }
+        self.file.write_all(&bytes)?;
+        Ok(())
+    }
+
+    pub fn current_t(&self) -> u64 {
+        self.adapter.current_t()
+    }
+
+    pub fn sync(&mut self) -> Result<(), Error> {
+        self.file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// Formats a time as "YYYY-MM-DD hh:mm:ss" (UTC), like Metavision headers.
+fn utc_date(time: std::time::SystemTime) -> String {
+    let seconds = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    // civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        (seconds % 86400) / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
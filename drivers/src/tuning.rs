@@ -0,0 +1,406 @@
+use crate::adapters;
+use crate::devices;
+use crate::flag;
+use crate::usb;
+
+/// Bias names a field of the cameras' biases.
+///
+/// Fo and Inv are not available on the Prophesee EVK3 HD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bias {
+    Pr,
+    Fo,
+    Hpf,
+    DiffOn,
+    Diff,
+    DiffOff,
+    Inv,
+    Refr,
+}
+
+impl std::fmt::Display for Bias {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                Bias::Pr => "pr",
+                Bias::Fo => "fo",
+                Bias::Hpf => "hpf",
+                Bias::DiffOn => "diff_on",
+                Bias::Diff => "diff",
+                Bias::DiffOff => "diff_off",
+                Bias::Inv => "inv",
+                Bias::Refr => "refr",
+            }
+        )
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Error {
+    #[error(transparent)]
+    Device(#[from] devices::Error),
+
+    #[error("the {device} has no bias {bias}")]
+    Bias { bias: Bias, device: &'static str },
+
+    #[error("the range {first}..={last} is empty")]
+    Range { first: u8, last: u8 },
+}
+
+macro_rules! bias_field {
+    (ref $biases:expr, $bias:expr, $device:expr, [$($name:ident => $field:ident),*]) => {
+        match $bias {
+            $(
+                Bias::$name => Some(&$biases.$field),
+            )*
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+        .ok_or(Error::Bias {
+            bias: $bias,
+            device: $device,
+        })
+    };
+    (mut $biases:expr, $bias:expr, $device:expr, [$($name:ident => $field:ident),*]) => {
+        match $bias {
+            $(
+                Bias::$name => Some(&mut $biases.$field),
+            )*
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+        .ok_or(Error::Bias {
+            bias: $bias,
+            device: $device,
+        })
+    };
+}
+
+/// bias_reference generates a function that returns a shared (ref) or mutable (mut) reference to a bias.
+macro_rules! bias_reference {
+    ($function:ident, $kind:tt, $configuration:ty, $value:ty) => {
+        fn $function(configuration: $configuration, bias: Bias) -> Result<$value, Error> {
+            match configuration {
+                devices::Configuration::PropheseeEvk3Hd(configuration) => bias_field!(
+                    $kind configuration.biases,
+                    bias,
+                    "Prophesee EVK3 HD",
+                    [Pr => pr, Hpf => hpf, DiffOn => diff_on, Diff => diff, DiffOff => diff_off, Refr => refr]
+                ),
+                devices::Configuration::PropheseeEvk4(configuration) => bias_field!(
+                    $kind configuration.biases,
+                    bias,
+                    "Prophesee EVK4",
+                    [Pr => pr, Fo => fo, Hpf => hpf, DiffOn => diff_on, Diff => diff, DiffOff => diff_off, Inv => inv, Refr => refr]
+                ),
+                devices::Configuration::CenturyarksSilkyevcamhd(configuration) => bias_field!(
+                    $kind configuration.biases,
+                    bias,
+                    "CenturyArks SilkyEvCamHD",
+                    [Pr => pr, Fo => fo, Hpf => hpf, DiffOn => diff_on, Diff => diff, DiffOff => diff_off, Inv => inv, Refr => refr]
+                ),
+                devices::Configuration::Mock(_) => Err(Error::Bias {
+                    bias,
+                    device: "Mock",
+                }),
+            }
+        }
+    };
+}
+
+bias_reference!(bias_ref, ref, &devices::Configuration, &u8);
+bias_reference!(bias_mut, mut, &mut devices::Configuration, &mut u8);
+
+/// bias returns the value of a bias in the configuration.
+pub fn bias(configuration: &devices::Configuration, bias: Bias) -> Result<u8, Error> {
+    bias_ref(configuration, bias).copied()
+}
+
+/// set_bias changes the value of a bias in the configuration (the device is not updated).
+pub fn set_bias(
+    configuration: &mut devices::Configuration,
+    bias: Bias,
+    value: u8,
+) -> Result<(), Error> {
+    *bias_mut(configuration, bias)? = value;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Parameters {
+    /// settle is the time given to the sensor after a configuration update, events are discarded.
+    pub settle: std::time::Duration,
+
+    /// duration is the measurement time for each set of biases.
+    pub duration: std::time::Duration,
+
+    /// Pixels whose event rate (in events/s) is larger than hot_pixel_rate are hot.
+    pub hot_pixel_rate: f64,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            settle: std::time::Duration::from_millis(200),
+            duration: std::time::Duration::from_secs(1),
+            hot_pixel_rate: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    /// duration_us is the span of the sensor's timestamps during the measurement.
+    pub duration_us: u64,
+    pub on_events: u64,
+    pub off_events: u64,
+    pub hot_pixels: usize,
+}
+
+impl Measurement {
+    /// event_rate is in events per second.
+    pub fn event_rate(&self) -> f64 {
+        if self.duration_us == 0 {
+            0.0
+        } else {
+            (self.on_events + self.off_events) as f64 * 1e6 / self.duration_us as f64
+        }
+    }
+
+    /// on_off_ratio is infinite if there are ON events but no OFF events, and NaN without events.
+    pub fn on_off_ratio(&self) -> f64 {
+        self.on_events as f64 / self.off_events as f64
+    }
+}
+
+/// Row is a measurement and the biases that were swept to obtain it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub biases: Vec<(Bias, u8)>,
+    pub measurement: Measurement,
+}
+
+/// Table's Display implementation prints one line per row, in measurement order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub rows: Vec<Row>,
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(first) = self.rows.first() {
+            for (bias, _) in first.biases.iter() {
+                write!(formatter, "{:>9} ", bias.to_string())?;
+            }
+        }
+        writeln!(
+            formatter,
+            "{:>14} {:>8} {:>10}",
+            "rate (ev/s)", "on/off", "hot pixels"
+        )?;
+        for row in self.rows.iter() {
+            for (_, value) in row.biases.iter() {
+                write!(formatter, "{:>9} ", format!("0x{:02X}", value))?;
+            }
+            writeln!(
+                formatter,
+                "{:>14.0} {:>8.3} {:>10}",
+                row.measurement.event_rate(),
+                row.measurement.on_off_ratio(),
+                row.measurement.hot_pixels
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Tuner changes a device's biases with update_configuration and measures the resulting events.
+///
+/// The tuner must be the only consumer of the device's buffers while it is used.
+pub struct Tuner<'a> {
+    device: &'a devices::Device,
+    flag: &'a flag::Flag<devices::Error, usb::Overflow>,
+    adapter: adapters::Adapter,
+    configuration: devices::Configuration,
+    parameters: Parameters,
+}
+
+impl<'a> Tuner<'a> {
+    /// configuration must be the device's current configuration, other fields are left unchanged.
+    pub fn new(
+        device: &'a devices::Device,
+        flag: &'a flag::Flag<devices::Error, usb::Overflow>,
+        configuration: devices::Configuration,
+        parameters: Parameters,
+    ) -> Self {
+        Self {
+            device,
+            flag,
+            adapter: device.adapter(),
+            configuration,
+            parameters,
+        }
+    }
+
+    /// configuration returns the last configuration sent to the device.
+    pub fn configuration(&self) -> &devices::Configuration {
+        &self.configuration
+    }
+
+    /// update sends the configuration to the device, without waiting for the sensor to settle.
+    pub fn update(&mut self, configuration: devices::Configuration) -> Result<(), Error> {
+        self.device.update_configuration(configuration.clone())?;
+        self.configuration = configuration;
+        Ok(())
+    }
+
+    /// measure discards the events for parameters.settle, then counts events for parameters.duration.
+    pub fn measure(&mut self) -> Result<Measurement, Error> {
+        let timeout = std::time::Duration::from_millis(100);
+        let start = std::time::Instant::now();
+        while start.elapsed() < self.parameters.settle {
+            if let Some(buffer_view) = self.device.next_with_timeout(&timeout) {
+                self.adapter.consume(buffer_view.slice);
+            }
+            self.flag.load_error()?;
+        }
+        let properties = self.device.properties();
+        let width = properties.width() as usize;
+        let mut counts = vec![0u32; width * properties.height() as usize];
+        let mut on_events = 0u64;
+        let mut off_events = 0u64;
+        let begin_t = self.adapter.current_t();
+        let start = std::time::Instant::now();
+        while start.elapsed() < self.parameters.duration {
+            if let Some(buffer_view) = self.device.next_with_timeout(&timeout) {
+                self.adapter.convert(
+                    buffer_view.slice,
+                    |event| {
+                        if let Some(count) =
+                            counts.get_mut(event.y as usize * width + event.x as usize)
+                        {
+                            *count += 1;
+                        }
+                        match event.polarity {
+                            neuromorphic_types::DvsPolarity::Off => off_events += 1,
+                            neuromorphic_types::DvsPolarity::On => on_events += 1,
+                        }
+                    },
+                    |_| {},
+                );
+            }
+            self.flag.load_error()?;
+        }
+        let duration_us = self.adapter.current_t().saturating_sub(begin_t);
+        let hot_pixel_count = (self.parameters.hot_pixel_rate * duration_us as f64 / 1e6) as u32;
+        Ok(Measurement {
+            duration_us,
+            on_events,
+            off_events,
+            hot_pixels: counts
+                .iter()
+                .filter(|count| **count > hot_pixel_count)
+                .count(),
+        })
+    }
+
+    /// sweep measures every combination of the given bias values (the last bias changes fastest).
+    ///
+    /// The device is restored to the tuner's configuration before sweep returns, even if a measurement fails.
+    pub fn sweep(&mut self, biases: &[(Bias, Vec<u8>)]) -> Result<Table, Error> {
+        let initial_configuration = self.configuration.clone();
+        let result = self.sweep_from(&initial_configuration, biases);
+        let restored = self.update(initial_configuration);
+        let table = result?;
+        restored?;
+        Ok(table)
+    }
+
+    fn sweep_from(
+        &mut self,
+        initial_configuration: &devices::Configuration,
+        biases: &[(Bias, Vec<u8>)],
+    ) -> Result<Table, Error> {
+        let mut table = Table::default();
+        let combinations: usize = biases.iter().map(|(_, values)| values.len()).product();
+        for mut index in 0..combinations {
+            let mut configuration = initial_configuration.clone();
+            let mut row_biases = vec![(Bias::Pr, 0u8); biases.len()];
+            for (slot, (bias, values)) in biases.iter().enumerate().rev() {
+                let value = values[index % values.len()];
+                index /= values.len();
+                set_bias(&mut configuration, *bias, value)?;
+                row_biases[slot] = (*bias, value);
+            }
+            self.update(configuration)?;
+            table.rows.push(Row {
+                biases: row_biases,
+                measurement: self.measure()?,
+            });
+        }
+        Ok(table)
+    }
+
+    /// tune bisects the bias in first..=last to get an event rate close to target_event_rate (in events/s).
+    ///
+    /// The event rate must be monotonic with respect to the bias (either direction).
+    /// The device is left with the value whose rate is the closest to the target,
+    /// and the returned table lists the measurements in order.
+    pub fn tune(
+        &mut self,
+        bias: Bias,
+        first: u8,
+        last: u8,
+        target_event_rate: f64,
+    ) -> Result<Table, Error> {
+        if first > last {
+            return Err(Error::Range { first, last });
+        }
+        let mut table = Table::default();
+        let (mut low, mut high) = (first, last);
+        let low_rate = self.measure_bias(bias, low, &mut table)?;
+        let high_rate = if high == low {
+            low_rate
+        } else {
+            self.measure_bias(bias, high, &mut table)?
+        };
+        let increasing = high_rate >= low_rate;
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            let rate = self.measure_bias(bias, middle, &mut table)?;
+            if (rate < target_event_rate) == increasing {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let best = table
+            .rows
+            .iter()
+            .min_by(|a, b| {
+                (a.measurement.event_rate() - target_event_rate)
+                    .abs()
+                    .total_cmp(&(b.measurement.event_rate() - target_event_rate).abs())
+            })
+            .map(|row| row.biases[0].1)
+            .expect("the table has at least one row");
+        let mut configuration = self.configuration.clone();
+        set_bias(&mut configuration, bias, best)?;
+        self.update(configuration)?;
+        Ok(table)
+    }
+
+    fn measure_bias(&mut self, bias: Bias, value: u8, table: &mut Table) -> Result<f64, Error> {
+        let mut configuration = self.configuration.clone();
+        set_bias(&mut configuration, bias, value)?;
+        self.update(configuration)?;
+        let measurement = self.measure()?;
+        table.rows.push(Row {
+            biases: vec![(bias, value)],
+            measurement,
+        });
+        Ok(measurement.event_rate())
+    }
+}
@@ -132,7 +132,7 @@ struct Buffer {
 }
 
 pub struct EventLoop {
-    context: rusb::Context,
+    context: rusb::Result<rusb::Context>,
     running: std::sync::Arc<std::sync::atomic::AtomicBool>,
     thread: Option<std::thread::JoinHandle<()>>,
 }
@@ -149,25 +149,30 @@ impl EventLoop {
         IntoError: From<Error> + Clone + Send + 'static,
         IntoWarning: From<Overflow> + Clone + Send + 'static,
     {
-        let context = rusb::Context::new()?;
+        // libusb initialization fails on hosts without a USB bus, mock devices do not need it
+        let context = rusb::Context::new();
         let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
         let thread_running = running.clone();
-        let thread_context = context.clone();
-        Ok(Self {
-            context,
-            thread: Some(std::thread::spawn(move || {
+        let thread = context.as_ref().ok().map(|context| {
+            let thread_context = context.clone();
+            std::thread::spawn(move || {
                 while thread_running.load(std::sync::atomic::Ordering::Acquire) {
                     if let Err(handle_events_error) = thread_context.handle_events(Some(timeout)) {
                         flag.store_error_if_not_set(Error::from(handle_events_error));
                     }
                 }
-            })),
+            })
+        });
+        Ok(Self {
+            context,
+            thread,
             running,
         })
     }
 
-    pub fn context(&self) -> &rusb::Context {
-        &self.context
+    /// context returns the libusb initialization error if the event loop has no context.
+    pub fn context(&self) -> Result<&rusb::Context, Error> {
+        self.context.as_ref().map_err(|error| Error::from(*error))
     }
 }
 
@@ -218,6 +223,53 @@ struct RingContext {
     buffers: Vec<Buffer>,
     freewheel_buffers: Vec<Buffer>,
     clutch: Clutch,
+    leases: Leases,
+}
+
+/// Leases keeps track of the buffers lent to the user (BufferView).
+///
+/// Buffers may be released out of order. The writer must not overwrite any buffer
+/// in the range [first_leased, read], hence first_leased only moves forward when
+/// the oldest leased buffer is released.
+struct Leases {
+    leased: Vec<bool>,
+    first_leased: usize,
+    active: usize,
+    maximum: usize,
+}
+
+impl Leases {
+    fn new(ring_length: usize) -> Self {
+        Self {
+            leased: vec![false; ring_length],
+            first_leased: ring_length - 1,
+            active: 0,
+            maximum: 1,
+        }
+    }
+
+    fn lease(&mut self, read: usize) {
+        self.leased[read] = true;
+        self.active += 1;
+        self.advance(read);
+    }
+
+    fn release(&mut self, index: usize, read: usize) {
+        self.leased[index] = false;
+        self.active -= 1;
+        self.advance(read);
+    }
+
+    fn advance(&mut self, read: usize) {
+        while self.first_leased != read && !self.leased[self.first_leased] {
+            self.first_leased = (self.first_leased + 1) % self.leased.len();
+        }
+    }
+
+    /// With a budget of one, the only lease can never be released while the caller waits.
+    fn is_deadlocked(&self) -> bool {
+        self.maximum == 1 && self.active == 1
+    }
 }
 
 struct SharedRingContext {
@@ -225,6 +277,60 @@ struct SharedRingContext {
     on_overflow: Box<dyn Fn(Overflow) + Send + Sync + 'static>,
     shared: std::sync::Mutex<RingContext>,
     shared_condvar: std::sync::Condvar,
+    #[cfg(feature = "async")]
+    waker: AsyncWaker,
+}
+
+impl SharedRingContext {
+    fn release(&self, index: usize) {
+        {
+            let mut shared = self
+                .shared
+                .lock()
+                .expect("ring context's lock is not poisoned");
+            let read = shared.read;
+            shared.leases.release(index, read);
+        }
+        self.shared_condvar.notify_all();
+    }
+
+    fn notify(&self) {
+        self.shared_condvar.notify_one();
+        #[cfg(feature = "async")]
+        self.waker.wake();
+    }
+}
+
+/// AsyncWaker stores the waker of the task polling a ring's stream.
+#[cfg(feature = "async")]
+struct AsyncWaker(std::sync::Mutex<Option<std::task::Waker>>);
+
+#[cfg(feature = "async")]
+impl AsyncWaker {
+    fn new() -> Self {
+        Self(std::sync::Mutex::new(None))
+    }
+
+    fn register(&self, waker: &std::task::Waker) {
+        let mut stored_waker = self.0.lock().expect("waker's lock is not poisoned");
+        if !stored_waker
+            .as_ref()
+            .is_some_and(|stored_waker| stored_waker.will_wake(waker))
+        {
+            *stored_waker = Some(waker.clone());
+        }
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self
+            .0
+            .lock()
+            .expect("waker's lock is not poisoned")
+            .as_ref()
+        {
+            waker.wake_by_ref();
+        }
+    }
 }
 
 struct LibusbTransfer(std::ptr::NonNull<libusb1_sys::libusb_transfer>);
@@ -244,7 +350,6 @@ impl LibusbTransfer {
 pub struct Ring {
     transfers: Vec<LibusbTransfer>,
     handle: std::sync::Arc<rusb::DeviceHandle<rusb::Context>>,
-    active_buffer_view: std::sync::Arc<std::sync::atomic::AtomicBool>,
     #[allow(dead_code)]
     event_loop: std::sync::Arc<EventLoop>,
     context: std::sync::Arc<SharedRingContext>,
@@ -321,9 +426,9 @@ extern "system" fn usb_transfer_callback(transfer_pointer: *mut libusb1_sys::lib
                                 matches!(context.clutch, TransferClutch::DisengagedFirst);
                             shared.buffers[active_buffer].length = transfer.actual_length as usize;
                             shared.write_range.increment_start();
-                            context.ring.shared_condvar.notify_one();
+                            context.ring.notify();
                         }
-                        if shared.write_range.end == shared.read {
+                        if shared.write_range.end == shared.leases.first_leased {
                             if matches!(shared.clutch, Clutch::Disengaged) {
                                 shared.clutch = Clutch::Engaged;
                                 (context.ring.on_overflow)(Overflow(()));
@@ -361,7 +466,7 @@ extern "system" fn usb_transfer_callback(transfer_pointer: *mut libusb1_sys::lib
                             shared.buffers[active_buffer].instant = now;
                             shared.buffers[active_buffer].length = transfer.actual_length as usize;
                             shared.write_range.increment_start();
-                            context.ring.shared_condvar.notify_one();
+                            context.ring.notify();
                         }
                         // set clutch to report a packet drop
                         shared.clutch = Clutch::Engaged;
@@ -401,7 +506,7 @@ extern "system" fn usb_transfer_callback(transfer_pointer: *mut libusb1_sys::lib
                             shared.buffers[active_buffer].instant = now;
                             shared.buffers[active_buffer].length = transfer.actual_length as usize;
                             shared.write_range.increment_start();
-                            context.ring.shared_condvar.notify_one();
+                            context.ring.notify();
                         }
                         // set clutch to report a packet drop
                         shared.clutch = Clutch::Engaged;
@@ -479,7 +584,9 @@ impl Ring {
         OnOverflow: Fn(Overflow) + Send + Sync + 'static,
     {
         assert!(
-            handle.context() == event_loop.context(),
+            event_loop
+                .context()
+                .is_ok_and(|context| context == handle.context()),
             "handle and event_loop must have the same context"
         );
         if configuration.ring_length <= configuration.transfer_queue_length {
@@ -566,8 +673,11 @@ impl Ring {
                 buffers,
                 freewheel_buffers,
                 clutch: Clutch::Disengaged,
+                leases: Leases::new(configuration.ring_length),
             }),
             shared_condvar: std::sync::Condvar::new(),
+            #[cfg(feature = "async")]
+            waker: AsyncWaker::new(),
         });
         let mut transfers: Vec<LibusbTransfer> = Vec::new();
         transfers.reserve_exact(configuration.transfer_queue_length);
@@ -690,7 +800,6 @@ impl Ring {
         let result = Self {
             transfers,
             handle,
-            active_buffer_view: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
             event_loop,
             context,
         };
@@ -747,10 +856,35 @@ pub struct BufferView<'a> {
     pub read: usize,
     pub write_range: WriteRange,
     pub clutch: Clutch,
-    active: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    lease: Lease<'a>,
+}
+
+enum Lease<'a> {
+    Ring(&'a SharedRingContext),
+    VirtualRing(&'a SharedVirtualRingContext),
+}
+
+/// OwnedBuffer is a copy of a BufferView that does not borrow the ring.
+#[derive(Debug, Clone)]
+pub struct OwnedBuffer {
+    pub instant: std::time::Instant,
+    pub first_after_overflow: bool,
+    pub bytes: Vec<u8>,
+    pub backlog: usize,
+    pub clutch: Clutch,
 }
 
 impl BufferView<'_> {
+    pub fn to_owned_buffer(&self) -> OwnedBuffer {
+        OwnedBuffer {
+            instant: self.instant,
+            first_after_overflow: self.first_after_overflow,
+            bytes: self.slice.to_vec(),
+            backlog: self.backlog(),
+            clutch: self.clutch,
+        }
+    }
+
     pub fn backlog(&self) -> usize {
         let result = (self.write_range.start + self.write_range.ring_length - 1 - self.read)
             % self.write_range.ring_length;
@@ -768,8 +902,10 @@ impl BufferView<'_> {
 
 impl Drop for BufferView<'_> {
     fn drop(&mut self) {
-        self.active
-            .store(false, std::sync::atomic::Ordering::Release);
+        match self.lease {
+            Lease::Ring(context) => context.release(self.read),
+            Lease::VirtualRing(context) => context.release(self.read),
+        }
     }
 }
 
@@ -792,13 +928,235 @@ impl Ring {
         shared.clutch
     }
 
-    pub fn next_with_timeout(&self, duration: &std::time::Duration) -> Option<BufferView> {
-        if self
-            .active_buffer_view
-            .swap(true, std::sync::atomic::Ordering::AcqRel)
+    /// The waker is woken whenever a new buffer is available.
+    #[cfg(feature = "async")]
+    pub fn register_waker(&self, waker: &std::task::Waker) {
+        self.context.waker.register(waker);
+    }
+
+    /// set_maximum_leases controls how many buffers may be leased (BufferView) at once.
+    ///
+    /// With the default budget (1), calling next_with_timeout while a BufferView is alive panics.
+    /// With a larger budget, buffers may be dropped in any order (for instance by a thread pool),
+    /// and next_with_timeout waits for a release if the budget is exhausted.
+    /// The ring cannot overwrite a buffer until it and all the buffers leased before it are released.
+    pub fn set_maximum_leases(&self, maximum_leases: usize) {
+        assert!(
+            maximum_leases > 0,
+            "maximum_leases must be larger than zero"
+        );
+        self.context
+            .shared
+            .lock()
+            .expect("ring context's lock is not poisoned")
+            .leases
+            .maximum = maximum_leases;
+        self.context.shared_condvar.notify_all();
+    }
+
+    pub fn next_with_timeout(&self, duration: &std::time::Duration) -> Option<BufferView<'_>> {
+        let (instant, first_after_overflow, slice, read, write_range, clutch) = {
+            let start = std::time::Instant::now();
+            let mut shared = self
+                .context
+                .shared
+                .lock()
+                .expect("ring context's lock is not poisoned");
+            if shared.leases.is_deadlocked() {
+                drop(shared);
+                panic!("the buffer returned by a previous call of next_with_timeout must be dropped before calling next_with_timeout again");
+            }
+            loop {
+                if shared.leases.active < shared.leases.maximum {
+                    let next_read = (shared.read + 1) % shared.buffers.len();
+                    if (shared.write_range.end + shared.buffers.len() - 1 - next_read)
+                        % shared.buffers.len()
+                        >= shared.transfer_statuses.len()
+                    {
+                        shared.read = next_read;
+                        if shared.buffers[next_read].length > 0 {
+                            break;
+                        }
+                        // skipped buffers are not leased, the writer may reuse them
+                        shared.leases.advance(next_read);
+                        continue;
+                    }
+                }
+                let ellapsed = std::time::Instant::now() - start;
+                if ellapsed >= *duration {
+                    return None;
+                }
+                shared = self
+                    .context
+                    .shared_condvar
+                    .wait_timeout(shared, *duration - ellapsed)
+                    .expect("shared_condvar used with two different mutexes")
+                    .0;
+            }
+            let read = shared.read;
+            shared.leases.lease(read);
+            (
+                shared.buffers[read].instant,
+                shared.buffers[read].first_after_overflow,
+                // unsafe: data validity guaranteed by read / write_range / leases in shared
+                unsafe {
+                    std::slice::from_raw_parts(
+                        shared.buffers[read].data.as_ptr(),
+                        shared.buffers[read].length,
+                    )
+                },
+                read,
+                shared.write_range.clone(),
+                shared.clutch,
+            )
+        };
+        Some(BufferView {
+            instant,
+            first_after_overflow,
+            slice,
+            read,
+            write_range,
+            clutch,
+            lease: Lease::Ring(&self.context),
+        })
+    }
+}
+
+struct VirtualBuffer {
+    instant: std::time::Instant,
+    first_after_overflow: bool,
+    data: Vec<u8>,
+    length: usize,
+}
+
+struct VirtualRingContext {
+    read: usize,
+    write: usize,
+    buffers: Vec<VirtualBuffer>,
+    clutch: Clutch,
+    first_after_overflow: bool,
+    leases: Leases,
+}
+
+struct SharedVirtualRingContext {
+    on_overflow: Box<dyn Fn(Overflow) + Send + Sync + 'static>,
+    shared: std::sync::Mutex<VirtualRingContext>,
+    shared_condvar: std::sync::Condvar,
+    #[cfg(feature = "async")]
+    waker: AsyncWaker,
+}
+
+impl SharedVirtualRingContext {
+    fn release(&self, index: usize) {
         {
-            panic!("the buffer returned by a previous call of next_with_timeout must be dropped before calling next_with_timeout again");
+            let mut shared = self
+                .shared
+                .lock()
+                .expect("ring context's lock is not poisoned");
+            let read = shared.read;
+            shared.leases.release(index, read);
         }
+        self.shared_condvar.notify_all();
+    }
+}
+
+/// VirtualRing mimics Ring without a USB device.
+///
+/// Buffers are written by software (for instance a mock device's thread) with VirtualRing::write
+/// and read with the same next_with_timeout / backlog / clutch API as Ring.
+pub struct VirtualRing {
+    context: std::sync::Arc<SharedVirtualRingContext>,
+}
+
+/// VirtualRingWriter fills the buffers of a VirtualRing.
+#[derive(Clone)]
+pub struct VirtualRingWriter {
+    context: std::sync::Arc<SharedVirtualRingContext>,
+}
+
+impl VirtualRing {
+    pub fn new<OnOverflow>(
+        configuration: &Configuration,
+        on_overflow: OnOverflow,
+    ) -> Result<(Self, VirtualRingWriter), Error>
+    where
+        OnOverflow: Fn(Overflow) + Send + Sync + 'static,
+    {
+        if configuration.ring_length <= configuration.transfer_queue_length {
+            return Err(Error::ConfigurationSizes);
+        }
+        let mut buffers = Vec::new();
+        buffers.reserve_exact(configuration.ring_length);
+        for _ in 0..configuration.ring_length {
+            buffers.push(VirtualBuffer {
+                instant: std::time::Instant::now(),
+                first_after_overflow: false,
+                data: vec![0u8; configuration.buffer_length],
+                length: 0,
+            });
+        }
+        let context = std::sync::Arc::new(SharedVirtualRingContext {
+            on_overflow: Box::new(on_overflow),
+            shared: std::sync::Mutex::new(VirtualRingContext {
+                read: buffers.len() - 1,
+                write: 0,
+                buffers,
+                clutch: Clutch::Disengaged,
+                first_after_overflow: false,
+                leases: Leases::new(configuration.ring_length),
+            }),
+            shared_condvar: std::sync::Condvar::new(),
+            #[cfg(feature = "async")]
+            waker: AsyncWaker::new(),
+        });
+        Ok((
+            Self {
+                context: context.clone(),
+            },
+            VirtualRingWriter { context },
+        ))
+    }
+
+    pub fn backlog(&self) -> usize {
+        let shared = self
+            .context
+            .shared
+            .lock()
+            .expect("ring context's lock is not poisoned");
+        (shared.write + shared.buffers.len() - 1 - shared.read) % shared.buffers.len()
+    }
+
+    pub fn clutch(&self) -> Clutch {
+        let shared = self
+            .context
+            .shared
+            .lock()
+            .expect("ring context's lock is not poisoned");
+        shared.clutch
+    }
+
+    /// The waker is woken whenever a new buffer is available.
+    #[cfg(feature = "async")]
+    pub fn register_waker(&self, waker: &std::task::Waker) {
+        self.context.waker.register(waker);
+    }
+
+    /// set_maximum_leases behaves like Ring::set_maximum_leases.
+    pub fn set_maximum_leases(&self, maximum_leases: usize) {
+        assert!(
+            maximum_leases > 0,
+            "maximum_leases must be larger than zero"
+        );
+        self.context
+            .shared
+            .lock()
+            .expect("ring context's lock is not poisoned")
+            .leases
+            .maximum = maximum_leases;
+        self.context.shared_condvar.notify_all();
+    }
+
+    pub fn next_with_timeout(&self, duration: &std::time::Duration) -> Option<BufferView<'_>> {
         let (instant, first_after_overflow, slice, read, write_range, clutch) = {
             let start = std::time::Instant::now();
             let mut shared = self
@@ -806,43 +1164,52 @@ impl Ring {
                 .shared
                 .lock()
                 .expect("ring context's lock is not poisoned");
+            if shared.leases.is_deadlocked() {
+                drop(shared);
+                panic!("the buffer returned by a previous call of next_with_timeout must be dropped before calling next_with_timeout again");
+            }
             loop {
-                shared.read = (shared.read + 1) % shared.buffers.len();
-                while (shared.write_range.end + shared.buffers.len() - 1 - shared.read)
-                    % shared.buffers.len()
-                    < shared.transfer_statuses.len()
-                {
-                    let ellapsed = std::time::Instant::now() - start;
-                    if ellapsed >= *duration {
-                        self.active_buffer_view
-                            .store(false, std::sync::atomic::Ordering::Release);
-                        shared.read =
-                            (shared.read + shared.buffers.len() - 1) % shared.buffers.len();
-                        return None;
+                if shared.leases.active < shared.leases.maximum {
+                    let next_read = (shared.read + 1) % shared.buffers.len();
+                    if next_read != shared.write {
+                        shared.read = next_read;
+                        if shared.buffers[next_read].length > 0 {
+                            break;
+                        }
+                        // skipped buffers are not leased, the writer may reuse them
+                        shared.leases.advance(next_read);
+                        continue;
                     }
-                    shared = self
-                        .context
-                        .shared_condvar
-                        .wait_timeout(shared, *duration - ellapsed)
-                        .expect("shared_condvar used with two different mutexes")
-                        .0;
                 }
-                if shared.buffers[shared.read].length > 0 {
-                    break;
+                let ellapsed = std::time::Instant::now() - start;
+                if ellapsed >= *duration {
+                    return None;
                 }
+                shared = self
+                    .context
+                    .shared_condvar
+                    .wait_timeout(shared, *duration - ellapsed)
+                    .expect("shared_condvar used with two different mutexes")
+                    .0;
             }
+            let read = shared.read;
+            shared.leases.lease(read);
             (
-                shared.buffers[shared.read].instant,
-                shared.buffers[shared.read].first_after_overflow,
-                // unsafe: data validity guaranteed by read / write_range in shared
+                shared.buffers[read].instant,
+                shared.buffers[read].first_after_overflow,
+                // unsafe: the writer never modifies the buffers in the range [first_leased, read]
                 unsafe {
                     std::slice::from_raw_parts(
-                        shared.buffers[shared.read].data.as_ptr(),
-                        shared.buffers[shared.read].length,
+                        shared.buffers[read].data.as_ptr(),
+                        shared.buffers[read].length,
                     )
                 },
-                shared.read,
-                shared.write_range.clone(),
+                read,
+                WriteRange {
+                    start: shared.write,
+                    end: shared.write,
+                    ring_length: shared.buffers.len(),
+                },
                 shared.clutch,
             )
         };
@@ -853,11 +1220,58 @@ impl Ring {
             read,
             write_range,
             clutch,
-            active: self.active_buffer_view.clone(),
+            lease: Lease::VirtualRing(&self.context),
         })
     }
 }
 
+impl VirtualRingWriter {
+    /// write copies bytes into the next available buffer.
+    ///
+    /// Bytes beyond the ring's buffer_length are ignored. If the reader is too slow, the data is dropped,
+    /// the clutch is engaged, and on_overflow is called. write returns the number of bytes that were stored.
+    pub fn write(&self, bytes: &[u8]) -> usize {
+        let mut shared = self
+            .context
+            .shared
+            .lock()
+            .expect("ring context's lock is not poisoned");
+        if shared.write == shared.leases.first_leased {
+            if matches!(shared.clutch, Clutch::Disengaged) {
+                shared.clutch = Clutch::Engaged;
+                shared.first_after_overflow = true;
+                (self.context.on_overflow)(Overflow(()));
+            }
+            return 0;
+        }
+        shared.clutch = Clutch::Disengaged;
+        let write = shared.write;
+        let first_after_overflow = shared.first_after_overflow;
+        shared.first_after_overflow = false;
+        let buffer = &mut shared.buffers[write];
+        let length = bytes.len().min(buffer.data.len());
+        buffer.data[0..length].copy_from_slice(&bytes[0..length]);
+        buffer.length = length;
+        buffer.instant = std::time::Instant::now();
+        buffer.first_after_overflow = first_after_overflow;
+        shared.write = (write + 1) % shared.buffers.len();
+        self.context.shared_condvar.notify_one();
+        #[cfg(feature = "async")]
+        self.context.waker.wake();
+        length
+    }
+
+    pub fn buffer_length(&self) -> usize {
+        self.context
+            .shared
+            .lock()
+            .expect("ring context's lock is not poisoned")
+            .buffers[0]
+            .data
+            .len()
+    }
+}
+
 impl Drop for Ring {
     fn drop(&mut self) {
         let mut dealloc_buffers = true;
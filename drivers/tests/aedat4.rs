@@ -0,0 +1,110 @@
+fn read_u32(bytes: &[u8], position: usize) -> u32 {
+    u32::from_le_bytes(bytes[position..position + 4].try_into().unwrap())
+}
+
+#[test]
+fn write() {
+    let path = std::env::temp_dir().join("neuromorphic_drivers_aedat4_write.aedat4");
+    let mut dvs_events = Vec::new();
+    for index in 0..10000u64 {
+        dvs_events.push(neuromorphic_types::DvsEvent {
+            t: index * 3,
+            x: (index % 1280) as u16,
+            y: (index % 720) as u16,
+            polarity: if index % 3 == 1 {
+                neuromorphic_types::DvsPolarity::On
+            } else {
+                neuromorphic_types::DvsPolarity::Off
+            },
+        });
+    }
+    let trigger_events = [
+        neuromorphic_types::TriggerEvent {
+            t: 100,
+            id: 0,
+            polarity: neuromorphic_types::TriggerPolarity::Rising,
+        },
+        neuromorphic_types::TriggerEvent {
+            t: 200,
+            id: 0,
+            polarity: neuromorphic_types::TriggerPolarity::Falling,
+        },
+    ];
+    let mut bytes = Vec::new();
+    let mut encoder = neuromorphic_drivers::adapters::evt3::Encoder::from_dimensions(1280, 720);
+    encoder.encode(&dvs_events, &trigger_events, &mut bytes);
+    {
+        let mut writer =
+            neuromorphic_drivers::aedat4::Writer::create(&path, 1280, 720, "Mock").unwrap();
+        let mut adapter: neuromorphic_drivers::Adapter =
+            neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720).into();
+        for slice in bytes.chunks(8192) {
+            writer.write_slice(&mut adapter, slice).unwrap();
+        }
+    }
+
+    let file = std::fs::read(&path).unwrap();
+    assert_eq!(&file[0..14], neuromorphic_drivers::aedat4::SIGNATURE);
+    let header_length = read_u32(&file, 14) as usize;
+    let header = &file[14..18 + header_length];
+    assert_eq!(&header[8..12], b"IOHE");
+    let info_node_position = 40 + read_u32(header, 40) as usize;
+    let info_node_length = read_u32(header, info_node_position) as usize;
+    let info_node = std::str::from_utf8(
+        &header[info_node_position + 4..info_node_position + 4 + info_node_length],
+    )
+    .unwrap();
+    assert!(info_node.contains("<attr key=\"typeIdentifier\" type=\"string\">EVTS</attr>"));
+    assert!(info_node.contains("<attr key=\"sizeX\" type=\"int\">1280</attr>"));
+
+    let mut decoded_dvs_events = Vec::new();
+    let mut decoded_trigger_events = Vec::new();
+    let mut position = 18 + header_length;
+    while position < file.len() {
+        let stream_id = read_u32(&file, position) as i32;
+        let length = read_u32(&file, position + 4) as usize;
+        let packet = &file[position + 8..position + 8 + length];
+        assert_eq!(read_u32(packet, 0) as usize, length - 4);
+        let root = 4 + read_u32(packet, 4) as usize;
+        let elements = root + 4 + read_u32(packet, root + 4) as usize;
+        let elements_length = read_u32(packet, elements) as usize;
+        match stream_id {
+            neuromorphic_drivers::aedat4::DVS_STREAM_ID => {
+                assert_eq!(&packet[8..12], b"EVTS");
+                for index in 0..elements_length {
+                    let event = &packet[elements + 4 + index * 16..elements + 4 + (index + 1) * 16];
+                    decoded_dvs_events.push((
+                        i64::from_le_bytes(event[0..8].try_into().unwrap()) as u64,
+                        i16::from_le_bytes(event[8..10].try_into().unwrap()) as u16,
+                        719 - i16::from_le_bytes(event[10..12].try_into().unwrap()) as u16,
+                        event[12],
+                    ));
+                }
+            }
+            neuromorphic_drivers::aedat4::TRIGGER_STREAM_ID => {
+                assert_eq!(&packet[8..12], b"TRIG");
+                for index in 0..elements_length {
+                    let offset_position = elements + 4 + index * 4;
+                    let table = offset_position + read_u32(packet, offset_position) as usize;
+                    decoded_trigger_events.push((
+                        i64::from_le_bytes(packet[table + 8..table + 16].try_into().unwrap())
+                            as u64,
+                        packet[table + 16],
+                    ));
+                }
+            }
+            _ => panic!("unexpected stream id {stream_id}"),
+        }
+        position += 8 + length;
+    }
+    assert_eq!(position, file.len());
+    assert_eq!(
+        decoded_dvs_events,
+        dvs_events
+            .iter()
+            .map(|event| (event.t, event.x, event.y, event.polarity as u8))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(decoded_trigger_events, vec![(100, 1), (200, 2)]);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,13 @@
+/// event creates a DVS event, on selects the polarity.
+pub fn event(t: u64, x: u16, y: u16, on: bool) -> neuromorphic_types::DvsEvent<u64, u16, u16> {
+    neuromorphic_types::DvsEvent {
+        t,
+        x,
+        y,
+        polarity: if on {
+            neuromorphic_types::DvsPolarity::On
+        } else {
+            neuromorphic_types::DvsPolarity::Off
+        },
+    }
+}
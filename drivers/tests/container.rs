@@ -0,0 +1,106 @@
+fn events() -> Vec<neuromorphic_types::DvsEvent<u64, u16, u16>> {
+    let mut state = 0x2545f4914f6cdd1du64;
+    let mut t = 0u64;
+    let mut events = Vec::new();
+    for _ in 0..500000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        t += match state % 1000 {
+            1 => 1 << 20,
+            2..=500 => 0,
+            _ => state % 10,
+        };
+        events.push(neuromorphic_types::DvsEvent {
+            t,
+            x: ((state >> 16) % 1280) as u16,
+            y: ((state >> 32) % 720) as u16,
+            polarity: if (state >> 48) % 2 == 1 {
+                neuromorphic_types::DvsPolarity::On
+            } else {
+                neuromorphic_types::DvsPolarity::Off
+            },
+        });
+    }
+    events
+}
+
+fn key(event: &neuromorphic_types::DvsEvent<u64, u16, u16>) -> (u64, u16, u16, u8) {
+    (event.t, event.x, event.y, event.polarity as u8)
+}
+
+fn round_trip(name: &str, compression: neuromorphic_drivers::container::Compression) {
+    let path = std::env::temp_dir().join(name);
+    let events = events();
+    let mut writer =
+        neuromorphic_drivers::container::Writer::create(&path, 1280, 720, 10000, compression)
+            .unwrap();
+    for chunk in events.chunks(4096) {
+        writer.write_dvs_events(chunk).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let mut reader = neuromorphic_drivers::container::Reader::open(&path).unwrap();
+    assert_eq!((reader.width(), reader.height()), (1280, 720));
+    assert!(reader.index().len() > 1);
+    let last_t = events[events.len() - 1].t;
+    for (t0, t1) in [
+        (0, last_t + 1),
+        (0, 1),
+        (last_t / 3, last_t / 2),
+        (last_t / 2, last_t / 2),
+        (last_t, last_t + 1000),
+    ] {
+        assert_eq!(
+            reader
+                .read_range(t0, t1)
+                .unwrap()
+                .iter()
+                .map(key)
+                .collect::<Vec<_>>(),
+            events
+                .iter()
+                .filter(|event| event.t >= t0 && event.t < t1)
+                .map(key)
+                .collect::<Vec<_>>()
+        );
+    }
+    let index = reader.index().to_vec();
+
+    // with a corrupted index count (count * entry length overflows), the reader scans the chunks
+    let bytes = std::fs::read(&path).unwrap();
+    let index_length = 12 + 9 + index.len() * 28;
+    let mut corrupted_bytes = bytes.clone();
+    let count_offset = bytes.len() - index_length + 1;
+    corrupted_bytes[count_offset..count_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+    std::fs::write(&path, &corrupted_bytes).unwrap();
+    let reader = neuromorphic_drivers::container::Reader::open(&path).unwrap();
+    assert_eq!(reader.index(), &index[..]);
+
+    // without the index (truncated writer output), the reader scans the chunks
+    std::fs::write(&path, &bytes[0..bytes.len() - index_length]).unwrap();
+    let mut reader = neuromorphic_drivers::container::Reader::open(&path).unwrap();
+    assert_eq!(reader.index(), &index[..]);
+    assert_eq!(
+        reader.read_range(0, last_t + 1).unwrap().len(),
+        events.len()
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn uncompressed() {
+    round_trip(
+        "neuromorphic_drivers_container_uncompressed.events",
+        neuromorphic_drivers::container::Compression::None,
+    );
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd() {
+    round_trip(
+        "neuromorphic_drivers_container_zstd.events",
+        neuromorphic_drivers::container::Compression::Zstd(3),
+    );
+}
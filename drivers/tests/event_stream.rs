@@ -0,0 +1,190 @@
+fn next_state(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn dvs() {
+    let path = std::env::temp_dir().join("neuromorphic_drivers_event_stream_dvs.es");
+    let mut state = 0x2545f4914f6cdd1du64;
+    let mut t = 0u64;
+    let mut events = Vec::new();
+    for _ in 0..100000 {
+        let value = next_state(&mut state);
+        // occasional long gaps exercise the overflow bytes
+        t += if value % 100 == 1 {
+            value % 100000
+        } else {
+            value % 200
+        };
+        events.push(neuromorphic_types::DvsEvent {
+            t,
+            x: ((value >> 16) % 1280) as u16,
+            y: ((value >> 32) % 720) as u16,
+            polarity: if (value >> 48) % 2 == 1 {
+                neuromorphic_types::DvsPolarity::On
+            } else {
+                neuromorphic_types::DvsPolarity::Off
+            },
+        });
+    }
+    {
+        let mut writer = neuromorphic_drivers::event_stream::Writer::create(
+            &path,
+            neuromorphic_drivers::event_stream::Type::Dvs,
+            1280,
+            720,
+        )
+        .unwrap();
+        for event in events.iter() {
+            writer.write_dvs_event(*event).unwrap();
+        }
+        assert!(matches!(
+            writer.write_dvs_event(neuromorphic_types::DvsEvent {
+                t: 0,
+                x: 0,
+                y: 0,
+                polarity: neuromorphic_types::DvsPolarity::On,
+            }),
+            Err(neuromorphic_drivers::event_stream::Error::Timestamp { .. })
+        ));
+    }
+    let mut reader = neuromorphic_drivers::event_stream::Reader::open(&path).unwrap();
+    let header = reader.header();
+    assert_eq!(
+        header.event_type,
+        neuromorphic_drivers::event_stream::Type::Dvs
+    );
+    assert_eq!((header.width, header.height), (1280, 720));
+    let mut decoded_events = Vec::new();
+    for chunk in &mut reader {
+        match chunk.unwrap() {
+            neuromorphic_drivers::event_stream::Events::Dvs(chunk_events) => {
+                decoded_events.extend(chunk_events)
+            }
+            _ => panic!("unexpected event type"),
+        }
+    }
+    assert_eq!(reader.current_t(), t);
+    let key = |event: &neuromorphic_types::DvsEvent<u64, u16, u16>| {
+        (event.t, event.x, event.y, event.polarity as u8)
+    };
+    assert_eq!(
+        decoded_events.iter().map(key).collect::<Vec<_>>(),
+        events.iter().map(key).collect::<Vec<_>>()
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn atis() {
+    let path = std::env::temp_dir().join("neuromorphic_drivers_event_stream_atis.es");
+    let mut state = 0x9e3779b97f4a7c15u64;
+    let mut t = 0u64;
+    let mut events = Vec::new();
+    for _ in 0..100000 {
+        let value = next_state(&mut state);
+        t += if value % 100 == 1 {
+            value % 100000
+        } else {
+            value % 200
+        };
+        events.push(neuromorphic_types::AtisEvent {
+            t,
+            x: ((value >> 16) % 320) as u16,
+            y: ((value >> 32) % 240) as u16,
+            polarity: match (value >> 48) % 4 {
+                0 => neuromorphic_types::AtisPolarity::Off,
+                1 => neuromorphic_types::AtisPolarity::On,
+                2 => neuromorphic_types::AtisPolarity::ExposureStart,
+                _ => neuromorphic_types::AtisPolarity::ExposureEnd,
+            },
+        });
+    }
+    {
+        let mut writer = neuromorphic_drivers::event_stream::Writer::create(
+            &path,
+            neuromorphic_drivers::event_stream::Type::Atis,
+            320,
+            240,
+        )
+        .unwrap();
+        for event in events.iter() {
+            writer.write_atis_event(*event).unwrap();
+        }
+    }
+    let mut decoded_events = Vec::new();
+    for chunk in neuromorphic_drivers::event_stream::Reader::open(&path).unwrap() {
+        match chunk.unwrap() {
+            neuromorphic_drivers::event_stream::Events::Atis(chunk_events) => {
+                decoded_events.extend(chunk_events)
+            }
+            _ => panic!("unexpected event type"),
+        }
+    }
+    let key = |event: &neuromorphic_types::AtisEvent<u64, u16, u16>| {
+        (event.t, event.x, event.y, event.polarity as u8)
+    };
+    assert_eq!(
+        decoded_events.iter().map(key).collect::<Vec<_>>(),
+        events.iter().map(key).collect::<Vec<_>>()
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn generic() {
+    let path = std::env::temp_dir().join("neuromorphic_drivers_event_stream_generic.es");
+    let events = vec![
+        neuromorphic_drivers::event_stream::GenericEvent::from_trigger_event(
+            neuromorphic_types::TriggerEvent {
+                t: 10,
+                id: 3,
+                polarity: neuromorphic_types::TriggerPolarity::Rising,
+            },
+        ),
+        neuromorphic_drivers::event_stream::GenericEvent {
+            t: 10,
+            bytes: Vec::new(),
+        },
+        neuromorphic_drivers::event_stream::GenericEvent {
+            t: 100000,
+            bytes: (0..1000).map(|index| (index % 256) as u8).collect(),
+        },
+        neuromorphic_drivers::event_stream::GenericEvent::from_trigger_event(
+            neuromorphic_types::TriggerEvent {
+                t: 100254,
+                id: 0,
+                polarity: neuromorphic_types::TriggerPolarity::Falling,
+            },
+        ),
+    ];
+    {
+        let mut writer = neuromorphic_drivers::event_stream::Writer::create(
+            &path,
+            neuromorphic_drivers::event_stream::Type::Generic,
+            0,
+            0,
+        )
+        .unwrap();
+        for event in events.iter() {
+            writer.write_generic_event(event).unwrap();
+        }
+    }
+    let mut decoded_events = Vec::new();
+    for chunk in neuromorphic_drivers::event_stream::Reader::open(&path).unwrap() {
+        match chunk.unwrap() {
+            neuromorphic_drivers::event_stream::Events::Generic(chunk_events) => {
+                decoded_events.extend(chunk_events)
+            }
+            _ => panic!("unexpected event type"),
+        }
+    }
+    assert_eq!(decoded_events, events);
+    let trigger_event = decoded_events[3].to_trigger_event().unwrap();
+    assert_eq!((trigger_event.t, trigger_event.id), (100254, 0));
+    assert!(decoded_events[2].to_trigger_event().is_none());
+    std::fs::remove_file(path).unwrap();
+}
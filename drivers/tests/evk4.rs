@@ -32,3 +32,303 @@ fn read() -> Result<(), neuromorphic_drivers::Error> {
     }
     Ok(())
 }
+
+#[test]
+fn configuration_bincode() {
+    let mut configuration = neuromorphic_drivers::prophesee_evk4::DEFAULT_CONFIGURATION;
+    configuration.anti_flicker = Some(neuromorphic_drivers::prophesee_evk4::AntiFlicker {
+        low_frequency: 90,
+        high_frequency: 110,
+        duty_cycle: 50,
+        mode: neuromorphic_drivers::prophesee_evk4::AntiFlickerMode::BandStop,
+    });
+    configuration.burst_filter = Some(neuromorphic_drivers::prophesee_evk4::BurstFilter {
+        mode: neuromorphic_drivers::prophesee_evk4::BurstFilterMode::StcCutTrail,
+        threshold_us: 10000,
+    });
+    configuration.event_rate_control =
+        Some(neuromorphic_drivers::prophesee_evk4::EventRateControl {
+            mode: neuromorphic_drivers::prophesee_evk4::EventRateControlMode::Vertical,
+            reference_period_us: 200,
+            target_event_rate_kev_s: 20000,
+        });
+    configuration.area_counters = Some(neuromorphic_drivers::prophesee_evk4::AreaCounters {
+        x: [0, 320, 640, 960, 1280],
+        y: [0, 180, 360, 540, 720],
+    });
+    configuration.roi = Some(neuromorphic_drivers::prophesee_evk4::Roi {
+        mode: neuromorphic_drivers::prophesee_evk4::RoiMode::Exclude,
+        windows: vec![
+            neuromorphic_drivers::prophesee_evk4::RoiWindow {
+                x: 100,
+                y: 100,
+                width: 200,
+                height: 100,
+            },
+            neuromorphic_drivers::prophesee_evk4::RoiWindow {
+                x: 400,
+                y: 100,
+                width: 50,
+                height: 100,
+            },
+        ],
+    });
+    configuration.masked_pixels = vec![neuromorphic_drivers::prophesee_evk4::Pixel { x: 5, y: 6 }];
+    configuration.digital_crop = Some(neuromorphic_drivers::prophesee_evk4::RoiWindow {
+        x: 0,
+        y: 0,
+        width: 640,
+        height: 360,
+    });
+    let data = neuromorphic_drivers::Configuration::PropheseeEvk4(configuration.clone())
+        .serialize_bincode()
+        .unwrap();
+    match neuromorphic_drivers::Configuration::deserialize_bincode(
+        neuromorphic_drivers::Type::PropheseeEvk4,
+        &data,
+    )
+    .unwrap()
+    {
+        neuromorphic_drivers::Configuration::PropheseeEvk4(deserialized_configuration) => {
+            assert_eq!(deserialized_configuration, configuration)
+        }
+        _ => panic!("unexpected configuration type"),
+    }
+}
+
+#[test]
+fn validate_digital_mask_capacity() {
+    use neuromorphic_drivers::prophesee_evk4::{
+        Error, Pixel, DEFAULT_CONFIGURATION, DIGITAL_MASK_CAPACITY,
+    };
+    let mut configuration = DEFAULT_CONFIGURATION;
+    configuration.masked_pixels = (0..DIGITAL_MASK_CAPACITY as u16)
+        .map(|x| Pixel { x, y: 0 })
+        .collect();
+    configuration.validate().unwrap();
+    configuration.masked_pixels.push(Pixel { x: 0, y: 1 });
+    assert!(matches!(
+        configuration.validate(),
+        Err(Error::DigitalMaskCapacity {
+            pixels: 65,
+            capacity: DIGITAL_MASK_CAPACITY
+        })
+    ));
+}
+
+#[test]
+fn validate_digital_mask_duplicates() {
+    use neuromorphic_drivers::prophesee_evk4::{
+        Pixel, DEFAULT_CONFIGURATION, DIGITAL_MASK_CAPACITY,
+    };
+    let mut configuration = DEFAULT_CONFIGURATION;
+    // pixel_mask codes are 1 + x + width * y, three per word
+    configuration.pixel_mask[0] = 1 + 5 + 1280 * 10;
+    configuration.masked_pixels = (0..DIGITAL_MASK_CAPACITY as u16)
+        .map(|x| Pixel { x, y: 10 })
+        .collect();
+    configuration.masked_pixels.push(Pixel { x: 0, y: 10 });
+    configuration.validate().unwrap();
+}
+
+#[test]
+fn validate_masked_pixel_and_digital_crop() {
+    use neuromorphic_drivers::prophesee_evk4::{Error, Pixel, RoiWindow, DEFAULT_CONFIGURATION};
+    let mut configuration = DEFAULT_CONFIGURATION;
+    configuration.masked_pixels = vec![Pixel { x: 1280, y: 0 }];
+    assert!(matches!(
+        configuration.validate(),
+        Err(Error::MaskedPixel { x: 1280, y: 0 })
+    ));
+    let mut configuration = DEFAULT_CONFIGURATION;
+    configuration.digital_crop = Some(RoiWindow {
+        x: 640,
+        y: 0,
+        width: 641,
+        height: 720,
+    });
+    assert!(matches!(
+        configuration.validate(),
+        Err(Error::DigitalCrop(_))
+    ));
+}
+
+fn roi_masks(
+    mode: neuromorphic_drivers::prophesee_evk4::RoiMode,
+    windows: &[(u16, u16, u16, u16)],
+) -> Result<neuromorphic_drivers::prophesee_evk4::Masks, neuromorphic_drivers::prophesee_evk4::Error>
+{
+    let mut configuration = neuromorphic_drivers::prophesee_evk4::DEFAULT_CONFIGURATION;
+    configuration.roi = Some(neuromorphic_drivers::prophesee_evk4::Roi {
+        mode,
+        windows: windows
+            .iter()
+            .map(
+                |(x, y, width, height)| neuromorphic_drivers::prophesee_evk4::RoiWindow {
+                    x: *x,
+                    y: *y,
+                    width: *width,
+                    height: *height,
+                },
+            )
+            .collect(),
+    });
+    configuration.validate()?;
+    neuromorphic_drivers::prophesee_evk4::Masks::from_configuration(&configuration)
+}
+
+fn masked_lines<const N: usize>(mask: &[u64; N], length: usize) -> Vec<bool> {
+    (0..length)
+        .map(|index| (mask[index / 64] >> (index % 64)) & 1 == 1)
+        .collect()
+}
+
+#[test]
+fn roi_single_window() {
+    use neuromorphic_drivers::prophesee_evk4::{RoiMode, RoiWindow};
+    let masks = roi_masks(RoiMode::Include, &[(320, 180, 640, 360)]).unwrap();
+    assert_eq!(
+        masks.window,
+        Some(RoiWindow {
+            x: 320,
+            y: 180,
+            width: 640,
+            height: 360
+        })
+    );
+    assert_eq!(masks.x_mask, [0; 20]);
+    assert_eq!(masks.y_mask, [0; 12]);
+    assert!(!masks.intersection_only);
+}
+
+#[test]
+fn roi_grid_union() {
+    use neuromorphic_drivers::prophesee_evk4::RoiMode;
+    // two columns of two rows, the union is the product of [0, 10) ∪ [20, 30) and [0, 5) ∪ [10, 15)
+    let masks = roi_masks(
+        RoiMode::Include,
+        &[
+            (0, 0, 10, 5),
+            (20, 0, 10, 5),
+            (0, 10, 10, 5),
+            (20, 10, 10, 5),
+        ],
+    )
+    .unwrap();
+    assert_eq!(masks.window, None);
+    assert!(!masks.intersection_only);
+    let columns = masked_lines(&masks.x_mask, 1280);
+    let rows = masked_lines(&masks.y_mask, 720);
+    for (x, masked) in columns.iter().enumerate() {
+        assert_eq!(*masked, !(x < 10 || (20..30).contains(&x)), "x = {x}");
+    }
+    for (y, masked) in rows.iter().enumerate() {
+        assert_eq!(*masked, !(y < 5 || (10..15).contains(&y)), "y = {y}");
+    }
+}
+
+#[test]
+fn roi_non_grid_union() {
+    use neuromorphic_drivers::prophesee_evk4::{Error, RoiMode};
+    // the product of the windows' ranges contains (20, 0), which is in neither window
+    assert!(matches!(
+        roi_masks(RoiMode::Include, &[(0, 0, 10, 5), (20, 10, 10, 5)]),
+        Err(Error::RoiWindows)
+    ));
+    assert!(matches!(
+        roi_masks(RoiMode::Include, &[]),
+        Err(Error::RoiEmpty)
+    ));
+}
+
+#[test]
+fn roi_exclude() {
+    use neuromorphic_drivers::prophesee_evk4::RoiMode;
+    let masks = roi_masks(RoiMode::Exclude, &[(100, 200, 30, 40)]).unwrap();
+    assert_eq!(masks.window, None);
+    assert!(masks.intersection_only);
+    let columns = masked_lines(&masks.x_mask, 1280);
+    let rows = masked_lines(&masks.y_mask, 720);
+    for (x, masked) in columns.iter().enumerate() {
+        assert_eq!(*masked, (100..130).contains(&x), "x = {x}");
+    }
+    for (y, masked) in rows.iter().enumerate() {
+        assert_eq!(*masked, (200..240).contains(&y), "y = {y}");
+    }
+}
+
+#[test]
+fn burst_filter_parameters() {
+    use neuromorphic_drivers::prophesee_evk4::{
+        BurstFilter, BurstFilterMode, BurstFilterParameters, DEFAULT_CONFIGURATION,
+    };
+    let parameters = |burst_filter: Option<BurstFilter>| {
+        let mut configuration = DEFAULT_CONFIGURATION;
+        configuration.burst_filter = burst_filter;
+        configuration.validate().unwrap();
+        BurstFilterParameters::from_configuration(&configuration)
+    };
+    assert_eq!(
+        parameters(None),
+        BurstFilterParameters {
+            stc_threshold: None,
+            disable_cut_trail: true,
+            trail_threshold: None,
+        }
+    );
+    assert_eq!(
+        parameters(Some(BurstFilter {
+            mode: BurstFilterMode::Trail,
+            threshold_us: 1000,
+        })),
+        BurstFilterParameters {
+            stc_threshold: None,
+            disable_cut_trail: true,
+            trail_threshold: Some(1000),
+        }
+    );
+    assert_eq!(
+        parameters(Some(BurstFilter {
+            mode: BurstFilterMode::StcCutTrail,
+            threshold_us: 2000,
+        })),
+        BurstFilterParameters {
+            stc_threshold: Some(2000),
+            disable_cut_trail: false,
+            trail_threshold: None,
+        }
+    );
+    assert_eq!(
+        parameters(Some(BurstFilter {
+            mode: BurstFilterMode::StcKeepTrail,
+            threshold_us: 3000,
+        })),
+        BurstFilterParameters {
+            stc_threshold: Some(3000),
+            disable_cut_trail: true,
+            trail_threshold: None,
+        }
+    );
+}
+
+#[test]
+fn burst_filter_threshold() {
+    use neuromorphic_drivers::prophesee_evk4::{
+        BurstFilter, BurstFilterMode, Error, DEFAULT_CONFIGURATION,
+    };
+    let mut configuration = DEFAULT_CONFIGURATION;
+    for (threshold_us, valid) in [(0, false), (1, true), (524287, true), (524288, false)] {
+        configuration.burst_filter = Some(BurstFilter {
+            mode: BurstFilterMode::Trail,
+            threshold_us,
+        });
+        match configuration.validate() {
+            Ok(()) => assert!(valid, "{threshold_us} µs"),
+            Err(Error::BurstFilterThreshold(error_threshold_us)) => {
+                assert!(!valid, "{threshold_us} µs");
+                assert_eq!(error_threshold_us, threshold_us);
+            }
+            Err(error) => panic!("unexpected error {error}"),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+fn cd(on: bool, lsb_t: u32, x: u32, y: u32) -> u32 {
+    ((on as u32) << 28) | (lsb_t << 22) | (x << 11) | y
+}
+
+fn time_high(msb_t: u32) -> u32 {
+    (0b1000 << 28) | msb_t
+}
+
+fn ext_trigger(lsb_t: u32, id: u32, rising: bool) -> u32 {
+    (0b1010 << 28) | (lsb_t << 22) | (id << 8) | (rising as u32)
+}
+
+fn to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+/// (t, x, y, polarity)
+type DvsEvents = Vec<(u64, u16, u16, u8)>;
+
+/// (t, id, polarity)
+type TriggerEvents = Vec<(u64, u8, u8)>;
+
+fn decode(
+    adapter: &mut neuromorphic_drivers::adapters::evt2::Adapter,
+    bytes: &[u8],
+) -> (DvsEvents, TriggerEvents) {
+    let mut dvs_events = Vec::new();
+    let mut trigger_events = Vec::new();
+    adapter.convert(
+        bytes,
+        |event| dvs_events.push((event.t, event.x, event.y, event.polarity as u8)),
+        |event| trigger_events.push((event.t, event.id, event.polarity as u8)),
+    );
+    (dvs_events, trigger_events)
+}
+
+#[test]
+fn cd_events() {
+    let bytes = to_bytes(&[
+        time_high(2),
+        cd(false, 5, 10, 0),
+        cd(true, 6, 1279, 719),
+        // out of bounds (x >= width), dropped but still updates the timestamp
+        cd(true, 7, 1280, 0),
+        cd(false, 8, 0, 1),
+    ]);
+    let mut adapter = neuromorphic_drivers::adapters::evt2::Adapter::from_dimensions(1280, 720);
+    let lengths = adapter.events_lengths(&bytes);
+    assert_eq!(lengths.dvs, 3);
+    assert_eq!(lengths.trigger, 0);
+    let (dvs_events, trigger_events) = decode(&mut adapter, &bytes);
+    assert_eq!(
+        dvs_events,
+        vec![
+            (
+                (2 << 6) | 5,
+                10,
+                719,
+                neuromorphic_types::DvsPolarity::Off as u8
+            ),
+            (
+                (2 << 6) | 6,
+                1279,
+                0,
+                neuromorphic_types::DvsPolarity::On as u8
+            ),
+            (
+                (2 << 6) | 8,
+                0,
+                718,
+                neuromorphic_types::DvsPolarity::Off as u8
+            ),
+        ]
+    );
+    assert!(trigger_events.is_empty());
+    assert_eq!(adapter.current_t(), (2 << 6) | 8);
+}
+
+#[test]
+fn time_high_overflow() {
+    let bytes = to_bytes(&[
+        time_high(0xfffffff),
+        cd(true, 63, 0, 0),
+        // the 28-bit counter wraps around
+        time_high(1),
+        cd(true, 2, 0, 0),
+    ]);
+    let mut adapter = neuromorphic_drivers::adapters::evt2::Adapter::from_dimensions(1280, 720);
+    let (dvs_events, _) = decode(&mut adapter, &bytes);
+    assert_eq!(
+        dvs_events.iter().map(|event| event.0).collect::<Vec<_>>(),
+        vec![(0xfffffff << 6) | 63, (1 << 34) | (1 << 6) | 2]
+    );
+
+    // consume must track overflows like convert
+    let mut consumer = neuromorphic_drivers::adapters::evt2::Adapter::from_dimensions(1280, 720);
+    consumer.consume(&bytes);
+    assert_eq!(consumer.current_t(), adapter.current_t());
+}
+
+#[test]
+fn ext_trigger_events() {
+    let bytes = to_bytes(&[
+        time_high(3),
+        ext_trigger(1, 0, true),
+        cd(true, 2, 4, 4),
+        ext_trigger(3, 17, false),
+    ]);
+    let mut adapter = neuromorphic_drivers::adapters::evt2::Adapter::from_dimensions(1280, 720);
+    let lengths = adapter.events_lengths(&bytes);
+    assert_eq!(lengths.dvs, 1);
+    assert_eq!(lengths.trigger, 2);
+    let (dvs_events, trigger_events) = decode(&mut adapter, &bytes);
+    assert_eq!(dvs_events.len(), 1);
+    assert_eq!(
+        trigger_events,
+        vec![
+            (
+                (3 << 6) | 1,
+                0,
+                neuromorphic_types::TriggerPolarity::Rising as u8
+            ),
+            (
+                (3 << 6) | 3,
+                17,
+                neuromorphic_types::TriggerPolarity::Falling as u8
+            ),
+        ]
+    );
+}
@@ -0,0 +1,130 @@
+fn cd(on: bool, lsb_t: u64, x: u64, y: u64, mask: u32) -> u64 {
+    ((on as u64) << 60) | (lsb_t << 54) | (x << 43) | (y << 32) | mask as u64
+}
+
+fn time_high(msb_t: u64) -> u64 {
+    (0b1000 << 60) | (msb_t << 32)
+}
+
+fn ext_trigger(lsb_t: u64, id: u64, rising: bool) -> u64 {
+    (0b1010 << 60) | (lsb_t << 54) | (id << 40) | ((rising as u64) << 32)
+}
+
+fn to_bytes(words: &[u64]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+/// (t, x, y, polarity)
+type DvsEvents = Vec<(u64, u16, u16, u8)>;
+
+/// (t, id, polarity)
+type TriggerEvents = Vec<(u64, u8, u8)>;
+
+fn decode(
+    adapter: &mut neuromorphic_drivers::adapters::evt21::Adapter,
+    bytes: &[u8],
+) -> (DvsEvents, TriggerEvents) {
+    let mut dvs_events = Vec::new();
+    let mut trigger_events = Vec::new();
+    adapter.convert(
+        bytes,
+        |event| dvs_events.push((event.t, event.x, event.y, event.polarity as u8)),
+        |event| trigger_events.push((event.t, event.id, event.polarity as u8)),
+    );
+    (dvs_events, trigger_events)
+}
+
+#[test]
+fn cd_events() {
+    let off = neuromorphic_types::DvsPolarity::Off as u8;
+    let on = neuromorphic_types::DvsPolarity::On as u8;
+    let bytes = to_bytes(&[
+        time_high(2),
+        cd(false, 5, 64, 0, 0b1),
+        cd(true, 6, 96, 719, 0b1),
+    ]);
+    let mut adapter = neuromorphic_drivers::adapters::evt21::Adapter::from_dimensions(1280, 720);
+    let lengths = adapter.events_lengths(&bytes);
+    assert_eq!(lengths.dvs, 2);
+    assert_eq!(lengths.trigger, 0);
+    let (dvs_events, trigger_events) = decode(&mut adapter, &bytes);
+    assert_eq!(
+        dvs_events,
+        vec![((2 << 6) | 5, 64, 719, off), ((2 << 6) | 6, 96, 0, on),]
+    );
+    assert!(trigger_events.is_empty());
+}
+
+#[test]
+fn vector_masks() {
+    let on = neuromorphic_types::DvsPolarity::On as u8;
+    let bytes = to_bytes(&[
+        // bits 0, 3, and 31 set
+        cd(true, 1, 32, 10, 0x80000009),
+        // the last vector overlaps the right edge, bits beyond the width are dropped
+        cd(true, 2, 1264, 10, 0xffffffff),
+        // empty mask
+        cd(true, 3, 0, 10, 0),
+    ]);
+    let mut adapter = neuromorphic_drivers::adapters::evt21::Adapter::from_dimensions(1280, 720);
+    let lengths = adapter.events_lengths(&bytes);
+    assert_eq!(lengths.dvs, 3 + 16);
+    let (dvs_events, _) = decode(&mut adapter, &bytes);
+    let mut expected = vec![(1, 32, 709, on), (1, 35, 709, on), (1, 63, 709, on)];
+    expected.extend((1264..1280).map(|x| (2, x, 709, on)));
+    assert_eq!(dvs_events, expected);
+    assert_eq!(adapter.current_t(), 3);
+}
+
+#[test]
+fn time_high_overflow() {
+    let bytes = to_bytes(&[
+        time_high(0xfffffff),
+        cd(true, 63, 0, 0, 1),
+        // the 28-bit counter wraps around
+        time_high(1),
+        cd(true, 2, 0, 0, 1),
+    ]);
+    let mut adapter = neuromorphic_drivers::adapters::evt21::Adapter::from_dimensions(1280, 720);
+    let (dvs_events, _) = decode(&mut adapter, &bytes);
+    assert_eq!(
+        dvs_events.iter().map(|event| event.0).collect::<Vec<_>>(),
+        vec![(0xfffffff << 6) | 63, (1 << 34) | (1 << 6) | 2]
+    );
+
+    // consume must track overflows like convert
+    let mut consumer = neuromorphic_drivers::adapters::evt21::Adapter::from_dimensions(1280, 720);
+    consumer.consume(&bytes);
+    assert_eq!(consumer.current_t(), adapter.current_t());
+}
+
+#[test]
+fn ext_trigger_events() {
+    let bytes = to_bytes(&[
+        time_high(3),
+        ext_trigger(1, 0, true),
+        cd(true, 2, 4, 4, 1),
+        ext_trigger(3, 17, false),
+    ]);
+    let mut adapter = neuromorphic_drivers::adapters::evt21::Adapter::from_dimensions(1280, 720);
+    let lengths = adapter.events_lengths(&bytes);
+    assert_eq!(lengths.dvs, 1);
+    assert_eq!(lengths.trigger, 2);
+    let (dvs_events, trigger_events) = decode(&mut adapter, &bytes);
+    assert_eq!(dvs_events.len(), 1);
+    assert_eq!(
+        trigger_events,
+        vec![
+            (
+                (3 << 6) | 1,
+                0,
+                neuromorphic_types::TriggerPolarity::Rising as u8
+            ),
+            (
+                (3 << 6) | 3,
+                17,
+                neuromorphic_types::TriggerPolarity::Falling as u8
+            ),
+        ]
+    );
+}
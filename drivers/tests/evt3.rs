@@ -73,3 +73,370 @@ fn convert() {
         }
     }
 }
+
+#[test]
+fn encode() {
+    let mut state = 0x2545f4914f6cdd1du64;
+    let mut dvs_events = Vec::new();
+    let mut trigger_events = Vec::new();
+    let mut t = 0u64;
+    for index in 0..100000u64 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        // mostly small steps, with occasional jumps larger than the 24-bit timestamp range
+        t += match state % 1000 {
+            0 => 1 << 24,
+            1..=500 => 0,
+            _ => state % 100,
+        };
+        if index % 1000 == 999 {
+            trigger_events.push(neuromorphic_types::TriggerEvent {
+                t,
+                id: (index / 1000 % 16) as u8,
+                polarity: if index % 2000 == 999 {
+                    neuromorphic_types::TriggerPolarity::Rising
+                } else {
+                    neuromorphic_types::TriggerPolarity::Falling
+                },
+            });
+        }
+        let x = if state % 3 == 1 {
+            (dvs_events
+                .last()
+                .map_or(0, |event: &neuromorphic_types::DvsEvent<u64, u16, u16>| {
+                    event.x
+                })
+                + 1
+                + (state >> 8) as u16 % 20)
+                % 1280
+        } else {
+            ((state >> 16) % 1280) as u16
+        };
+        dvs_events.push(neuromorphic_types::DvsEvent {
+            t,
+            x,
+            y: if state % 3 == 1 {
+                dvs_events.last().map_or(0, |event| event.y)
+            } else {
+                ((state >> 32) % 720) as u16
+            },
+            polarity: if (state >> 40) % 4 == 3 {
+                neuromorphic_types::DvsPolarity::Off
+            } else {
+                neuromorphic_types::DvsPolarity::On
+            },
+        });
+    }
+
+    let mut bytes = Vec::new();
+    let mut encoder = neuromorphic_drivers::adapters::evt3::Encoder::from_dimensions(1280, 720);
+    encoder.encode(&dvs_events, &trigger_events, &mut bytes);
+    let mut adapter = neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+    let mut decoded_dvs_events = Vec::new();
+    let mut decoded_trigger_events = Vec::new();
+    adapter.convert(
+        &bytes,
+        |event| decoded_dvs_events.push(event),
+        |event| decoded_trigger_events.push(event),
+    );
+    assert_eq!(adapter.current_t(), encoder.current_t());
+
+    // the encoder groups events with the same timestamp, row, and polarity into vectors sorted by x
+    let key = |event: &neuromorphic_types::DvsEvent<u64, u16, u16>| {
+        (event.t, event.y, event.polarity as u8, event.x)
+    };
+    let mut expected: Vec<_> = dvs_events.iter().map(key).collect();
+    expected.sort_unstable();
+    expected.dedup();
+    let mut decoded: Vec<_> = decoded_dvs_events.iter().map(key).collect();
+    decoded.sort_unstable();
+    assert_eq!(decoded, expected);
+    assert_eq!(
+        decoded_trigger_events
+            .iter()
+            .map(|event| (event.t, event.id, event.polarity as u8))
+            .collect::<Vec<_>>(),
+        trigger_events
+            .iter()
+            .map(|event| (event.t, event.id, event.polarity as u8))
+            .collect::<Vec<_>>(),
+    );
+}
+
+fn encoded_test_stream() -> Vec<u8> {
+    encoded_test_stream_with_density(0)
+}
+
+/// density controls the probability that an event is adjacent to the previous one,
+/// which makes the encoder generate vectors (0 for random positions, 7 for 7 in 8 adjacent events)
+fn encoded_test_stream_with_density(density: u64) -> Vec<u8> {
+    let mut state = 0x9e3779b97f4a7c15u64;
+    let mut dvs_events: Vec<neuromorphic_types::DvsEvent<u64, u16, u16>> = Vec::new();
+    let mut trigger_events = Vec::new();
+    let mut t = 0u64;
+    for index in 0..1000000u64 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        t += match state % 100000 {
+            0 => 1 << 24,
+            _ => state % 3,
+        };
+        if index % 10000 == 0 {
+            trigger_events.push(neuromorphic_types::TriggerEvent {
+                t,
+                id: 0,
+                polarity: neuromorphic_types::TriggerPolarity::Rising,
+            });
+        }
+        if (state >> 56) % 8 < density {
+            if let Some(previous) = dvs_events.last().copied() {
+                dvs_events.push(neuromorphic_types::DvsEvent {
+                    t: previous.t,
+                    x: (previous.x + 1 + (state >> 8) as u16 % 2) % 1280,
+                    ..previous
+                });
+                continue;
+            }
+        }
+        dvs_events.push(neuromorphic_types::DvsEvent {
+            t,
+            x: ((state >> 16) % 1280) as u16,
+            y: ((state >> 32) % 720) as u16,
+            polarity: if (state >> 48) % 2 == 1 {
+                neuromorphic_types::DvsPolarity::On
+            } else {
+                neuromorphic_types::DvsPolarity::Off
+            },
+        });
+    }
+    let mut bytes = Vec::new();
+    let mut encoder = neuromorphic_drivers::adapters::evt3::Encoder::from_dimensions(1280, 720);
+    encoder.encode(&dvs_events, &trigger_events, &mut bytes);
+    bytes
+}
+
+fn dvs_keys(events: &[neuromorphic_types::DvsEvent<u64, u16, u16>]) -> Vec<(u64, u16, u16, u8)> {
+    events
+        .iter()
+        .map(|event| (event.t, event.x, event.y, event.polarity as u8))
+        .collect()
+}
+
+#[test]
+fn checkpoints() {
+    let bytes = encoded_test_stream();
+    let mut adapter = neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+    let mut dvs_events = Vec::new();
+    let mut trigger_events = 0;
+    adapter.convert(
+        &bytes,
+        |event| dvs_events.push(event),
+        |_| trigger_events += 1,
+    );
+
+    let checkpoints_adapter =
+        neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+    let checkpoints = checkpoints_adapter.checkpoints(&bytes, 1 << 16);
+    assert!(checkpoints.len() > 10);
+    assert_eq!(checkpoints[0].offset, 0);
+    let mut segments_dvs_events = Vec::new();
+    let mut segments_trigger_events = 0;
+    let mut segment_adapter = checkpoints_adapter.clone();
+    // decode the segments in reverse order to make sure that they do not depend on each other
+    for (index, checkpoint) in checkpoints.iter().enumerate().rev() {
+        if index > 0 {
+            assert_eq!(bytes[checkpoint.offset + 1] >> 4, 0b1000);
+        }
+        let end = checkpoints
+            .get(index + 1)
+            .map_or(bytes.len(), |next_checkpoint| next_checkpoint.offset);
+        segment_adapter.restore(checkpoint.state);
+        let mut segment_dvs_events = Vec::new();
+        segment_adapter.convert(
+            &bytes[checkpoint.offset..end],
+            |event| segment_dvs_events.push(event),
+            |_| segments_trigger_events += 1,
+        );
+        if index == checkpoints.len() - 1 {
+            assert_eq!(segment_adapter.current_t(), adapter.current_t());
+        }
+        segment_dvs_events.extend(segments_dvs_events);
+        segments_dvs_events = segment_dvs_events;
+    }
+    assert_eq!(dvs_keys(&segments_dvs_events), dvs_keys(&dvs_events));
+    assert_eq!(segments_trigger_events, trigger_events);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn convert_parallel() {
+    let bytes = encoded_test_stream();
+    let mut adapter = neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+    let start = std::time::Instant::now();
+    let mut dvs_events = Vec::new();
+    let mut trigger_events = Vec::new();
+    adapter.convert(
+        &bytes,
+        |event| dvs_events.push(event),
+        |event| trigger_events.push(event),
+    );
+    println!("convert: {} µs", start.elapsed().as_micros());
+
+    let mut parallel_adapter =
+        neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+    let start = std::time::Instant::now();
+    let parallel_events = parallel_adapter.convert_parallel(&bytes, 1 << 16);
+    println!("convert_parallel: {} µs", start.elapsed().as_micros());
+    assert_eq!(dvs_keys(&parallel_events.dvs_events), dvs_keys(&dvs_events));
+    assert_eq!(
+        parallel_events
+            .trigger_events
+            .iter()
+            .map(|event| event.t)
+            .collect::<Vec<_>>(),
+        trigger_events
+            .iter()
+            .map(|event| event.t)
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(parallel_adapter.current_t(), adapter.current_t());
+}
+
+#[test]
+fn convert_into() {
+    let bytes = encoded_test_stream_with_density(7);
+    for _ in 0..5 {
+        let mut adapter = neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+        let start = std::time::Instant::now();
+        let mut dvs_events = Vec::with_capacity(adapter.events_lengths(&bytes).dvs);
+        let mut trigger_events = 0;
+        adapter.convert(
+            &bytes,
+            |event| dvs_events.push(event),
+            |_| trigger_events += 1,
+        );
+        println!(
+            "convert (calc. size + single allocation): {} µs, dvs={}",
+            start.elapsed().as_micros(),
+            dvs_events.len(),
+        );
+
+        let mut into_adapter =
+            neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+        let mut into_dvs_events = vec![
+            neuromorphic_types::DvsEvent {
+                t: 0,
+                x: 0,
+                y: 0,
+                polarity: neuromorphic_types::DvsPolarity::Off,
+            };
+            into_adapter.events_lengths(&bytes).dvs
+        ];
+        let mut into_trigger_events = 0;
+        let start = std::time::Instant::now();
+        let length =
+            into_adapter.convert_into(&bytes, &mut into_dvs_events, |_| into_trigger_events += 1);
+        println!(
+            "convert_into (pre-sized slice, excluding allocation): {} µs, dvs={}",
+            start.elapsed().as_micros(),
+            length,
+        );
+        assert_eq!(length, into_dvs_events.len());
+        assert_eq!(dvs_keys(&into_dvs_events), dvs_keys(&dvs_events));
+        assert_eq!(into_trigger_events, trigger_events);
+        assert_eq!(into_adapter.current_t(), adapter.current_t());
+    }
+}
+
+#[test]
+fn convert_into_columns() {
+    let bytes = encoded_test_stream_with_density(4);
+    let mut adapter = neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+    let mut dvs_events = Vec::new();
+    adapter.convert(&bytes, |event| dvs_events.push(event), |_| {});
+    let mut columns_adapter: neuromorphic_drivers::adapters::Adapter =
+        neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720).into();
+    let mut columns = neuromorphic_drivers::adapters::DvsColumns::default();
+    for chunk in bytes.chunks(1 << 16) {
+        columns_adapter.convert_into_columns(chunk, &mut columns, |_| {});
+    }
+    assert_eq!(columns.len(), dvs_events.len());
+    assert_eq!(
+        (0..columns.len())
+            .map(|index| (
+                columns.t[index],
+                columns.x[index],
+                columns.y[index],
+                columns.on[index] as u8
+            ))
+            .collect::<Vec<_>>(),
+        dvs_keys(&dvs_events),
+    );
+    assert_eq!(columns_adapter.current_t(), adapter.current_t());
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn convert_into_record_batches() {
+    let bytes = encoded_test_stream();
+    let mut adapter = neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720);
+    let mut dvs_events = Vec::new();
+    let mut trigger_events = Vec::new();
+    adapter.convert(
+        &bytes,
+        |event| dvs_events.push(event),
+        |event| trigger_events.push(event),
+    );
+    let mut batches_adapter: neuromorphic_drivers::adapters::Adapter =
+        neuromorphic_drivers::adapters::evt3::Adapter::from_dimensions(1280, 720).into();
+    let record_batches = batches_adapter.convert_into_record_batches(&bytes).unwrap();
+    assert_eq!(
+        record_batches.dvs_events.schema(),
+        neuromorphic_drivers::adapters::dvs_events_schema()
+    );
+    assert_eq!(record_batches.dvs_events.num_rows(), dvs_events.len());
+    assert_eq!(
+        record_batches.trigger_events.num_rows(),
+        trigger_events.len()
+    );
+    let column = |name: &str| {
+        record_batches
+            .dvs_events
+            .column_by_name(name)
+            .unwrap()
+            .clone()
+    };
+    let t = column("t");
+    let t = t
+        .as_any()
+        .downcast_ref::<arrow::array::UInt64Array>()
+        .unwrap();
+    let x = column("x");
+    let x = x
+        .as_any()
+        .downcast_ref::<arrow::array::UInt16Array>()
+        .unwrap();
+    let y = column("y");
+    let y = y
+        .as_any()
+        .downcast_ref::<arrow::array::UInt16Array>()
+        .unwrap();
+    let polarity = column("polarity");
+    let polarity = polarity
+        .as_any()
+        .downcast_ref::<arrow::array::UInt8Array>()
+        .unwrap();
+    assert_eq!(
+        (0..dvs_events.len())
+            .map(|index| (
+                t.value(index),
+                x.value(index),
+                y.value(index),
+                polarity.value(index)
+            ))
+            .collect::<Vec<_>>(),
+        dvs_keys(&dvs_events),
+    );
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::event;
+use neuromorphic_drivers::filters::Filter;
+
+fn kept<F: Filter>(
+    filter: &mut F,
+    events: &[neuromorphic_types::DvsEvent<u64, u16, u16>],
+) -> Vec<bool> {
+    events.iter().map(|event| filter.keep(*event)).collect()
+}
+
+#[test]
+fn background_activity() {
+    let mut filter = neuromorphic_drivers::filters::BackgroundActivity::new(4, 4, 100);
+    assert_eq!(
+        kept(
+            &mut filter,
+            &[
+                event(0, 1, 1, true),
+                // neighbour of (1, 1)
+                event(50, 2, 2, false),
+                // same pixel, no support from itself
+                event(60, 2, 2, true),
+                // neighbour of (2, 2) but too late
+                event(200, 3, 3, true),
+                // not a neighbour of (3, 3)
+                event(210, 0, 0, true),
+                // corner, neighbour of (0, 0)
+                event(220, 0, 1, true),
+            ]
+        ),
+        vec![false, true, true, false, false, true]
+    );
+}
+
+#[test]
+fn refractory() {
+    let mut filter = neuromorphic_drivers::filters::Refractory::new(2, 1, 100);
+    assert_eq!(
+        kept(
+            &mut filter,
+            &[
+                event(0, 0, 0, true),
+                event(50, 0, 0, false),
+                event(60, 1, 0, true),
+                event(100, 0, 0, true),
+                event(150, 0, 0, true),
+            ]
+        ),
+        vec![true, false, true, true, false]
+    );
+}
+
+#[test]
+fn hot_pixel() {
+    let mut filter = neuromorphic_drivers::filters::HotPixel::new(2, 1, 1000, 2);
+    assert_eq!(
+        kept(
+            &mut filter,
+            &[
+                event(0, 0, 0, true),
+                event(10, 0, 0, true),
+                event(20, 0, 0, true),
+                event(30, 1, 0, true),
+                // the window [1000, 2000) is empty, counts are reset at 2500
+                event(2500, 0, 0, true),
+                event(2999, 0, 0, true),
+                event(2999, 0, 0, true),
+                event(3000, 0, 0, true),
+            ]
+        ),
+        vec![true, true, false, true, true, true, false, true]
+    );
+}
+
+#[test]
+fn spatio_temporal_contrast_and_trail() {
+    let events = [
+        event(0, 0, 0, true),
+        event(10, 0, 0, true),
+        event(20, 0, 0, true),
+        // polarity change starts a new burst
+        event(30, 0, 0, false),
+        event(40, 0, 0, false),
+        // too late, new burst
+        event(200, 0, 0, false),
+    ];
+    let mut filter = neuromorphic_drivers::filters::SpatioTemporalContrast::new(1, 1, 100, false);
+    assert_eq!(
+        kept(&mut filter, &events),
+        vec![false, true, true, false, true, false]
+    );
+    let mut filter = neuromorphic_drivers::filters::SpatioTemporalContrast::new(1, 1, 100, true);
+    assert_eq!(
+        kept(&mut filter, &events),
+        vec![false, true, false, false, true, false]
+    );
+    let mut filter = neuromorphic_drivers::filters::Trail::new(1, 1, 100);
+    assert_eq!(
+        kept(&mut filter, &events),
+        vec![true, false, false, true, false, true]
+    );
+}
+
+#[test]
+fn out_of_order() {
+    // out-of-order events must not panic (subtraction overflow in debug builds)
+    let events = [
+        event(100, 0, 0, true),
+        event(50, 0, 0, true),
+        event(0, 0, 0, true),
+    ];
+    let mut filter = neuromorphic_drivers::filters::Trail::new(1, 1, 100);
+    assert_eq!(kept(&mut filter, &events), vec![true, false, false]);
+    let mut filter = neuromorphic_drivers::filters::HotPixel::new(1, 1, 1000, 2);
+    assert_eq!(kept(&mut filter, &events), vec![true, true, false]);
+}
+
+#[test]
+fn chain() {
+    let mut chain = neuromorphic_drivers::filters::Chain::new()
+        .with(neuromorphic_drivers::filters::Refractory::new(1, 1, 100))
+        .with(neuromorphic_drivers::filters::HotPixel::new(1, 1, 10000, 2));
+    let mut events = vec![
+        event(0, 0, 0, true),
+        event(10, 0, 0, true),
+        event(100, 0, 0, true),
+        event(200, 0, 0, true),
+    ];
+    chain.retain(&mut events);
+    // the refractory filter rejects the second event before the hot pixel filter counts it
+    assert_eq!(
+        events.iter().map(|event| event.t).collect::<Vec<_>>(),
+        vec![0, 100]
+    );
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::event;
+use neuromorphic_drivers::frames;
+
+#[test]
+fn duration_histogram() {
+    let mut accumulator =
+        frames::Accumulator::new(4, 3, frames::Window::Duration(100), frames::Mode::Histogram);
+    let mut frames = Vec::new();
+    accumulator.extend(
+        [
+            event(1000, 0, 0, true),
+            event(1010, 0, 0, true),
+            event(1050, 3, 2, false),
+            // the window [1100, 1200) is empty
+            event(1250, 1, 1, true),
+        ],
+        |frame| frames.push(frame.clone()),
+    );
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].t, 1100);
+    match &frames[0].pixels {
+        frames::Pixels::Histogram { on, off } => {
+            assert_eq!(on[0], 2);
+            assert_eq!(off[2 * 4 + 3], 1);
+            assert_eq!(on.iter().sum::<u32>(), 2);
+            assert_eq!(off.iter().sum::<u32>(), 1);
+        }
+        _ => panic!("unexpected pixels"),
+    }
+    assert_eq!(frames[1].t, 1200);
+    assert_eq!(
+        frames[1].pixels,
+        frames::Pixels::Histogram {
+            on: vec![0; 12],
+            off: vec![0; 12]
+        }
+    );
+    accumulator.flush(1300, |frame| frames.push(frame.clone()));
+    assert_eq!(frames.len(), 3);
+    match &frames[2].pixels {
+        frames::Pixels::Histogram { on, .. } => assert_eq!(on[4 + 1], 1),
+        _ => panic!("unexpected pixels"),
+    }
+    accumulator.flush(1400, |frame| frames.push(frame.clone()));
+    assert_eq!(frames.len(), 3);
+}
+
+#[test]
+fn count_signed_sum_and_last_polarity() {
+    let events = [
+        event(0, 1, 0, true),
+        event(1, 1, 0, true),
+        event(2, 1, 0, false),
+        event(3, 0, 1, false),
+        event(4, 0, 1, true),
+        event(5, 0, 0, false),
+    ];
+    let mut sums = Vec::new();
+    let mut accumulator =
+        frames::Accumulator::new(2, 2, frames::Window::Count(3), frames::Mode::SignedSum);
+    accumulator.extend(events, |frame| {
+        sums.push((frame.t, frame.pixels.clone()));
+    });
+    assert_eq!(
+        sums,
+        vec![
+            (2, frames::Pixels::SignedSum(vec![0, 1, 0, 0])),
+            (5, frames::Pixels::SignedSum(vec![-1, 0, 0, 0])),
+        ]
+    );
+    let mut polarities = Vec::new();
+    let mut accumulator =
+        frames::Accumulator::new(2, 2, frames::Window::Count(3), frames::Mode::LastPolarity);
+    accumulator.extend(events, |frame| polarities.push(frame.pixels.clone()));
+    assert_eq!(
+        polarities,
+        vec![
+            frames::Pixels::LastPolarity(vec![0, -1, 0, 0]),
+            frames::Pixels::LastPolarity(vec![-1, 0, 1, 0]),
+        ]
+    );
+}
+
+#[test]
+fn time_surface() {
+    let mut accumulator = frames::Accumulator::new(
+        2,
+        1,
+        frames::Window::Duration(1000),
+        frames::Mode::TimeSurface { tau: 500.0 },
+    );
+    let mut surfaces = Vec::new();
+    accumulator.extend(
+        [
+            event(0, 0, 0, true),
+            event(500, 1, 0, false),
+            event(2500, 1, 0, true),
+        ],
+        |frame| match &frame.pixels {
+            frames::Pixels::TimeSurface(values) => surfaces.push(values.clone()),
+            _ => panic!("unexpected pixels"),
+        },
+    );
+    assert_eq!(surfaces.len(), 2);
+    assert!((surfaces[0][0] - (-2.0f32).exp()).abs() < 1e-6);
+    assert!((surfaces[0][1] + (-1.0f32).exp()).abs() < 1e-6);
+    // time surfaces are not reset between frames
+    assert!((surfaces[1][0] - (-4.0f32).exp()).abs() < 1e-6);
+    assert!((surfaces[1][1] + (-3.0f32).exp()).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic]
+fn outside_frame() {
+    let mut accumulator =
+        frames::Accumulator::new(2, 2, frames::Window::Count(1), frames::Mode::Histogram);
+    accumulator.push(event(0, 2, 0, true), |_| {});
+}
@@ -0,0 +1,143 @@
+use neuromorphic_drivers::filters::Filter;
+
+fn counts(hot_pixels: &[(u16, u16)]) -> neuromorphic_drivers::hot_pixels::Counts {
+    let width = neuromorphic_drivers::prophesee_evk4::PROPERTIES.width;
+    let height = neuromorphic_drivers::prophesee_evk4::PROPERTIES.height;
+    let mut counts = vec![1u64; width as usize * height as usize];
+    for (index, (x, y)) in hot_pixels.iter().enumerate() {
+        counts[*y as usize * width as usize + *x as usize] = 1000 + index as u64;
+    }
+    neuromorphic_drivers::hot_pixels::Counts {
+        width,
+        height,
+        duration_us: 1000000,
+        counts,
+    }
+}
+
+#[test]
+fn mask_pixels() {
+    let mask = counts(&[(10, 20), (30, 40), (50, 60)]).mask(100.0, None);
+    assert_eq!(mask.pixels, vec![(50, 60), (30, 40), (10, 20)]);
+    assert_eq!(
+        counts(&[(10, 20), (30, 40), (50, 60)])
+            .mask(100.0, Some(2))
+            .pixels,
+        vec![(50, 60), (30, 40)]
+    );
+    let mut configuration = neuromorphic_drivers::prophesee_evk4::DEFAULT_CONFIGURATION;
+    configuration.pixel_mask[0] = 1;
+    assert_eq!(mask.apply_to_prophesee_evk4(&mut configuration).unwrap(), 0);
+    assert_eq!(configuration.pixel_mask, [0; 21]);
+    assert_eq!(
+        configuration.masked_pixels,
+        vec![
+            neuromorphic_drivers::prophesee_evk4::Pixel { x: 50, y: 60 },
+            neuromorphic_drivers::prophesee_evk4::Pixel { x: 30, y: 40 },
+            neuromorphic_drivers::prophesee_evk4::Pixel { x: 10, y: 20 },
+        ]
+    );
+    assert_eq!(configuration.x_mask, [0; 20]);
+    assert_eq!(configuration.y_mask, [0; 12]);
+    let mut filter = mask.filter();
+    let event = |x, y| neuromorphic_types::DvsEvent {
+        t: 0,
+        x,
+        y,
+        polarity: neuromorphic_types::DvsPolarity::On,
+    };
+    assert!(!filter.keep(event(30, 40)));
+    assert!(filter.keep(event(30, 41)));
+}
+
+#[test]
+fn mask_rows_and_columns() {
+    let mut hot_pixels: Vec<(u16, u16)> = (0..100).map(|y| (700, y * 7)).collect();
+    hot_pixels.extend((0..10).map(|x| (x * 3, 500)));
+    hot_pixels.extend((0..10).map(|index| (900 + index, 100 + index)));
+    let mask = counts(&hot_pixels).mask(100.0, None);
+    assert_eq!(mask.pixels.len(), 120);
+    let mut configuration = neuromorphic_drivers::prophesee_evk4::DEFAULT_CONFIGURATION;
+    assert_eq!(mask.apply_to_prophesee_evk4(&mut configuration).unwrap(), 1);
+    let mut x_mask = [0u64; 20];
+    x_mask[700 / 64] |= 1 << (700 % 64);
+    assert_eq!(configuration.x_mask, x_mask);
+    assert_eq!(configuration.y_mask, [0; 12]);
+    assert_eq!(configuration.masked_pixels.len(), 20);
+
+    let mut configuration = neuromorphic_drivers::prophesee_evk4::DEFAULT_CONFIGURATION;
+    configuration.mask_intersection_only = true;
+    assert!(mask.apply_to_prophesee_evk4(&mut configuration).is_err());
+}
+
+#[test]
+fn save_and_load() {
+    let directory = std::env::temp_dir().join(format!(
+        "neuromorphic_drivers_hot_pixels_{}",
+        std::process::id()
+    ));
+    let mask = counts(&[(1, 2), (3, 4)]).mask(100.0, None);
+    mask.save(&directory, "00050423").unwrap();
+    assert_eq!(
+        neuromorphic_drivers::hot_pixels::Mask::load(&directory, "00050423").unwrap(),
+        Some(mask)
+    );
+    assert_eq!(
+        neuromorphic_drivers::hot_pixels::Mask::load(&directory, "unknown").unwrap(),
+        None
+    );
+
+    // a pixel outside the mask would make filter panic
+    let mut invalid_mask = counts(&[(1, 2)]).mask(100.0, None);
+    invalid_mask.height = 2;
+    invalid_mask.save(&directory, "invalid").unwrap();
+    assert!(matches!(
+        neuromorphic_drivers::hot_pixels::Mask::load(&directory, "invalid"),
+        Err(neuromorphic_drivers::hot_pixels::Error::Pixel {
+            x: 1,
+            y: 2,
+            width: 1280,
+            height: 2,
+        })
+    ));
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn record() {
+    std::env::set_var(
+        neuromorphic_drivers::devices::mock::SERIALS_VARIABLE,
+        "MOCK0",
+    );
+    let (flag, event_loop) = neuromorphic_drivers::flag_and_event_loop().unwrap();
+    let device = neuromorphic_drivers::open(
+        Some("MOCK0"),
+        Some(neuromorphic_drivers::Configuration::Mock(
+            neuromorphic_drivers::devices::mock::DEFAULT_CONFIGURATION,
+        )),
+        None,
+        event_loop,
+        flag.clone(),
+    )
+    .unwrap();
+
+    // discard the buffers produced before record, the sensor's timestamps no longer start at 0
+    let start = std::time::Instant::now();
+    while start.elapsed() < std::time::Duration::from_millis(500) {
+        device.next_with_timeout(&std::time::Duration::from_millis(100));
+    }
+
+    let counts = neuromorphic_drivers::hot_pixels::record(
+        &device,
+        &flag,
+        std::time::Duration::from_millis(500),
+    )
+    .unwrap();
+    assert_eq!((counts.width, counts.height), (1280, 720));
+    assert!(
+        counts.duration_us > 300000 && counts.duration_us < 700000,
+        "duration_us = {}",
+        counts.duration_us
+    );
+    assert!(counts.counts.iter().sum::<u64>() > 0);
+}
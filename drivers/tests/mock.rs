@@ -0,0 +1,93 @@
+#[test]
+fn read() -> Result<(), neuromorphic_drivers::Error> {
+    std::env::set_var(
+        neuromorphic_drivers::devices::mock::SERIALS_VARIABLE,
+        "MOCK0",
+    );
+    let (flag, event_loop) = neuromorphic_drivers::flag_and_event_loop()?;
+    let device = neuromorphic_drivers::open(
+        Some("MOCK0"),
+        Some(neuromorphic_drivers::Configuration::Mock(
+            neuromorphic_drivers::devices::mock::Configuration {
+                source: neuromorphic_drivers::devices::mock::Source::Synthetic {
+                    events_per_second: 100000,
+                    trigger_period_us: Some(10000),
+                },
+                packet_duration_us: 1000,
+                enable_output: true,
+            },
+        )),
+        None,
+        event_loop,
+        flag.clone(),
+    )?;
+    assert_eq!(device.serial(), "MOCK0");
+    let mut adapter = device.adapter();
+    let mut dvs_events = 0;
+    let mut trigger_events = 0;
+    let start = std::time::Instant::now();
+    while start.elapsed() < std::time::Duration::from_millis(200) {
+        if let Some(buffer_view) = device.next_with_timeout(&std::time::Duration::from_millis(100))
+        {
+            adapter.convert(
+                buffer_view.slice,
+                |_| dvs_events += 1,
+                |_| trigger_events += 1,
+            );
+        }
+        flag.load_error()?;
+    }
+    assert!(dvs_events > 0);
+    assert!(trigger_events > 0);
+    assert!(adapter.current_t() > 0);
+
+    // a file source that cannot be opened is rejected before reaching the device
+    assert!(device
+        .update_configuration(neuromorphic_drivers::Configuration::Mock(
+            neuromorphic_drivers::devices::mock::Configuration {
+                source: neuromorphic_drivers::devices::mock::Source::File {
+                    path: std::env::temp_dir()
+                        .join("neuromorphic_drivers_mock_missing.raw")
+                        .to_string_lossy()
+                        .into_owned(),
+                    repeat: false,
+                },
+                ..neuromorphic_drivers::devices::mock::DEFAULT_CONFIGURATION
+            },
+        ))
+        .is_err());
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    flag.load_error()?;
+
+    device.update_configuration(neuromorphic_drivers::Configuration::Mock(
+        neuromorphic_drivers::devices::mock::Configuration {
+            enable_output: false,
+            ..neuromorphic_drivers::devices::mock::DEFAULT_CONFIGURATION
+        },
+    ))?;
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    while device
+        .next_with_timeout(&std::time::Duration::from_millis(0))
+        .is_some()
+    {}
+    assert!(device
+        .next_with_timeout(&std::time::Duration::from_millis(100))
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn list() -> Result<(), neuromorphic_drivers::rusb::Error> {
+    std::env::set_var(
+        neuromorphic_drivers::devices::mock::SERIALS_VARIABLE,
+        "MOCK0",
+    );
+    let listed_devices = neuromorphic_drivers::list_devices()?;
+    assert!(listed_devices.iter().any(|listed_device| {
+        matches!(
+            listed_device.device_type,
+            neuromorphic_drivers::devices::Type::Mock
+        ) && listed_device.serial.as_deref().ok() == Some("MOCK0")
+    }));
+    Ok(())
+}
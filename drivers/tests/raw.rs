@@ -0,0 +1,316 @@
+fn write_test_file(path: &std::path::Path, header: bool) -> u64 {
+    let mut dvs_events = Vec::new();
+    let mut state = 0x9e3779b97f4a7c15u64;
+    let mut t = 0u64;
+    for _ in 0..1000000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        t += state % 4;
+        dvs_events.push(neuromorphic_types::DvsEvent {
+            t,
+            x: ((state >> 16) % 1280) as u16,
+            y: ((state >> 32) % 720) as u16,
+            polarity: if (state >> 48) % 2 == 1 {
+                neuromorphic_types::DvsPolarity::On
+            } else {
+                neuromorphic_types::DvsPolarity::Off
+            },
+        });
+    }
+    let mut bytes = Vec::new();
+    if header {
+        bytes.extend_from_slice(
+            b"% camera_integrator_name Prophesee\n% date 2024-03-01 10:20:30\n% format EVT3;height=720;width=1280\n% serial_number 00ca0009\n% end\n",
+        );
+    }
+    let mut encoder = neuromorphic_drivers::adapters::evt3::Encoder::from_dimensions(1280, 720);
+    encoder.encode(&dvs_events, &[], &mut bytes);
+    std::fs::write(path, bytes).unwrap();
+    t
+}
+
+fn read_all(reader: &mut neuromorphic_drivers::raw::Reader) -> Vec<(u64, u16, u16)> {
+    let mut events = Vec::new();
+    for chunk in reader {
+        events.extend(
+            chunk
+                .unwrap()
+                .dvs_events
+                .iter()
+                .map(|event| (event.t, event.x, event.y)),
+        );
+    }
+    events
+}
+
+#[test]
+fn read() {
+    let path = std::env::temp_dir().join("neuromorphic_drivers_raw_read.raw");
+    let last_t = write_test_file(&path, true);
+    let mut reader = neuromorphic_drivers::raw::Reader::open(&path).unwrap();
+    let header = reader.header().clone();
+    assert_eq!(header.format, neuromorphic_drivers::raw::Format::Evt3);
+    assert_eq!((header.width, header.height), (1280, 720));
+    assert_eq!(header.serial.as_deref(), Some("00ca0009"));
+    assert_eq!(header.date.as_deref(), Some("2024-03-01 10:20:30"));
+    let events = read_all(&mut reader);
+    assert_eq!(events.len(), 1000000);
+    assert_eq!(reader.current_t(), last_t);
+
+    for target in [0, 1, last_t / 3, last_t / 2, last_t - 10, last_t + 1] {
+        reader.seek(target).unwrap();
+        let sought_events = read_all(&mut reader);
+        let expected: Vec<_> = events
+            .iter()
+            .filter(|event| event.0 >= target)
+            .cloned()
+            .collect();
+        assert_eq!(sought_events, expected);
+    }
+
+    let mut headerless_path = path.clone();
+    headerless_path.set_extension("bin");
+    write_test_file(&headerless_path, false);
+    let mut headerless_reader = neuromorphic_drivers::raw::Reader::open_headerless(
+        &headerless_path,
+        neuromorphic_drivers::raw::Format::Evt3,
+        1280,
+        720,
+    )
+    .unwrap();
+    assert_eq!(read_all(&mut headerless_reader), events);
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(headerless_path).unwrap();
+}
+
+fn read_all_with_configuration_changes(
+    reader: &mut neuromorphic_drivers::raw::Reader,
+) -> (
+    Vec<(u64, u16, u16)>,
+    Vec<neuromorphic_drivers::raw::ConfigurationChange>,
+) {
+    let mut events = Vec::new();
+    let mut configuration_changes = Vec::new();
+    for chunk in reader {
+        let chunk = chunk.unwrap();
+        events.extend(
+            chunk
+                .dvs_events
+                .iter()
+                .map(|event| (event.t, event.x, event.y)),
+        );
+        configuration_changes.extend(chunk.configuration_changes);
+    }
+    (events, configuration_changes)
+}
+
+fn json(configuration: &neuromorphic_drivers::Configuration) -> String {
+    serde_json::to_string(configuration).unwrap()
+}
+
+fn synthetic_configuration(events_per_second: u32) -> neuromorphic_drivers::Configuration {
+    neuromorphic_drivers::Configuration::Mock(neuromorphic_drivers::devices::mock::Configuration {
+        source: neuromorphic_drivers::devices::mock::Source::Synthetic {
+            events_per_second,
+            trigger_period_us: None,
+        },
+        ..neuromorphic_drivers::devices::mock::DEFAULT_CONFIGURATION
+    })
+}
+
+fn open_mock(
+    configuration: &neuromorphic_drivers::Configuration,
+) -> (
+    neuromorphic_drivers::Device,
+    neuromorphic_drivers::Flag<neuromorphic_drivers::Error, neuromorphic_drivers::UsbOverflow>,
+) {
+    std::env::set_var(
+        neuromorphic_drivers::devices::mock::SERIALS_VARIABLE,
+        "MOCK0",
+    );
+    let (flag, event_loop) = neuromorphic_drivers::flag_and_event_loop().unwrap();
+    let device = neuromorphic_drivers::open(
+        Some("MOCK0"),
+        Some(configuration.clone()),
+        None,
+        event_loop,
+        flag.clone(),
+    )
+    .unwrap();
+    (device, flag)
+}
+
+#[test]
+fn record() {
+    let path = std::env::temp_dir().join("neuromorphic_drivers_raw_record.raw");
+    let mut encoded_path = path.clone();
+    encoded_path.set_extension("bin");
+    write_test_file(&encoded_path, false);
+    let bytes = std::fs::read(&encoded_path).unwrap();
+    let header = neuromorphic_drivers::raw::Header {
+        format: neuromorphic_drivers::raw::Format::Evt3,
+        width: 1280,
+        height: 720,
+        serial: Some("00ca0009".to_owned()),
+        date: Some("2024-03-01 10:20:30".to_owned()),
+        entries: vec![(
+            "neuromorphic_drivers_configuration".to_owned(),
+            json(&neuromorphic_drivers::Configuration::Mock(
+                neuromorphic_drivers::devices::mock::DEFAULT_CONFIGURATION,
+            )),
+        )],
+    };
+    let configurations = [synthetic_configuration(1000), synthetic_configuration(2000)];
+    // the second change starts just before the end of the reader's first chunk and continues in the second
+    let marker_length = (json(&configurations[0]).len() + 5) * 2;
+    let split_offsets = [131072 * 2, (1 << 20) - marker_length - 10];
+    let mut changes_t = Vec::new();
+    {
+        let mut recorder = neuromorphic_drivers::raw::Recorder::create(&path, &header).unwrap();
+        let mut offset = 0;
+        for (split_offset, configuration) in split_offsets.iter().zip(configurations.iter()) {
+            recorder.write_slice(&bytes[offset..*split_offset]).unwrap();
+            offset = *split_offset;
+            changes_t.push(recorder.current_t());
+            recorder.write_configuration_change(configuration).unwrap();
+        }
+        recorder.write_slice(&bytes[offset..]).unwrap();
+    }
+    let mut reader = neuromorphic_drivers::raw::Reader::open(&path).unwrap();
+    let read_header = reader.header().clone();
+    assert_eq!(read_header.format, header.format);
+    assert_eq!((read_header.width, read_header.height), (1280, 720));
+    assert_eq!(read_header.serial, header.serial);
+    assert_eq!(read_header.date, header.date);
+    let configuration = read_header
+        .entries
+        .iter()
+        .find(|(key, _)| key == "neuromorphic_drivers_configuration")
+        .map(|(_, value)| serde_json::from_str(value).unwrap());
+    assert!(matches!(
+        configuration,
+        Some(neuromorphic_drivers::Configuration::Mock(_))
+    ));
+    let mut headerless_reader = neuromorphic_drivers::raw::Reader::open_headerless(
+        &encoded_path,
+        neuromorphic_drivers::raw::Format::Evt3,
+        1280,
+        720,
+    )
+    .unwrap();
+    let expected_events = read_all(&mut headerless_reader);
+    let (events, configuration_changes) = read_all_with_configuration_changes(&mut reader);
+    assert_eq!(events, expected_events);
+    assert_eq!(
+        configuration_changes
+            .iter()
+            .map(|change| (change.t, json(&change.configuration)))
+            .collect::<Vec<_>>(),
+        changes_t
+            .iter()
+            .zip(configurations.iter())
+            .map(|(t, configuration)| (*t, json(configuration)))
+            .collect::<Vec<_>>()
+    );
+
+    reader.seek(changes_t[1]).unwrap();
+    let (_, configuration_changes) = read_all_with_configuration_changes(&mut reader);
+    assert_eq!(configuration_changes.len(), 1);
+    assert_eq!(configuration_changes[0].t, changes_t[1]);
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(encoded_path).unwrap();
+}
+
+#[test]
+fn record_buffer_views() {
+    let configuration = synthetic_configuration(1000000);
+    let (device, flag) = open_mock(&configuration);
+    let path = std::env::temp_dir().join("neuromorphic_drivers_raw_record_buffer_views.raw");
+    let updated_configuration = synthetic_configuration(200000);
+    {
+        let mut recorder =
+            neuromorphic_drivers::raw::Recorder::from_device(&path, &device, &configuration)
+                .unwrap();
+        let start = std::time::Instant::now();
+        let mut updated = false;
+        while start.elapsed() < std::time::Duration::from_millis(400) {
+            if !updated && start.elapsed() > std::time::Duration::from_millis(100) {
+                device
+                    .update_configuration(updated_configuration.clone())
+                    .unwrap();
+                updated = true;
+            }
+            if let Some(buffer_view) =
+                device.next_with_timeout(&std::time::Duration::from_millis(100))
+            {
+                recorder.write(&buffer_view).unwrap();
+            }
+            flag.load_error().unwrap();
+        }
+    }
+    let mut reader = neuromorphic_drivers::raw::Reader::open(&path).unwrap();
+    let (events, configuration_changes) = read_all_with_configuration_changes(&mut reader);
+    assert_eq!(configuration_changes.len(), 1);
+    assert_eq!(
+        json(&configuration_changes[0].configuration),
+        json(&updated_configuration)
+    );
+    let first_t = events.first().unwrap().0;
+    let last_t = events.last().unwrap().0;
+    assert!(first_t < configuration_changes[0].t && configuration_changes[0].t < last_t);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn push_applied_configurations() {
+    let configuration = synthetic_configuration(1000000);
+    let (device, flag) = open_mock(&configuration);
+    let path =
+        std::env::temp_dir().join("neuromorphic_drivers_raw_push_applied_configurations.raw");
+    let updated_configuration = synthetic_configuration(200000);
+    let mut expected_t = None;
+    {
+        let mut recorder = neuromorphic_drivers::raw::Recorder::create(
+            &path,
+            &neuromorphic_drivers::raw::Header::from_device(&device, &configuration).unwrap(),
+        )
+        .unwrap();
+        while recorder.current_t() == 0 {
+            if let Some(buffer_view) =
+                device.next_with_timeout(&std::time::Duration::from_millis(100))
+            {
+                recorder.write(&buffer_view).unwrap();
+            }
+            flag.load_error().unwrap();
+        }
+        let instant = std::time::Instant::now();
+        recorder.push_applied_configurations(vec![neuromorphic_drivers::configuration::Applied {
+            instant,
+            configuration: updated_configuration.clone(),
+        }]);
+        let start = std::time::Instant::now();
+        while start.elapsed() < std::time::Duration::from_millis(200) {
+            if let Some(buffer_view) =
+                device.next_with_timeout(&std::time::Duration::from_millis(100))
+            {
+                // the configuration is recorded before the first view received after its instant
+                if expected_t.is_none() && buffer_view.instant >= instant {
+                    expected_t = Some(recorder.current_t());
+                }
+                recorder.write(&buffer_view).unwrap();
+            }
+            flag.load_error().unwrap();
+        }
+    }
+    let mut reader = neuromorphic_drivers::raw::Reader::open(&path).unwrap();
+    let (_, configuration_changes) = read_all_with_configuration_changes(&mut reader);
+    assert_eq!(
+        configuration_changes
+            .iter()
+            .map(|change| (change.t, json(&change.configuration)))
+            .collect::<Vec<_>>(),
+        vec![(expected_t.unwrap(), json(&updated_configuration))]
+    );
+    std::fs::remove_file(path).unwrap();
+}
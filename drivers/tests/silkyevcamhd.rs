@@ -0,0 +1,51 @@
+#[test]
+fn validate_event_rate_control() {
+    let mut configuration =
+        neuromorphic_drivers::devices::centuryarks_silkyevcamhd::DEFAULT_CONFIGURATION;
+    assert!(configuration.validate().is_ok());
+    configuration.event_rate_control = Some(
+        neuromorphic_drivers::devices::centuryarks_silkyevcamhd::EventRateControl {
+            mode: neuromorphic_drivers::devices::centuryarks_silkyevcamhd::EventRateControlMode::Temporal,
+            reference_period_us: 200,
+            target_event_rate_kev_s: 1000,
+        },
+    );
+    assert!(configuration.validate().is_ok());
+    for (reference_period_us, target_event_rate_kev_s) in
+        [(0, 1000), (201, 1000), (200, 0), (200, 1 << 25)]
+    {
+        configuration.event_rate_control = Some(
+            neuromorphic_drivers::devices::centuryarks_silkyevcamhd::EventRateControl {
+                mode: neuromorphic_drivers::devices::centuryarks_silkyevcamhd::EventRateControlMode::Temporal,
+                reference_period_us,
+                target_event_rate_kev_s,
+            },
+        );
+        assert!(matches!(
+            configuration.validate(),
+            Err(
+                neuromorphic_drivers::devices::centuryarks_silkyevcamhd::Error::EventRateControl { .. }
+            )
+        ));
+    }
+}
+
+#[test]
+fn validate_pixel_mask() {
+    let mut configuration =
+        neuromorphic_drivers::devices::centuryarks_silkyevcamhd::DEFAULT_CONFIGURATION;
+    // the last pixel of the sensor
+    configuration.pixel_mask[0] = 1280 * 720;
+    assert!(configuration.validate().is_ok());
+    // one past the last pixel
+    configuration.pixel_mask[0] = 1280 * 720 + 1;
+    assert!(matches!(
+        configuration.validate(),
+        Err(
+            neuromorphic_drivers::devices::centuryarks_silkyevcamhd::Error::PixelMask {
+                code: 921601,
+                offset: 0
+            }
+        )
+    ));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "async")]
+
+struct ThreadWaker(std::thread::Thread);
+
+impl std::task::Wake for ThreadWaker {
+    fn wake(self: std::sync::Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn next<S: futures_core::Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+    let waker = std::task::Waker::from(std::sync::Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = std::task::Context::from_waker(&waker);
+    loop {
+        match std::pin::Pin::new(&mut *stream).poll_next(&mut context) {
+            std::task::Poll::Ready(item) => return item,
+            std::task::Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn stream() -> Result<(), neuromorphic_drivers::Error> {
+    std::env::set_var(
+        neuromorphic_drivers::devices::mock::SERIALS_VARIABLE,
+        "MOCK1",
+    );
+    let (flag, event_loop) = neuromorphic_drivers::flag_and_event_loop()?;
+    let device = neuromorphic_drivers::open(
+        Some("MOCK1"),
+        Some(neuromorphic_drivers::Configuration::Mock(
+            neuromorphic_drivers::devices::mock::Configuration {
+                source: neuromorphic_drivers::devices::mock::Source::Synthetic {
+                    events_per_second: 100000,
+                    trigger_period_us: Some(10000),
+                },
+                packet_duration_us: 1000,
+                enable_output: true,
+            },
+        )),
+        None,
+        event_loop,
+        flag.clone(),
+    )?;
+    let mut adapter = device.adapter();
+    let mut dvs_events = 0;
+    let mut stream = device.stream(flag.clone());
+    for _ in 0..100 {
+        let buffer = next(&mut stream).expect("the stream is infinite");
+        adapter.convert(&buffer.bytes, |_| dvs_events += 1, |_| {});
+        flag.load_error()?;
+    }
+    assert!(dvs_events > 0);
+    Ok(())
+}
+
+#[test]
+fn stream_ends_on_error() -> Result<(), neuromorphic_drivers::Error> {
+    std::env::set_var(
+        neuromorphic_drivers::devices::mock::SERIALS_VARIABLE,
+        "MOCK1",
+    );
+    let (flag, event_loop) = neuromorphic_drivers::flag_and_event_loop()?;
+    let configuration = neuromorphic_drivers::devices::mock::Configuration {
+        source: neuromorphic_drivers::devices::mock::Source::Synthetic {
+            events_per_second: 100000,
+            trigger_period_us: None,
+        },
+        packet_duration_us: 10000,
+        enable_output: true,
+    };
+    let device = neuromorphic_drivers::open(
+        Some("MOCK1"),
+        Some(neuromorphic_drivers::Configuration::Mock(
+            configuration.clone(),
+        )),
+        None,
+        event_loop,
+        flag.clone(),
+    )?;
+    // the file exists when the configuration is validated, but not when the generator reads it
+    let path = std::env::temp_dir().join("neuromorphic_drivers_stream_removed.raw");
+    std::fs::write(&path, [0u8; 16]).expect("the temporary file is writable");
+    device.update_configuration(neuromorphic_drivers::Configuration::Mock(
+        neuromorphic_drivers::devices::mock::Configuration {
+            source: neuromorphic_drivers::devices::mock::Source::File {
+                path: path.to_string_lossy().into_owned(),
+                repeat: true,
+            },
+            ..configuration
+        },
+    ))?;
+    std::fs::remove_file(&path).expect("the temporary file is removable");
+    let mut stream = device.stream(flag.clone());
+    while next(&mut stream).is_some() {}
+    assert!(flag.load_error().is_err());
+    Ok(())
+}
@@ -0,0 +1,90 @@
+#[test]
+fn set_bias() {
+    let mut configuration = neuromorphic_drivers::Configuration::PropheseeEvk4(
+        neuromorphic_drivers::prophesee_evk4::DEFAULT_CONFIGURATION,
+    );
+    neuromorphic_drivers::tuning::set_bias(
+        &mut configuration,
+        neuromorphic_drivers::tuning::Bias::DiffOn,
+        0x70,
+    )
+    .unwrap();
+    assert_eq!(
+        neuromorphic_drivers::tuning::bias(
+            &configuration,
+            neuromorphic_drivers::tuning::Bias::DiffOn
+        )
+        .unwrap(),
+        0x70
+    );
+    match configuration {
+        neuromorphic_drivers::Configuration::PropheseeEvk4(configuration) => {
+            assert_eq!(configuration.biases.diff_on, 0x70);
+            assert_eq!(
+                configuration.biases.diff_off,
+                neuromorphic_drivers::prophesee_evk4::DEFAULT_CONFIGURATION
+                    .biases
+                    .diff_off
+            );
+        }
+        _ => panic!("unexpected configuration type"),
+    }
+    assert!(neuromorphic_drivers::tuning::bias(
+        &neuromorphic_drivers::Configuration::PropheseeEvk3Hd(
+            neuromorphic_drivers::prophesee_evk3_hd::DEFAULT_CONFIGURATION
+        ),
+        neuromorphic_drivers::tuning::Bias::Inv
+    )
+    .is_err());
+}
+
+#[test]
+fn measure() -> Result<(), neuromorphic_drivers::tuning::Error> {
+    std::env::set_var(
+        neuromorphic_drivers::devices::mock::SERIALS_VARIABLE,
+        "MOCK0",
+    );
+    let (flag, event_loop) =
+        neuromorphic_drivers::flag_and_event_loop().map_err(neuromorphic_drivers::Error::from)?;
+    let configuration = neuromorphic_drivers::Configuration::Mock(
+        neuromorphic_drivers::devices::mock::Configuration {
+            source: neuromorphic_drivers::devices::mock::Source::Synthetic {
+                events_per_second: 100000,
+                trigger_period_us: None,
+            },
+            packet_duration_us: 1000,
+            enable_output: true,
+        },
+    );
+    let device = neuromorphic_drivers::open(
+        Some("MOCK0"),
+        Some(configuration.clone()),
+        None,
+        event_loop,
+        flag.clone(),
+    )?;
+    let mut tuner = neuromorphic_drivers::tuning::Tuner::new(
+        &device,
+        &flag,
+        configuration.clone(),
+        neuromorphic_drivers::tuning::Parameters {
+            settle: std::time::Duration::from_millis(50),
+            duration: std::time::Duration::from_millis(300),
+            hot_pixel_rate: 100.0,
+        },
+    );
+    let measurement = tuner.measure()?;
+    assert!(measurement.duration_us > 0);
+    assert!(measurement.event_rate() > 50000.0 && measurement.event_rate() < 150000.0);
+    assert!(measurement.on_events > 0 && measurement.off_events > 0);
+    assert_eq!(measurement.hot_pixels, 0);
+    assert!(tuner
+        .sweep(&[(neuromorphic_drivers::tuning::Bias::DiffOn, vec![0x60, 0x70])])
+        .is_err());
+    // sweep restores the configuration on errors too
+    assert_eq!(
+        serde_json::to_string(tuner.configuration()).unwrap(),
+        serde_json::to_string(&configuration).unwrap()
+    );
+    Ok(())
+}
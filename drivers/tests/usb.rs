@@ -0,0 +1,126 @@
+fn virtual_ring() -> (
+    neuromorphic_drivers::usb::VirtualRing,
+    neuromorphic_drivers::usb::VirtualRingWriter,
+) {
+    neuromorphic_drivers::usb::VirtualRing::new(
+        &neuromorphic_drivers::usb::Configuration {
+            buffer_length: 16,
+            ring_length: 8,
+            transfer_queue_length: 1,
+            allow_dma: false,
+        },
+        |_| {},
+    )
+    .unwrap()
+}
+
+#[test]
+fn leases() {
+    let (ring, writer) = virtual_ring();
+    ring.set_maximum_leases(3);
+    for index in 0..7u8 {
+        assert_eq!(writer.write(&[index]), 1);
+    }
+    // the ring is full, the buffer at index read is protected
+    assert_eq!(writer.write(&[7]), 0);
+    let first = ring.next_with_timeout(&std::time::Duration::ZERO).unwrap();
+    let second = ring.next_with_timeout(&std::time::Duration::ZERO).unwrap();
+    let third = ring.next_with_timeout(&std::time::Duration::ZERO).unwrap();
+    assert_eq!(
+        (first.slice, second.slice, third.slice),
+        (&[0u8][..], &[1u8][..], &[2u8][..])
+    );
+    // the budget is exhausted
+    assert!(ring
+        .next_with_timeout(&std::time::Duration::from_millis(10))
+        .is_none());
+
+    // the buffer released by the first lease (index 7) is writable again
+    assert_eq!(writer.write(&[7]), 1);
+    assert_eq!(writer.write(&[8]), 0);
+
+    // releasing a buffer out of order does not free its slot for the writer
+    drop(second);
+    assert_eq!(writer.write(&[8]), 0);
+    let fourth = ring.next_with_timeout(&std::time::Duration::ZERO).unwrap();
+    assert_eq!(fourth.slice, &[3u8]);
+    assert_eq!(writer.write(&[8]), 0);
+
+    // releasing the oldest buffer frees the slots up to the next leased buffer
+    drop(first);
+    assert_eq!(writer.write(&[8]), 1);
+    assert_eq!(writer.write(&[9]), 1);
+    assert_eq!(writer.write(&[10]), 0);
+    drop(third);
+    drop(fourth);
+    assert_eq!(writer.write(&[10]), 1);
+    assert_eq!(writer.write(&[11]), 0);
+    for expected in 4..11u8 {
+        let buffer_view = ring.next_with_timeout(&std::time::Duration::ZERO).unwrap();
+        assert_eq!(buffer_view.slice, &[expected]);
+    }
+    assert!(ring.next_with_timeout(&std::time::Duration::ZERO).is_none());
+}
+
+#[test]
+fn skip_empty_buffers() {
+    let (ring, writer) = virtual_ring();
+    for _ in 0..7 {
+        assert_eq!(writer.write(&[]), 0);
+    }
+    assert_eq!(writer.write(&[0]), 0);
+    // the reader skips the empty buffers without leasing them, the writer may reuse their slots
+    assert!(ring.next_with_timeout(&std::time::Duration::ZERO).is_none());
+    for index in 0..7u8 {
+        assert_eq!(writer.write(&[index]), 1);
+    }
+    assert_eq!(writer.write(&[7]), 0);
+    for expected in 0..7u8 {
+        let buffer_view = ring.next_with_timeout(&std::time::Duration::ZERO).unwrap();
+        assert_eq!(buffer_view.slice, &[expected]);
+    }
+}
+
+#[test]
+fn leases_threads() {
+    let (ring, writer) = virtual_ring();
+    ring.set_maximum_leases(4);
+    let writer_thread = std::thread::spawn(move || {
+        let mut index = 0u8;
+        while index < 200 {
+            if writer.write(&[index]) == 1 {
+                index += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+    let received = std::sync::Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..200 {
+            let buffer_view = ring
+                .next_with_timeout(&std::time::Duration::from_secs(10))
+                .unwrap();
+            let received = &received;
+            scope.spawn(move || {
+                let buffer_view = buffer_view;
+                std::thread::sleep(std::time::Duration::from_micros(100));
+                received.lock().unwrap().push(buffer_view.slice[0]);
+            });
+        }
+    });
+    writer_thread.join().unwrap();
+    let mut received = received.into_inner().unwrap();
+    received.sort();
+    assert_eq!(received, (0..200).collect::<Vec<u8>>());
+}
+
+#[test]
+#[should_panic]
+fn lease_twice() {
+    let (ring, writer) = virtual_ring();
+    writer.write(&[0]);
+    writer.write(&[1]);
+    let _first = ring.next_with_timeout(&std::time::Duration::ZERO);
+    let _second = ring.next_with_timeout(&std::time::Duration::ZERO);
+}
@@ -118,6 +118,17 @@ fn camel_case_to_screaming_case(string: &str) -> String {
     result
 }
 
+/// is_unit_enum returns true if the enum can be represented by a Python enum.Enum.
+///
+/// Other enums are represented by a base class and one dataclass per variant (see VARIANTS in serde/binary.py).
+fn is_unit_enum(
+    id_to_field: &std::collections::BTreeMap<u32, reflect::Named<reflect::VariantFormat>>,
+) -> bool {
+    id_to_field
+        .values()
+        .all(|field| matches!(field.value, reflect::VariantFormat::Unit))
+}
+
 enum NodeClass {
     Dataclass {
         children: std::collections::HashSet<String>,
@@ -203,7 +214,7 @@ fn generate_dataclasses<Writer: std::io::Write, Structure>(
             for field in fields.iter() {
                 if !parameters.skip_fields.contains(&field.name) {
                     match &field.value {
-                        reflect::Format::Option(format) => {
+                        reflect::Format::Option(format) | reflect::Format::Seq(format) => {
                             if let reflect::Format::TypeName(name) = format.as_ref() {
                                 children.insert(name.to_owned());
                             }
@@ -321,11 +332,29 @@ fn generate_dataclasses<Writer: std::io::Write, Structure>(
                                                     NodeClass::Dataclass { children: _, fields: _ } => {
                                                         format!(" = dataclasses.field(default_factory={name})")
                                                     },
-                                                    NodeClass::Enum { id_to_field } => {
-                                                        format!(" = {}.{}", name, camel_case_to_screaming_case(&id_to_field.get(&0).unwrap().name))
+                                                    // structures that are only reachable through options have no samples
+                                                    NodeClass::Enum { id_to_field } => match values.map(|values| &values[index]) {
+                                                        Some(reflect::Value::Variant(id, value)) => {
+                                                            let variant = &id_to_field.get(id).unwrap().name;
+                                                            if is_unit_enum(id_to_field) {
+                                                                format!(" = {}.{}", name, camel_case_to_screaming_case(variant))
+                                                            } else {
+                                                                format!(
+                                                                    " = dataclasses.field(default_factory=lambda: {}{}{})",
+                                                                    name,
+                                                                    variant,
+                                                                    value_to_string(value),
+                                                                )
+                                                            }
+                                                        }
+                                                        _ => "".to_owned(),
                                                     },
                                                 }
                                             },
+                                            reflect::Format::Seq(_) => match values {
+                                                Some(_) => " = dataclasses.field(default_factory=list)".to_owned(),
+                                                None => "".to_owned(),
+                                            },
                                             _ => match values {
                                                 Some(values) => format!(" = {}", value_to_string(&values[index])),
                                                 None => "".to_owned(),
@@ -336,6 +365,83 @@ fn generate_dataclasses<Writer: std::io::Write, Structure>(
                                 }
                             }
                         }
+                        NodeClass::Enum { id_to_field } if !is_unit_enum(id_to_field) => {
+                            writeln!(
+                                writer,
+                                concat!(
+                                    "\n",
+                                    "\n",
+                                    "class {}:\n",
+                                    "    VARIANTS: typing.Sequence[typing.Type[{}]] = []",
+                                ),
+                                name, name,
+                            )
+                            .unwrap();
+                            if parameters.serializable {
+                                writeln!(
+                                    writer,
+                                    concat!(
+                                        "\n",
+                                        "    def serialize(self) -> bytes:\n",
+                                        "        return serde.bincode.serialize(self, {})",
+                                    ),
+                                    name
+                                )
+                                .unwrap();
+                            }
+                            for (id, field) in id_to_field {
+                                let fields = match &field.value {
+                                    reflect::VariantFormat::Unit => Vec::new(),
+                                    reflect::VariantFormat::Struct(fields) => fields.clone(),
+                                    _ => panic!(
+                                        "unsupported non-struct field {} in {}",
+                                        field.name, name
+                                    ),
+                                };
+                                writeln!(
+                                    writer,
+                                    concat!(
+                                        "\n",
+                                        "\n",
+                                        "@dataclasses.dataclass{}\n",
+                                        "class {}{}({}):\n",
+                                        "    INDEX = {}",
+                                    ),
+                                    if parameters.frozen {
+                                        "(frozen=True)"
+                                    } else {
+                                        ""
+                                    },
+                                    name,
+                                    field.name,
+                                    name,
+                                    id,
+                                )
+                                .unwrap();
+                                for field in fields.iter() {
+                                    writeln!(
+                                        writer,
+                                        "    {}: {}",
+                                        field.name,
+                                        quote_type(&field.value, &parameters.name_to_new_name),
+                                    )
+                                    .unwrap();
+                                }
+                            }
+                            writeln!(
+                                writer,
+                                "\n\n{}.VARIANTS = [{}]",
+                                name,
+                                id_to_field
+                                    .values()
+                                    .map(|field| format!("{}{}", name, field.name))
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                            )
+                            .unwrap();
+                            generated_nodes.insert(node.name.clone());
+                            continue;
+                        }
                         NodeClass::Enum { id_to_field } => {
                             writeln!(writer, concat!("\n", "\n", "class {}(enum.Enum):",), name,)
                                 .unwrap();
@@ -564,7 +670,9 @@ macro_rules! generate {
                                 writer,
                                 concat!(
                                     "\n",
-                                    "    def illuminance(self) -> int: ...",
+                                    "    def illuminance(self) -> int: ...\n",
+                                    "\n",
+                                    "    def area_counts(self) -> list[int]: ...",
                                 )
                             ).unwrap();
                         }
@@ -762,6 +870,7 @@ macro_rules! generate {
                                 "    serial: typing.Optional[str] = None,\n",
                                 "    usb_configuration: typing.Optional[UsbConfiguration] = None,\n",
                                 "    iterator_maximum_raw_packets: int = 64,\n",
+                                "    arrow: typing.Literal[False] = False,\n",
                                 ") -> {}.{}{}:\n",
                                 "    ...",
                             ),
@@ -797,6 +906,7 @@ macro_rules! generate {
                             "    serial: typing.Optional[str] = None,\n",
                             "    usb_configuration: typing.Optional[UsbConfiguration] = None,\n",
                             "    iterator_maximum_raw_packets: int = 64,\n",
+                            "    arrow: typing.Literal[False] = False,\n",
                             ") -> {}{}:\n",
                             "    ...",
                         ),
@@ -808,6 +918,25 @@ macro_rules! generate {
                     ).unwrap();
                 }
             }
+            writeln!(
+                writer,
+                concat!(
+                    "\n",
+                    "\n",
+                    "@typing.overload\n",
+                    "def open(\n",
+                    "    configuration: typing.Optional[Configuration] = None,\n",
+                    "    iterator_timeout: typing.Optional[float] = None,\n",
+                    "    raw: typing.Literal[False] = False,\n",
+                    "    serial: typing.Optional[str] = None,\n",
+                    "    usb_configuration: typing.Optional[UsbConfiguration] = None,\n",
+                    "    iterator_maximum_raw_packets: int = 64,\n",
+                    "    *,\n",
+                    "    arrow: typing.Literal[True],\n",
+                    ") -> typing.Any:\n",
+                    "    ...",
+                ),
+            ).unwrap();
             writeln!(
                 writer,
                 concat!(
@@ -820,6 +949,7 @@ macro_rules! generate {
                     "    serial: typing.Optional[str] = None,\n",
                     "    usb_configuration: typing.Optional[UsbConfiguration] = None,\n",
                     "    iterator_maximum_raw_packets: int = 64,\n",
+                    "    arrow: bool = False,\n",
                     ") -> typing.Any:\n",
                     "    return device.Device.__new__(\n",
                     "        device.Device,\n",
@@ -830,6 +960,7 @@ macro_rules! generate {
                     "        serial,\n",
                     "        None if usb_configuration is None else usb_configuration.serialize(),\n",
                     "        iterator_timeout,\n",
+                    "        arrow,\n",
                     "    )",
                 ),
             ).unwrap();
@@ -897,5 +1028,5 @@ fn main() {
             println!("cargo:rustc-link-search={}", path);
         }
     }
-    generate!(prophesee_evk3_hd, prophesee_evk4);
+    generate!(prophesee_evk3_hd, prophesee_evk4, mock);
 }
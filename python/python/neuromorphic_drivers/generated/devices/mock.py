@@ -0,0 +1,211 @@
+from __future__ import annotations
+
+import dataclasses
+import enum
+import types
+import typing
+
+import numpy
+
+from ... import serde
+from ... import status
+from .. import enums
+
+
+class Source:
+    VARIANTS: typing.Sequence[typing.Type[Source]] = []
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, Source)
+
+
+@dataclasses.dataclass
+class SourceSynthetic(Source):
+    INDEX = 0
+    events_per_second: serde.type.uint32
+    trigger_period_us: typing.Optional[serde.type.uint32]
+
+
+@dataclasses.dataclass
+class SourceFile(Source):
+    INDEX = 1
+    path: str
+    repeat: bool
+
+
+Source.VARIANTS = [SourceSynthetic, SourceFile]
+
+
+@dataclasses.dataclass
+class Configuration:
+    source: Source = dataclasses.field(default_factory=lambda: SourceSynthetic(1000000, None))
+    packet_duration_us: serde.type.uint32 = 1000
+    enable_output: bool = True
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, Configuration)
+
+    @staticmethod
+    def type() -> str:
+        return "mock"
+
+
+@dataclasses.dataclass
+class UsbConfiguration:
+    buffer_length: serde.type.uint64 = 131072
+    ring_length: serde.type.uint64 = 4096
+    transfer_queue_length: serde.type.uint64 = 32
+    allow_dma: bool = False
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, UsbConfiguration)
+
+
+@dataclasses.dataclass(frozen=True)
+class Properties:
+    width: serde.type.uint16 = 1280
+    height: serde.type.uint16 = 720
+
+
+class Device(typing.Protocol):
+    def __enter__(self) -> "Device": ...
+
+    def __exit__(
+        self,
+        exception_type: typing.Optional[typing.Type[BaseException]],
+        value: typing.Optional[BaseException],
+        traceback: typing.Optional[types.TracebackType],
+    ) -> bool:
+        ...
+
+    def __iter__(self) -> "Device": ...
+
+    def __next__(self) -> tuple[status.StatusNonOptional, dict[str, numpy.ndarray[typing.Any, numpy.dtype[numpy.void]]]]: ...
+
+    def backlog(self) -> int: ...
+
+    def clear_backlog(self, until: int): ...
+
+    def overflow(self) -> bool: ...
+
+    def name(self) -> typing.Literal[enums.Name.MOCK]: ...
+
+    def properties(self) -> Properties: ...
+
+    def serial(self) -> str: ...
+
+    def chip_firmware_configuration(self) -> Configuration: ...
+
+    def speed(self) -> enums.Speed: ...
+
+    def temperature_celsius(self) -> float: ...
+
+    def update_configuration(self, configuration: Configuration): ...
+
+
+class DeviceOptional(typing.Protocol):
+    def __enter__(self) -> "DeviceOptional": ...
+
+    def __exit__(
+        self,
+        exception_type: typing.Optional[typing.Type[BaseException]],
+        value: typing.Optional[BaseException],
+        traceback: typing.Optional[types.TracebackType],
+    ) -> bool:
+        ...
+
+    def __iter__(self) -> "DeviceOptional": ...
+
+    def __next__(self) -> tuple[status.Status, typing.Optional[dict[str, numpy.ndarray[typing.Any, numpy.dtype[numpy.void]]]]]: ...
+
+    def backlog(self) -> int: ...
+
+    def clear_backlog(self, until: int): ...
+
+    def overflow(self) -> bool: ...
+
+    def name(self) -> typing.Literal[enums.Name.MOCK]: ...
+
+    def properties(self) -> Properties: ...
+
+    def serial(self) -> str: ...
+
+    def chip_firmware_configuration(self) -> Configuration: ...
+
+    def speed(self) -> enums.Speed: ...
+
+    def temperature_celsius(self) -> float: ...
+
+    def update_configuration(self, configuration: Configuration): ...
+
+
+class DeviceRaw(typing.Protocol):
+    def __enter__(self) -> "DeviceRaw": ...
+
+    def __exit__(
+        self,
+        exception_type: typing.Optional[typing.Type[BaseException]],
+        value: typing.Optional[BaseException],
+        traceback: typing.Optional[types.TracebackType],
+    ) -> bool:
+        ...
+
+    def __iter__(self) -> "DeviceRaw": ...
+
+    def __next__(self) -> tuple[status.RawStatusNonOptional, bytes]: ...
+
+    def backlog(self) -> int: ...
+
+    def clear_backlog(self, until: int): ...
+
+    def overflow(self) -> bool: ...
+
+    def name(self) -> typing.Literal[enums.Name.MOCK]: ...
+
+    def properties(self) -> Properties: ...
+
+    def serial(self) -> str: ...
+
+    def chip_firmware_configuration(self) -> Configuration: ...
+
+    def speed(self) -> enums.Speed: ...
+
+    def temperature_celsius(self) -> float: ...
+
+    def update_configuration(self, configuration: Configuration): ...
+
+
+class DeviceRawOptional(typing.Protocol):
+    def __enter__(self) -> "DeviceRawOptional": ...
+
+    def __exit__(
+        self,
+        exception_type: typing.Optional[typing.Type[BaseException]],
+        value: typing.Optional[BaseException],
+        traceback: typing.Optional[types.TracebackType],
+    ) -> bool:
+        ...
+
+    def __iter__(self) -> "DeviceRawOptional": ...
+
+    def __next__(self) -> tuple[status.RawStatus, typing.Optional[bytes]]: ...
+
+    def backlog(self) -> int: ...
+
+    def clear_backlog(self, until: int): ...
+
+    def overflow(self) -> bool: ...
+
+    def name(self) -> typing.Literal[enums.Name.MOCK]: ...
+
+    def properties(self) -> Properties: ...
+
+    def serial(self) -> str: ...
+
+    def chip_firmware_configuration(self) -> Configuration: ...
+
+    def speed(self) -> enums.Speed: ...
+
+    def temperature_celsius(self) -> float: ...
+
+    def update_configuration(self, configuration: Configuration): ...
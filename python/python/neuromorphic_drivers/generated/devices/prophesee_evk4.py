@@ -12,6 +12,35 @@ from ... import status
 from .. import enums
 
 
+class AntiFlickerMode(enum.Enum):
+    BAND_STOP = 0
+    BAND_PASS = 1
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, AntiFlickerMode)
+
+
+@dataclasses.dataclass
+class AreaCounters:
+    x: tuple[
+        serde.type.uint16,
+        serde.type.uint16,
+        serde.type.uint16,
+        serde.type.uint16,
+        serde.type.uint16,
+    ]
+    y: tuple[
+        serde.type.uint16,
+        serde.type.uint16,
+        serde.type.uint16,
+        serde.type.uint16,
+        serde.type.uint16,
+    ]
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, AreaCounters)
+
+
 @dataclasses.dataclass
 class Biases:
     pr: serde.type.uint8 = 0x7C
@@ -32,6 +61,15 @@ class Biases:
         return serde.bincode.serialize(self, Biases)
 
 
+class BurstFilterMode(enum.Enum):
+    TRAIL = 0
+    STC_CUT_TRAIL = 1
+    STC_KEEP_TRAIL = 2
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, BurstFilterMode)
+
+
 class Clock(enum.Enum):
     INTERNAL = 0
     INTERNAL_WITH_OUTPUT_ENABLED = 1
@@ -41,6 +79,24 @@ class Clock(enum.Enum):
         return serde.bincode.serialize(self, Clock)
 
 
+class EventRateControlMode(enum.Enum):
+    TEMPORAL = 0
+    HORIZONTAL = 1
+    VERTICAL = 2
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, EventRateControlMode)
+
+
+@dataclasses.dataclass
+class Pixel:
+    x: serde.type.uint16
+    y: serde.type.uint16
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, Pixel)
+
+
 @dataclasses.dataclass
 class RateLimiter:
     reference_period_us: serde.type.uint16
@@ -50,6 +106,64 @@ class RateLimiter:
         return serde.bincode.serialize(self, RateLimiter)
 
 
+class RoiMode(enum.Enum):
+    INCLUDE = 0
+    EXCLUDE = 1
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, RoiMode)
+
+
+@dataclasses.dataclass
+class RoiWindow:
+    x: serde.type.uint16
+    y: serde.type.uint16
+    width: serde.type.uint16
+    height: serde.type.uint16
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, RoiWindow)
+
+
+@dataclasses.dataclass
+class AntiFlicker:
+    low_frequency: serde.type.uint16
+    high_frequency: serde.type.uint16
+    duty_cycle: serde.type.uint8
+    mode: AntiFlickerMode
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, AntiFlicker)
+
+
+@dataclasses.dataclass
+class BurstFilter:
+    mode: BurstFilterMode
+    threshold_us: serde.type.uint32
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, BurstFilter)
+
+
+@dataclasses.dataclass
+class EventRateControl:
+    mode: EventRateControlMode
+    reference_period_us: serde.type.uint16
+    target_event_rate_kev_s: serde.type.uint32
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, EventRateControl)
+
+
+@dataclasses.dataclass
+class Roi:
+    mode: RoiMode
+    windows: list[RoiWindow]
+
+    def serialize(self) -> bytes:
+        return serde.bincode.serialize(self, Roi)
+
+
 @dataclasses.dataclass
 class Configuration:
     biases: Biases = dataclasses.field(default_factory=Biases)
@@ -112,10 +226,17 @@ class Configuration:
         serde.type.uint64,
         serde.type.uint64,
     ] = (0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0)
+    masked_pixels: list[Pixel] = dataclasses.field(default_factory=list)
     mask_intersection_only: bool = False
+    roi: typing.Optional[Roi] = None
+    digital_crop: typing.Optional[RoiWindow] = None
     enable_external_trigger: bool = True
     clock: Clock = Clock.INTERNAL
     rate_limiter: typing.Optional[RateLimiter] = None
+    event_rate_control: typing.Optional[EventRateControl] = None
+    burst_filter: typing.Optional[BurstFilter] = None
+    anti_flicker: typing.Optional[AntiFlicker] = None
+    area_counters: typing.Optional[AreaCounters] = None
     enable_output: bool = True
 
     def serialize(self) -> bytes:
@@ -180,6 +301,8 @@ class Device(typing.Protocol):
 
     def illuminance(self) -> int: ...
 
+    def area_counts(self) -> list[int]: ...
+
 
 class DeviceOptional(typing.Protocol):
     def __enter__(self) -> "DeviceOptional": ...
@@ -218,6 +341,8 @@ class DeviceOptional(typing.Protocol):
 
     def illuminance(self) -> int: ...
 
+    def area_counts(self) -> list[int]: ...
+
 
 class DeviceRaw(typing.Protocol):
     def __enter__(self) -> "DeviceRaw": ...
@@ -256,6 +381,8 @@ class DeviceRaw(typing.Protocol):
 
     def illuminance(self) -> int: ...
 
+    def area_counts(self) -> list[int]: ...
+
 
 class DeviceRawOptional(typing.Protocol):
     def __enter__(self) -> "DeviceRawOptional": ...
@@ -293,3 +420,5 @@ class DeviceRawOptional(typing.Protocol):
     def update_configuration(self, configuration: Configuration): ...
 
     def illuminance(self) -> int: ...
+
+    def area_counts(self) -> list[int]: ...
@@ -9,6 +9,7 @@ from .. import device
 from .. import status
 from .devices import prophesee_evk3_hd as prophesee_evk3_hd
 from .devices import prophesee_evk4 as prophesee_evk4
+from .devices import mock as mock
 from .enums import *
 from .unions import *
 
@@ -170,6 +171,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk3_hd.Device:
     ...
 
@@ -182,6 +184,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk3_hd.DeviceOptional:
     ...
 
@@ -194,6 +197,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk3_hd.DeviceRaw:
     ...
 
@@ -206,6 +210,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk3_hd.DeviceRawOptional:
     ...
 
@@ -218,6 +223,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk4.Device:
     ...
 
@@ -230,6 +236,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk4.DeviceOptional:
     ...
 
@@ -242,6 +249,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk4.DeviceRaw:
     ...
 
@@ -254,10 +262,63 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> prophesee_evk4.DeviceRawOptional:
     ...
 
 
+@typing.overload
+def open(
+    configuration: mock.Configuration,
+    iterator_timeout: typing.Literal[None] = None,
+    raw: typing.Literal[False] = False,
+    serial: typing.Optional[str] = None,
+    usb_configuration: typing.Optional[UsbConfiguration] = None,
+    iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
+) -> mock.Device:
+    ...
+
+
+@typing.overload
+def open(
+    configuration: mock.Configuration,
+    iterator_timeout: typing.Optional[float] = None,
+    raw: typing.Literal[False] = False,
+    serial: typing.Optional[str] = None,
+    usb_configuration: typing.Optional[UsbConfiguration] = None,
+    iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
+) -> mock.DeviceOptional:
+    ...
+
+
+@typing.overload
+def open(
+    configuration: mock.Configuration,
+    iterator_timeout: typing.Literal[None] = None,
+    raw: typing.Literal[True] = True,
+    serial: typing.Optional[str] = None,
+    usb_configuration: typing.Optional[UsbConfiguration] = None,
+    iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
+) -> mock.DeviceRaw:
+    ...
+
+
+@typing.overload
+def open(
+    configuration: mock.Configuration,
+    iterator_timeout: typing.Optional[float] = None,
+    raw: typing.Literal[True] = True,
+    serial: typing.Optional[str] = None,
+    usb_configuration: typing.Optional[UsbConfiguration] = None,
+    iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
+) -> mock.DeviceRawOptional:
+    ...
+
+
 @typing.overload
 def open(
     configuration: typing.Optional[Configuration] = None,
@@ -266,6 +327,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> GenericDevice:
     ...
 
@@ -278,6 +340,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> GenericDeviceOptional:
     ...
 
@@ -290,6 +353,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> GenericDeviceRaw:
     ...
 
@@ -302,10 +366,25 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: typing.Literal[False] = False,
 ) -> GenericDeviceRawOptional:
     ...
 
 
+@typing.overload
+def open(
+    configuration: typing.Optional[Configuration] = None,
+    iterator_timeout: typing.Optional[float] = None,
+    raw: typing.Literal[False] = False,
+    serial: typing.Optional[str] = None,
+    usb_configuration: typing.Optional[UsbConfiguration] = None,
+    iterator_maximum_raw_packets: int = 64,
+    *,
+    arrow: typing.Literal[True],
+) -> typing.Any:
+    ...
+
+
 def open(
     configuration: typing.Optional[Configuration] = None,
     iterator_timeout: typing.Optional[float] = None,
@@ -313,6 +392,7 @@ def open(
     serial: typing.Optional[str] = None,
     usb_configuration: typing.Optional[UsbConfiguration] = None,
     iterator_maximum_raw_packets: int = 64,
+    arrow: bool = False,
 ) -> typing.Any:
     return device.Device.__new__(
         device.Device,
@@ -323,4 +403,5 @@ def open(
         serial,
         None if usb_configuration is None else usb_configuration.serialize(),
         iterator_timeout,
+        arrow,
     )
@@ -13,3 +13,4 @@ class Speed(enum.Enum):
 class Name(enum.Enum):
     PROPHESEE_EVK3_HD = "Prophesee EVK3 HD"
     PROPHESEE_EVK4 = "Prophesee EVK4"
+    MOCK = "Mock"
@@ -4,21 +4,25 @@ from . import enums
 from .. import serde
 from .devices import prophesee_evk3_hd
 from .devices import prophesee_evk4
+from .devices import mock
 
 
 Properties = typing.Union[
     prophesee_evk3_hd.Properties,
     prophesee_evk4.Properties,
+    mock.Properties,
 ]
 
 Configuration = typing.Union[
     prophesee_evk3_hd.Configuration,
     prophesee_evk4.Configuration,
+    mock.Configuration,
 ]
 
 UsbConfiguration = typing.Union[
     prophesee_evk3_hd.UsbConfiguration,
     prophesee_evk4.UsbConfiguration,
+    mock.UsbConfiguration,
 ]
 
 
@@ -27,6 +31,8 @@ def name_to_properties(name: enums.Name) -> Properties:
         return prophesee_evk3_hd.Properties()
     if name == enums.Name.PROPHESEE_EVK4:
         return prophesee_evk4.Properties()
+    if name == enums.Name.MOCK:
+        return mock.Properties()
     raise Exception(f"unknown name {name}")
 
 
@@ -35,4 +41,6 @@ def deserialize_configuration(name: enums.Name, data: bytes) -> Configuration:
         return serde.bincode.deserialize(data, prophesee_evk3_hd.Configuration)[0]
     if name == enums.Name.PROPHESEE_EVK4:
         return serde.bincode.deserialize(data, prophesee_evk4.Configuration)[0]
+    if name == enums.Name.MOCK:
+        return serde.bincode.deserialize(data, mock.Configuration)[0]
     raise Exception(f"unknown name {name}")
@@ -12,6 +12,15 @@ import numpy
 from . import type
 
 
+def option_content_type(types: tuple[typing.Any, ...]) -> typing.Any:
+    # typing flattens Optional[Union[A, B]] into Union[A, B, None],
+    # hence Optional[uint32] (uint32 = Union[numpy.uint32, int]) has three arguments
+    assert len(types) >= 2 and types[-1] == builtins.type(None)
+    if len(types) == 2:
+        return types[0]
+    return typing.Union[types[:-1]]
+
+
 @dataclasses.dataclass
 class Serializer:
     """Serialization primitives for binary formats (abstract class).
@@ -129,7 +138,7 @@ class Serializer:
         elif hasattr(obj_type, "__origin__"):  # Generic type
             types = getattr(obj_type, "__args__")
 
-            if getattr(obj_type, "__origin__") == collections.abc.Sequence:  # Sequence
+            if getattr(obj_type, "__origin__") in (collections.abc.Sequence, list):  # Sequence
                 assert len(types) == 1
                 item_type = types[0]
                 self.serialize_len(len(obj))
@@ -142,12 +151,11 @@ class Serializer:
                         self.serialize_any(obj[i], types[i])
 
             elif getattr(obj_type, "__origin__") == typing.Union:  # Option
-                assert len(types) == 2 and types[1] == builtins.type(None)
                 if obj is None:
                     self.output.write(b"\x00")
                 else:
                     self.output.write(b"\x01")
-                    self.serialize_any(obj, types[0])
+                    self.serialize_any(obj, option_content_type(types))
 
             elif getattr(obj_type, "__origin__") == dict:  # Map
                 assert len(types) == 2
@@ -170,6 +178,11 @@ class Serializer:
             if not isinstance(obj, obj_type):
                 raise type.SerializationError("Wrong Value for the type", obj, obj_type)
 
+            # handle variant
+            if hasattr(obj_type, "VARIANTS"):
+                obj_type = builtins.type(obj)
+                self.serialize_variant_index(obj_type.INDEX)
+
             # Content of struct or variant
             fields = dataclasses.fields(obj_type)
             types = typing.get_type_hints(obj_type)
@@ -335,7 +348,7 @@ class Deserializer:
 
         elif hasattr(obj_type, "__origin__"):  # Generic type
             types = getattr(obj_type, "__args__")
-            if getattr(obj_type, "__origin__") == collections.abc.Sequence:  # Sequence
+            if getattr(obj_type, "__origin__") in (collections.abc.Sequence, list):  # Sequence
                 assert len(types) == 1
                 item_type = types[0]
                 length = self.deserialize_len()
@@ -356,12 +369,12 @@ class Deserializer:
                 return tuple(result)
 
             elif getattr(obj_type, "__origin__") == typing.Union:  # Option
-                assert len(types) == 2 and types[1] == builtins.type(None)
+                content_type = option_content_type(types)
                 tag = int.from_bytes(self.read(1), byteorder="little", signed=False)
                 if tag == 0:
                     return None
                 elif tag == 1:
-                    return self.deserialize_any(types[0])
+                    return self.deserialize_any(content_type)
                 else:
                     raise type.DeserializationError("Wrong tag for Option value")
 
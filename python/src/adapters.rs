@@ -1,163 +1,224 @@
 use neuromorphic_drivers::types::SliceView;
 use numpy::IntoPyArray;
 
+use crate::record_batch;
 use crate::structured_array;
 use pyo3::prelude::PyDictMethods;
 use pyo3::IntoPy;
 
-pub enum Adapter {
-    Evt3 {
-        inner: neuromorphic_drivers_rs::adapters::evt3::Adapter,
-        dvs_events: Vec<u8>,
-        trigger_events: Vec<u8>,
-        dvs_events_overflow_indices: Vec<usize>,
-        trigger_events_overflow_indices: Vec<usize>,
-    },
+pub struct Adapter {
+    inner: neuromorphic_drivers_rs::adapters::Adapter,
+    dvs_events: Vec<u8>,
+    trigger_events: Vec<u8>,
+    dvs_events_overflow_indices: Vec<usize>,
+    trigger_events_overflow_indices: Vec<usize>,
+    arrow: Option<Columns>,
+}
+
+/// Columns replaces the structured arrays when the device is opened with arrow=True.
+#[derive(Default)]
+pub struct Columns {
+    dvs_events: neuromorphic_drivers_rs::adapters::DvsColumns,
+    trigger_events: neuromorphic_drivers_rs::adapters::TriggerColumns,
 }
 
 impl Adapter {
     pub fn current_t(&self) -> u64 {
-        match self {
-            Adapter::Evt3 { inner, .. } => inner.current_t(),
-        }
+        self.inner.current_t()
     }
 
     pub fn consume(&mut self, slice: &[u8]) {
-        match self {
-            Adapter::Evt3 { inner, .. } => inner.consume(slice),
-        }
+        self.inner.consume(slice);
+    }
+
+    pub fn with_arrow(mut self) -> Self {
+        self.arrow = Some(Columns::default());
+        self
     }
 
     pub fn push(&mut self, first_after_overflow: bool, slice: &[u8]) {
-        match self {
-            Adapter::Evt3 {
-                inner,
+        let Adapter {
+            inner,
+            dvs_events,
+            trigger_events,
+            dvs_events_overflow_indices,
+            trigger_events_overflow_indices,
+            arrow,
+        } = self;
+        if let Some(columns) = arrow {
+            if first_after_overflow {
+                dvs_events_overflow_indices.push(columns.dvs_events.len());
+                trigger_events_overflow_indices.push(columns.trigger_events.len());
+            }
+            columns
+                .trigger_events
+                .reserve(inner.events_lengths(slice).trigger);
+            let Columns {
                 dvs_events,
                 trigger_events,
-                dvs_events_overflow_indices,
-                trigger_events_overflow_indices,
-            } => {
-                if first_after_overflow {
-                    dvs_events_overflow_indices
-                        .push(dvs_events.len() / structured_array::DVS_EVENTS_DTYPE.size());
-                    trigger_events_overflow_indices
-                        .push(dvs_events.len() / structured_array::TRIGGER_EVENTS_DTYPE.size());
-                }
-                let events_lengths = inner.events_lengths(slice);
-                dvs_events.reserve_exact(events_lengths.dvs);
-                trigger_events.reserve_exact(events_lengths.trigger);
-                inner.convert(
-                    slice,
-                    |dvs_event| {
-                        dvs_events.extend_from_slice(dvs_event.as_bytes());
-                    },
-                    |trigger_event| {
-                        trigger_events.extend_from_slice(trigger_event.as_bytes());
-                    },
-                );
-            }
+            } = columns;
+            inner.convert_into_columns(slice, dvs_events, |trigger_event| {
+                trigger_events.push(trigger_event)
+            });
+            return;
         }
+        if first_after_overflow {
+            dvs_events_overflow_indices
+                .push(dvs_events.len() / structured_array::DVS_EVENTS_DTYPE.size());
+            trigger_events_overflow_indices
+                .push(dvs_events.len() / structured_array::TRIGGER_EVENTS_DTYPE.size());
+        }
+        let events_lengths = inner.events_lengths(slice);
+        dvs_events.reserve_exact(events_lengths.dvs);
+        trigger_events.reserve_exact(events_lengths.trigger);
+        inner.convert(
+            slice,
+            |dvs_event| {
+                dvs_events.extend_from_slice(dvs_event.as_bytes());
+            },
+            |trigger_event| {
+                trigger_events.extend_from_slice(trigger_event.as_bytes());
+            },
+        );
     }
 
     pub fn take_into_dict(&mut self, python: pyo3::Python) -> pyo3::PyResult<pyo3::PyObject> {
-        match self {
-            Adapter::Evt3 {
-                inner: _,
+        let Adapter {
+            inner: _,
+            dvs_events,
+            trigger_events,
+            dvs_events_overflow_indices,
+            trigger_events_overflow_indices,
+            arrow,
+        } = self;
+        let dict = pyo3::types::PyDict::new_bound(python);
+        if let Some(columns) = arrow {
+            let Columns {
                 dvs_events,
                 trigger_events,
-                dvs_events_overflow_indices,
-                trigger_events_overflow_indices,
-            } => {
-                let dict = pyo3::types::PyDict::new_bound(python);
-                if !dvs_events.is_empty() {
-                    let dvs_events_array = {
-                        let mut taken_dvs_events = Vec::new();
-                        std::mem::swap(dvs_events, &mut taken_dvs_events);
-                        taken_dvs_events.into_pyarray_bound(python)
-                    };
-                    let description = structured_array::DVS_EVENTS_DTYPE.into_py(python);
-                    use numpy::prelude::PyUntypedArrayMethods;
-                    {
-                        let dvs_events_array_pointer = dvs_events_array.as_array_ptr();
-                        unsafe {
-                            *(*dvs_events_array_pointer).dimensions /=
-                                structured_array::DVS_EVENTS_DTYPE.size() as isize;
-                            *(*dvs_events_array_pointer).strides =
-                                structured_array::DVS_EVENTS_DTYPE.size() as isize;
-                            let previous_description = (*dvs_events_array_pointer).descr;
-                            (*dvs_events_array_pointer).descr = description;
-                            pyo3::ffi::Py_DECREF(previous_description as *mut pyo3::ffi::PyObject);
-                        }
-                    }
-                    dict.set_item("dvs_events", dvs_events_array)?;
-                    if !dvs_events_overflow_indices.is_empty() {
-                        let dvs_events_overflow_indices_array = {
-                            let mut taken_dvs_events_overflow_indices = Vec::new();
-                            std::mem::swap(
-                                dvs_events_overflow_indices,
-                                &mut taken_dvs_events_overflow_indices,
-                            );
-                            taken_dvs_events_overflow_indices.into_pyarray_bound(python)
-                        };
-                        dict.set_item(
-                            "dvs_events_overflow_indices",
-                            dvs_events_overflow_indices_array,
-                        )?;
-                    }
+            } = std::mem::take(columns);
+            let to_runtime_error =
+                |error| pyo3::exceptions::PyRuntimeError::new_err(format!("{error}"));
+            if !dvs_events.is_empty() {
+                dict.set_item(
+                    "dvs_events",
+                    record_batch::RecordBatch::from(
+                        dvs_events.into_record_batch().map_err(to_runtime_error)?,
+                    )
+                    .into_py(python),
+                )?;
+                if !dvs_events_overflow_indices.is_empty() {
+                    dict.set_item(
+                        "dvs_events_overflow_indices",
+                        std::mem::take(dvs_events_overflow_indices).into_pyarray_bound(python),
+                    )?;
                 }
-                if !trigger_events.is_empty() {
-                    let trigger_events_array = {
-                        let mut taken_trigger_events = Vec::new();
-                        std::mem::swap(trigger_events, &mut taken_trigger_events);
-                        taken_trigger_events.into_pyarray_bound(python)
-                    };
-                    let description = structured_array::TRIGGER_EVENTS_DTYPE.into_py(python);
-                    use numpy::prelude::PyUntypedArrayMethods;
-                    {
-                        let trigger_events_array_pointer = trigger_events_array.as_array_ptr();
-                        unsafe {
-                            *(*trigger_events_array_pointer).dimensions /=
-                                structured_array::TRIGGER_EVENTS_DTYPE.size() as isize;
-                            *(*trigger_events_array_pointer).strides =
-                                structured_array::TRIGGER_EVENTS_DTYPE.size() as isize;
-                            let previous_description = (*trigger_events_array_pointer).descr;
-                            (*trigger_events_array_pointer).descr = description;
-                            pyo3::ffi::Py_DECREF(previous_description as *mut pyo3::ffi::PyObject);
-                        }
-                    }
-
-                    dict.set_item("trigger_events", trigger_events_array)?;
-                    if !trigger_events_overflow_indices.is_empty() {
-                        let trigger_events_overflow_indices_array = {
-                            let mut taken_trigger_events_overflow_indices = Vec::new();
-                            std::mem::swap(
-                                trigger_events_overflow_indices,
-                                &mut taken_trigger_events_overflow_indices,
-                            );
-                            taken_trigger_events_overflow_indices.into_pyarray_bound(python)
-                        };
-                        dict.set_item(
-                            "trigger_events_overflow_indices",
-                            trigger_events_overflow_indices_array,
-                        )?;
-                    }
+            }
+            if !trigger_events.is_empty() {
+                dict.set_item(
+                    "trigger_events",
+                    record_batch::RecordBatch::from(
+                        trigger_events
+                            .into_record_batch()
+                            .map_err(to_runtime_error)?,
+                    )
+                    .into_py(python),
+                )?;
+                if !trigger_events_overflow_indices.is_empty() {
+                    dict.set_item(
+                        "trigger_events_overflow_indices",
+                        std::mem::take(trigger_events_overflow_indices).into_pyarray_bound(python),
+                    )?;
+                }
+            }
+            return Ok(dict.into());
+        }
+        if !dvs_events.is_empty() {
+            let dvs_events_array = {
+                let mut taken_dvs_events = Vec::new();
+                std::mem::swap(dvs_events, &mut taken_dvs_events);
+                taken_dvs_events.into_pyarray_bound(python)
+            };
+            let description = structured_array::DVS_EVENTS_DTYPE.into_py(python);
+            use numpy::prelude::PyUntypedArrayMethods;
+            {
+                let dvs_events_array_pointer = dvs_events_array.as_array_ptr();
+                unsafe {
+                    *(*dvs_events_array_pointer).dimensions /=
+                        structured_array::DVS_EVENTS_DTYPE.size() as isize;
+                    *(*dvs_events_array_pointer).strides =
+                        structured_array::DVS_EVENTS_DTYPE.size() as isize;
+                    let previous_description = (*dvs_events_array_pointer).descr;
+                    (*dvs_events_array_pointer).descr = description;
+                    pyo3::ffi::Py_DECREF(previous_description as *mut pyo3::ffi::PyObject);
+                }
+            }
+            dict.set_item("dvs_events", dvs_events_array)?;
+            if !dvs_events_overflow_indices.is_empty() {
+                let dvs_events_overflow_indices_array = {
+                    let mut taken_dvs_events_overflow_indices = Vec::new();
+                    std::mem::swap(
+                        dvs_events_overflow_indices,
+                        &mut taken_dvs_events_overflow_indices,
+                    );
+                    taken_dvs_events_overflow_indices.into_pyarray_bound(python)
+                };
+                dict.set_item(
+                    "dvs_events_overflow_indices",
+                    dvs_events_overflow_indices_array,
+                )?;
+            }
+        }
+        if !trigger_events.is_empty() {
+            let trigger_events_array = {
+                let mut taken_trigger_events = Vec::new();
+                std::mem::swap(trigger_events, &mut taken_trigger_events);
+                taken_trigger_events.into_pyarray_bound(python)
+            };
+            let description = structured_array::TRIGGER_EVENTS_DTYPE.into_py(python);
+            use numpy::prelude::PyUntypedArrayMethods;
+            {
+                let trigger_events_array_pointer = trigger_events_array.as_array_ptr();
+                unsafe {
+                    *(*trigger_events_array_pointer).dimensions /=
+                        structured_array::TRIGGER_EVENTS_DTYPE.size() as isize;
+                    *(*trigger_events_array_pointer).strides =
+                        structured_array::TRIGGER_EVENTS_DTYPE.size() as isize;
+                    let previous_description = (*trigger_events_array_pointer).descr;
+                    (*trigger_events_array_pointer).descr = description;
+                    pyo3::ffi::Py_DECREF(previous_description as *mut pyo3::ffi::PyObject);
                 }
-                Ok(dict.into())
+            }
+
+            dict.set_item("trigger_events", trigger_events_array)?;
+            if !trigger_events_overflow_indices.is_empty() {
+                let trigger_events_overflow_indices_array = {
+                    let mut taken_trigger_events_overflow_indices = Vec::new();
+                    std::mem::swap(
+                        trigger_events_overflow_indices,
+                        &mut taken_trigger_events_overflow_indices,
+                    );
+                    taken_trigger_events_overflow_indices.into_pyarray_bound(python)
+                };
+                dict.set_item(
+                    "trigger_events_overflow_indices",
+                    trigger_events_overflow_indices_array,
+                )?;
             }
         }
+        Ok(dict.into())
     }
 }
 
 impl From<neuromorphic_drivers::Adapter> for Adapter {
     fn from(adapter: neuromorphic_drivers::Adapter) -> Self {
-        match adapter {
-            neuromorphic_drivers::Adapter::Evt3(inner) => Adapter::Evt3 {
-                inner,
-                dvs_events: Vec::new(),
-                trigger_events: Vec::new(),
-                dvs_events_overflow_indices: Vec::new(),
-                trigger_events_overflow_indices: Vec::new(),
-            },
+        Adapter {
+            inner: adapter,
+            dvs_events: Vec::new(),
+            trigger_events: Vec::new(),
+            dvs_events_overflow_indices: Vec::new(),
+            trigger_events_overflow_indices: Vec::new(),
+            arrow: None,
         }
     }
 }
@@ -1,5 +1,6 @@
 mod adapters;
 mod bytes;
+mod record_batch;
 mod structured_array;
 extern crate neuromorphic_drivers as neuromorphic_drivers_rs;
 use pyo3::IntoPy;
@@ -62,6 +63,17 @@ struct Status {
 #[pyo3::pymethods]
 impl Device {
     #[new]
+    #[pyo3(signature = (
+        raw,
+        iterator_maximum_raw_packets,
+        device_type,
+        configuration,
+        serial,
+        usb_configuration,
+        iterator_timeout,
+        arrow=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         raw: bool,
         iterator_maximum_raw_packets: usize,
@@ -70,6 +82,7 @@ impl Device {
         serial: Option<&str>,
         usb_configuration: Option<&[u8]>,
         iterator_timeout: Option<f64>,
+        arrow: bool,
     ) -> pyo3::PyResult<Self> {
         let (flag, event_loop) = neuromorphic_drivers_rs::flag_and_event_loop()
             .map_err(|error| pyo3::exceptions::PyRuntimeError::new_err(format!("{error}")))?;
@@ -112,6 +125,10 @@ impl Device {
         .map_err(|error| pyo3::exceptions::PyRuntimeError::new_err(format!("{error}")))?;
         let adapter = if raw {
             None
+        } else if arrow {
+            Some(std::cell::RefCell::new(
+                adapters::Adapter::from(device.adapter()).with_arrow(),
+            ))
         } else {
             Some(std::cell::RefCell::new(device.adapter().into()))
         };
@@ -405,6 +422,23 @@ impl Device {
         }
     }
 
+    fn area_counts(slf: pyo3::PyRef<Self>) -> pyo3::PyResult<Vec<u64>> {
+        match slf
+            .device
+            .as_ref()
+            .ok_or(pyo3::exceptions::PyRuntimeError::new_err(
+                "area_counts called after __exit__",
+            ))? {
+            neuromorphic_drivers_rs::Device::PropheseeEvk4(device) => {
+                Ok(device.area_counts().to_vec())
+            }
+            device => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "area_counts is not implemented for the {}",
+                device.name()
+            ))),
+        }
+    }
+
     fn update_configuration(
         slf: pyo3::PyRef<Self>,
         device_type: &str,
@@ -423,17 +457,14 @@ impl Device {
                 "__next__ called after __exit__",
             ))?
             .update_configuration(configuration)
-            .map_err(|_| {
-                pyo3::exceptions::PyRuntimeError::new_err(
-                    "update_configuration called while device is used by a different thread",
-                )
-            })
+            .map_err(|error| pyo3::exceptions::PyRuntimeError::new_err(format!("{error}")))
     }
 }
 
 #[pyo3::pymodule]
 fn neuromorphic_drivers(module: &pyo3::Bound<'_, pyo3::types::PyModule>) -> pyo3::PyResult<()> {
     module.add_class::<Device>()?;
+    module.add_class::<record_batch::RecordBatch>()?;
     module.add_function(pyo3::wrap_pyfunction!(list_devices, module)?)?;
     Ok(())
 }
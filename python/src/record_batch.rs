@@ -0,0 +1,61 @@
+/// RecordBatch wraps an Arrow record batch and implements the Arrow PyCapsule interface.
+///
+/// Libraries that support the interface (pyarrow, polars, duckdb...) can import the batch
+/// without copies, for instance with pyarrow.record_batch(batch).
+#[pyo3::pyclass(frozen)]
+pub struct RecordBatch {
+    inner: arrow::record_batch::RecordBatch,
+}
+
+impl From<arrow::record_batch::RecordBatch> for RecordBatch {
+    fn from(inner: arrow::record_batch::RecordBatch) -> Self {
+        Self { inner }
+    }
+}
+
+fn schema_capsule(
+    python: pyo3::Python,
+    schema: &arrow::datatypes::Schema,
+) -> pyo3::PyResult<pyo3::PyObject> {
+    let ffi_schema = arrow::ffi::FFI_ArrowSchema::try_from(schema)
+        .map_err(|error| pyo3::exceptions::PyRuntimeError::new_err(format!("{error}")))?;
+    Ok(pyo3::types::PyCapsule::new_bound(
+        python,
+        ffi_schema,
+        Some(std::ffi::CString::new("arrow_schema").expect("the name has no nul bytes")),
+    )?
+    .into())
+}
+
+#[pyo3::pymethods]
+impl RecordBatch {
+    fn __len__(&self) -> usize {
+        self.inner.num_rows()
+    }
+
+    fn __arrow_c_schema__(&self, python: pyo3::Python) -> pyo3::PyResult<pyo3::PyObject> {
+        schema_capsule(python, self.inner.schema_ref())
+    }
+
+    /// requested_schema is ignored, consumers must cast the batch themselves if needed.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_array__(
+        &self,
+        python: pyo3::Python,
+        requested_schema: Option<pyo3::PyObject>,
+    ) -> pyo3::PyResult<(pyo3::PyObject, pyo3::PyObject)> {
+        let _ = requested_schema;
+        let array = arrow::array::StructArray::from(self.inner.clone());
+        let (ffi_array, _) = arrow::ffi::to_ffi(&arrow::array::Array::to_data(&array))
+            .map_err(|error| pyo3::exceptions::PyRuntimeError::new_err(format!("{error}")))?;
+        Ok((
+            schema_capsule(python, self.inner.schema_ref())?,
+            pyo3::types::PyCapsule::new_bound(
+                python,
+                ffi_array,
+                Some(std::ffi::CString::new("arrow_array").expect("the name has no nul bytes")),
+            )?
+            .into(),
+        ))
+    }
+}
@@ -196,7 +196,7 @@ impl<'de, 'a> de::Deserializer<'de> for Deserializer<'de, 'a> {
         let mut format = Format::unknown();
         self.format
             .unify(Format::Option(Box::new(format.clone())))?;
-        if format.is_unknown() {
+        if format.is_unknown() || !self.tracer.partial_enums.is_empty() {
             let inner = Deserializer::new(self.tracer, self.samples, &mut format);
             visitor.visit_some(inner)
         } else {
@@ -411,11 +411,16 @@ impl<'de, 'a> de::Deserializer<'de> for Deserializer<'de, 'a> {
             _ => unreachable!(),
         };
         // If we have found all the variants OR if the enum is marked as
-        // incomplete already, pick the first index.
+        // incomplete already, pick the first variant whose format is still
+        // unknown (for instance a variant only seen during serialization),
+        // or the first index.
         let index = if known_variants.len() == variants.len()
             || self.tracer.incomplete_enums.contains(name)
         {
-            0
+            known_variants
+                .iter()
+                .find(|(_, variant)| has_unknown_format(&variant.value))
+                .map_or(0, |(index, _)| *index)
         } else {
             let mut index = known_variants.len() as u32;
             // Scan the range 0..=known_variants.len() downwards to find the next
@@ -434,8 +439,15 @@ impl<'de, 'a> de::Deserializer<'de> for Deserializer<'de, 'a> {
         });
         let mut value = variant.value.clone();
         // Mark the enum as incomplete if this was not the last variant to explore.
-        if known_variants.len() != variants.len() {
+        if known_variants.len() != variants.len()
+            || known_variants.iter().any(|(other_index, variant)| {
+                *other_index != index && has_unknown_format(&variant.value)
+            })
+        {
             self.tracer.incomplete_enums.insert(name.into());
+            self.tracer.partial_enums.insert(name.into());
+        } else {
+            self.tracer.partial_enums.remove(name);
         }
         // Compute the format for this variant.
         let inner = EnumDeserializer::new(self.tracer, self.samples, index, &mut value);
@@ -622,3 +634,7 @@ impl<'de, 'a> de::VariantAccess<'de> for EnumDeserializer<'de, 'a> {
         visitor.visit_seq(inner)
     }
 }
+
+fn has_unknown_format(variant: &VariantFormat) -> bool {
+    variant.clone().normalize().is_err()
+}
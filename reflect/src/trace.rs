@@ -29,6 +29,11 @@ pub struct Tracer {
     /// Enums that have detected to be yet incomplete (i.e. missing variants)
     /// while tracing deserialization.
     pub(crate) incomplete_enums: BTreeSet<String>,
+
+    /// Enums that are missing variants. Unlike incomplete_enums, this set is
+    /// not reset between passes, so that options containing such enums are
+    /// explored again.
+    pub(crate) partial_enums: BTreeSet<String>,
 }
 
 /// User inputs, aka "samples", recorded during serialization.
@@ -110,6 +115,7 @@ impl Tracer {
             config,
             registry: BTreeMap::new(),
             incomplete_enums: BTreeSet::new(),
+            partial_enums: BTreeSet::new(),
         }
     }
 